@@ -0,0 +1,339 @@
+use ark_bls12_381::{Bls12_381, Fr, G1Projective};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{test_rng, PrimeField, UniformRand, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial,
+    UVPolynomial,
+};
+use ark_poly_commit::kzg10::{
+    self, Powers, PreparedCommitment, PreparedVerifierKey, Proof, Randomness, UniversalParams,
+    VerifierKey, KZG10,
+};
+use ark_poly_commit::PCRandomness;
+use ark_std::borrow::Cow;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+type UniPoly = DensePolynomial<Fr>;
+type KZG = KZG10<Bls12_381, UniPoly>;
+
+const MAX_DEGREE: usize = 1 << 10;
+/// The degrees at which `bench_poly_open` and `bench_poly_check` run.
+const OPEN_CHECK_DEGREES: [usize; 4] = [1 << 4, 1 << 6, 1 << 8, 1 << 10];
+
+/// Whether to run `bench_poly_open`.
+const ENABLE_OPEN_BENCH: bool = true;
+/// Whether to run `bench_poly_check`.
+const ENABLE_CHECK_BENCH: bool = true;
+
+fn trim(pp: &UniversalParams<Bls12_381>, supported_degree: usize) -> Powers<Bls12_381> {
+    let powers_of_gamma_g = (0..=supported_degree)
+        .map(|i| pp.powers_of_gamma_g[&i])
+        .collect();
+    Powers {
+        powers_of_g: Cow::Borrowed(&pp.powers_of_g[..=supported_degree]),
+        powers_of_gamma_g: Cow::Owned(powers_of_gamma_g),
+        parallel_threshold: kzg10::DEFAULT_PARALLEL_THRESHOLD,
+    }
+}
+
+fn verifier_key(pp: &UniversalParams<Bls12_381>) -> VerifierKey<Bls12_381> {
+    VerifierKey {
+        g: pp.powers_of_g[0],
+        gamma_g: pp.powers_of_gamma_g[&0],
+        h: pp.h,
+        beta_h: pp.beta_h,
+        prepared_h: pp.prepared_h.clone(),
+        prepared_beta_h: pp.prepared_beta_h.clone(),
+    }
+}
+
+/// Outputs a non-hiding proof for `p` at `point`.
+fn open_proof(powers: &Powers<Bls12_381>, p: &UniPoly, point: Fr) -> Proof<Bls12_381> {
+    KZG::open(powers, p, point, &Randomness::empty()).unwrap()
+}
+
+fn commit_shifted_by(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let pp = KZG::setup(MAX_DEGREE, false, rng).unwrap();
+    let powers = trim(&pp, MAX_DEGREE);
+
+    let mut group = c.benchmark_group("KZG10::commit_shifted_by");
+    for &degree in &[1 << 6, 1 << 8] {
+        let p = UniPoly::rand(degree, rng);
+        let k = MAX_DEGREE - degree;
+        group.bench_with_input(BenchmarkId::new("aligned", degree), &p, |b, p| {
+            b.iter(|| KZG::commit_shifted_by(&powers, p, k).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("explicit", degree), &p, |b, p| {
+            b.iter(|| {
+                let mut shifted_coeffs = vec![Fr::zero(); k];
+                shifted_coeffs.extend_from_slice(&p.coeffs);
+                let shifted = UniPoly::from_coefficients_vec(shifted_coeffs);
+                KZG::commit(&powers, &shifted, None, None).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn commit_constant(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let pp = KZG::setup(1, false, rng).unwrap();
+    let powers = trim(&pp, 1);
+    let p = UniPoly::from_coefficients_vec(vec![Fr::rand(rng)]);
+
+    let mut group = c.benchmark_group("KZG10::commit constant");
+    group.bench_function("scalar mul", |b| {
+        b.iter(|| KZG::commit(&powers, &p, None, None).unwrap())
+    });
+    group.bench_function("msm", |b| {
+        b.iter(|| KZG::commit_shifted_by(&powers, &p, 0).unwrap())
+    });
+    group.finish();
+}
+
+fn check_fully_prepared(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let degree = 1 << 6;
+    let pp = KZG::setup(degree, false, rng).unwrap();
+    let powers = trim(&pp, degree);
+    let vk = verifier_key(&pp);
+    let prepared_vk = PreparedVerifierKey::prepare(&vk);
+
+    let p = UniPoly::rand(degree, rng);
+    let (comm, _) = KZG::commit(&powers, &p, None, None).unwrap();
+    let prepared_comm = PreparedCommitment::prepare(&comm);
+    let point = Fr::rand(rng);
+    let value = p.evaluate(&point);
+    let proof = open_proof(&powers, &p, point);
+
+    // Each iteration is one of many repeated checks against the same key and
+    // commitment.
+    let mut group = c.benchmark_group("KZG10::check repeated");
+    group.bench_function("check", |b| {
+        b.iter(|| assert!(KZG::check(&vk, &comm, point, value, &proof).unwrap()))
+    });
+    group.bench_function("check_fully_prepared", |b| {
+        b.iter(|| {
+            assert!(
+                KZG::check_fully_prepared(&prepared_vk, &prepared_comm, point, value, &proof)
+                    .unwrap()
+            )
+        })
+    });
+    group.finish();
+}
+
+fn commit_from_reprs(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let pp = KZG::setup(MAX_DEGREE, false, rng).unwrap();
+    let powers = trim(&pp, MAX_DEGREE);
+    let p = UniPoly::rand(MAX_DEGREE, rng);
+    let reprs = p.coeffs.iter().map(|c| c.into_repr()).collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("KZG10::commit_from_reprs");
+    group.bench_function("reprs", |b| {
+        b.iter(|| KZG::commit_from_reprs(&powers, &reprs).unwrap())
+    });
+    group.bench_function("field elements", |b| {
+        b.iter(|| KZG::commit(&powers, &p, None, None).unwrap())
+    });
+    group.finish();
+}
+
+fn commit_parallel_threshold(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let pp = KZG::setup(MAX_DEGREE, false, rng).unwrap();
+    let powers = trim(&pp, MAX_DEGREE);
+
+    let mut group = c.benchmark_group("KZG10::commit parallel threshold");
+    for &degree in &[1 << 2, 1 << 4, 1 << 6, 1 << 8, 1 << 10] {
+        let p = UniPoly::rand(degree, rng);
+        for &(name, threshold) in &[
+            ("always parallel", 0),
+            ("default", kzg10::DEFAULT_PARALLEL_THRESHOLD),
+        ] {
+            let mut powers = powers.clone();
+            powers.parallel_threshold = threshold;
+            group.bench_with_input(BenchmarkId::new(name, degree), &p, |b, p| {
+                b.iter(|| KZG::commit(&powers, p, None, None).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+fn batch_check(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let degree = 1 << 6;
+    let pp = KZG::setup(degree, false, rng).unwrap();
+    let powers = trim(&pp, degree);
+    let vk = verifier_key(&pp);
+
+    let mut group = c.benchmark_group("KZG10::batch_check");
+    for &num_proofs in &[1, 4, 16, 64] {
+        let mut comms = Vec::new();
+        let mut points = Vec::new();
+        let mut values = Vec::new();
+        let mut proofs = Vec::new();
+        for _ in 0..num_proofs {
+            let p = UniPoly::rand(degree, rng);
+            let (comm, _) = KZG::commit(&powers, &p, None, None).unwrap();
+            let point = Fr::rand(rng);
+            comms.push(comm);
+            values.push(p.evaluate(&point));
+            proofs.push(open_proof(&powers, &p, point));
+            points.push(point);
+        }
+
+        group.bench_function(BenchmarkId::new("individual checks", num_proofs), |b| {
+            b.iter(|| {
+                for i in 0..num_proofs {
+                    assert!(KZG::check(&vk, &comms[i], points[i], values[i], &proofs[i]).unwrap());
+                }
+            })
+        });
+        group.bench_function(BenchmarkId::new("batch_check", num_proofs), |b| {
+            b.iter(|| {
+                assert!(
+                    KZG::batch_check(&vk, &comms, &points, &values, &proofs, &mut test_rng())
+                        .unwrap()
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+fn prepare_commitments(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let comms = (0..1024)
+        .map(|_| kzg10::Commitment::<Bls12_381>(G1Projective::rand(rng).into_affine()))
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("PreparedCommitment::prepare 1024 commitments");
+    group.sample_size(10);
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            comms
+                .iter()
+                .map(PreparedCommitment::prepare)
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("prepare_batch", |b| {
+        b.iter(|| PreparedCommitment::prepare_batch(&comms))
+    });
+    group.finish();
+}
+
+fn commit_msm_vs_naive(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let degree = 1 << 16;
+    let pp = KZG::setup(degree, false, rng).unwrap();
+    let powers = trim(&pp, degree);
+    let p = UniPoly::rand(degree, rng);
+
+    let mut group = c.benchmark_group("KZG10::commit degree 2^16");
+    group.sample_size(10);
+    group.bench_function("msm", |b| {
+        b.iter(|| KZG::commit(&powers, &p, None, None).unwrap())
+    });
+    group.bench_function("naive", |b| {
+        b.iter(|| {
+            p.coeffs
+                .iter()
+                .zip(powers.powers_of_g.iter())
+                .fold(G1Projective::zero(), |acc, (c, g)| acc + &g.mul(*c))
+                .into_affine()
+        })
+    });
+    group.finish();
+}
+
+fn bench_poly_open(c: &mut Criterion) {
+    if !ENABLE_OPEN_BENCH {
+        return;
+    }
+    let rng = &mut test_rng();
+    let pp = KZG::setup(MAX_DEGREE, false, rng).unwrap();
+    let powers = trim(&pp, MAX_DEGREE);
+
+    let mut group = c.benchmark_group("KZG10::open");
+    for &degree in &OPEN_CHECK_DEGREES {
+        let p = UniPoly::rand(degree, rng);
+        let point = Fr::rand(rng);
+        group.bench_with_input(BenchmarkId::from_parameter(degree), &p, |b, p| {
+            b.iter(|| open_proof(&powers, p, point))
+        });
+    }
+    group.finish();
+}
+
+fn bench_poly_check(c: &mut Criterion) {
+    if !ENABLE_CHECK_BENCH {
+        return;
+    }
+    let rng = &mut test_rng();
+    let pp = KZG::setup(MAX_DEGREE, false, rng).unwrap();
+    let powers = trim(&pp, MAX_DEGREE);
+    let vk = verifier_key(&pp);
+
+    // The cost of a check does not depend on the degree; the degrees are
+    // benchmarked to catch regressions that would make it so.
+    let mut group = c.benchmark_group("KZG10::check");
+    for &degree in &OPEN_CHECK_DEGREES {
+        let p = UniPoly::rand(degree, rng);
+        let (comm, _) = KZG::commit(&powers, &p, None, None).unwrap();
+        let point = Fr::rand(rng);
+        let value = p.evaluate(&point);
+        let proof = open_proof(&powers, &p, point);
+        group.bench_function(BenchmarkId::from_parameter(degree), |b| {
+            b.iter(|| assert!(KZG::check(&vk, &comm, point, value, &proof).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+fn open_at_domain_point(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let pp = KZG::setup(MAX_DEGREE, false, rng).unwrap();
+    let powers = trim(&pp, MAX_DEGREE);
+
+    let mut group = c.benchmark_group("KZG10::open at a domain point");
+    for &size in &[1 << 6, 1 << 8, 1 << 10] {
+        let domain = GeneralEvaluationDomain::<Fr>::new(size).unwrap();
+        let p = UniPoly::rand(size - 1, rng);
+        let index = size / 3;
+        group.bench_with_input(BenchmarkId::new("open", size), &p, |b, p| {
+            b.iter(|| open_proof(&powers, p, domain.element(index)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("open_at_domain_point", size),
+            &p,
+            |b, p| {
+                b.iter(|| {
+                    KZG::open_at_domain_point(&powers, p, &domain, index, &Randomness::empty())
+                        .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    commit_shifted_by,
+    commit_constant,
+    check_fully_prepared,
+    commit_from_reprs,
+    commit_parallel_threshold,
+    batch_check,
+    prepare_commitments,
+    commit_msm_vs_naive,
+    bench_poly_open,
+    bench_poly_check,
+    open_at_domain_point
+);
+criterion_main!(benches);
@@ -0,0 +1,74 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::{test_rng, UniformRand};
+use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+use ark_poly_commit::{marlin_pc::MarlinKZG10, LabeledPolynomial, PolynomialCommitment};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+type UniPoly = DensePolynomial<Fr>;
+type PC = MarlinKZG10<Bls12_381, UniPoly>;
+
+const MAX_DEGREE: usize = 1 << 10;
+const DEGREES: [usize; 3] = [1 << 6, 1 << 8, 1 << 10];
+
+fn labeled_polynomials(degree: usize) -> Vec<(&'static str, LabeledPolynomial<Fr, UniPoly>)> {
+    let p = UniPoly::rand(degree, &mut test_rng());
+    vec![
+        (
+            "no degree bound",
+            LabeledPolynomial::new("p".to_string(), p.clone(), None, None),
+        ),
+        (
+            "degree bound",
+            LabeledPolynomial::new("p".to_string(), p, Some(degree), None),
+        ),
+    ]
+}
+
+fn commit_with_degree_bound(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let pp = PC::setup(MAX_DEGREE, None, rng).unwrap();
+    let (ck, _) = PC::trim(&pp, MAX_DEGREE, 0, Some(&DEGREES)).unwrap();
+
+    let mut group = c.benchmark_group("MarlinKZG10::commit degree bound");
+    for &degree in &DEGREES {
+        for (name, p) in labeled_polynomials(degree) {
+            group.bench_with_input(BenchmarkId::new(name, degree), &p, |b, p| {
+                b.iter(|| PC::commit(&ck, Some(p), None).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+fn open_with_degree_bound(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let pp = PC::setup(MAX_DEGREE, None, rng).unwrap();
+    let (ck, _) = PC::trim(&pp, MAX_DEGREE, 0, Some(&DEGREES)).unwrap();
+    let point = Fr::rand(rng);
+    let opening_challenge = Fr::rand(rng);
+
+    let mut group = c.benchmark_group("MarlinKZG10::open degree bound");
+    for &degree in &DEGREES {
+        for (name, p) in labeled_polynomials(degree) {
+            let (comms, rands) = PC::commit(&ck, Some(&p), None).unwrap();
+            group.bench_with_input(BenchmarkId::new(name, degree), &p, |b, p| {
+                b.iter(|| {
+                    PC::open(
+                        &ck,
+                        Some(p),
+                        &comms,
+                        &point,
+                        opening_challenge,
+                        &rands,
+                        None,
+                    )
+                    .unwrap()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, commit_with_degree_bound, open_with_degree_bound);
+criterion_main!(benches);
@@ -0,0 +1,108 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::{test_rng, UniformRand};
+use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+use ark_poly_commit::kzg10::{Powers, Scratch, UniversalParams, DEFAULT_PARALLEL_THRESHOLD, KZG10};
+use ark_std::borrow::Cow;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+type UniPoly = DensePolynomial<Fr>;
+type KZG = KZG10<Bls12_381, UniPoly>;
+
+const MAX_DEGREE: usize = 1 << 10;
+
+fn trim(pp: &UniversalParams<Bls12_381>, supported_degree: usize) -> Powers<Bls12_381> {
+    let powers_of_gamma_g = (0..=supported_degree)
+        .map(|i| pp.powers_of_gamma_g[&i])
+        .collect();
+    Powers {
+        powers_of_g: Cow::Borrowed(&pp.powers_of_g[..=supported_degree]),
+        powers_of_gamma_g: Cow::Owned(powers_of_gamma_g),
+        parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+    }
+}
+
+/// Counts the heap allocations made by the benchmarks, so that the allocating
+/// and scratch paths can be compared.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Returns the number of heap allocations made while running `f`.
+fn count_allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+    drop(result);
+    after - before
+}
+
+/// Compares committing and opening with fresh buffers on every call, with
+/// `commit` and `open`, against reusing one `Scratch` across calls, and
+/// reports the number of allocations per call of each.
+fn commit_and_open(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let pp = KZG::setup(MAX_DEGREE, false, rng).unwrap();
+    let mut powers = trim(&pp, MAX_DEGREE);
+    // Stay on the current thread, so that the thread pool does not allocate
+    // on behalf of the calls being counted.
+    powers.parallel_threshold = usize::MAX;
+    let mut scratch = Scratch::new();
+
+    let mut group = c.benchmark_group("KZG10::commit_and_open");
+    for &degree in &[1 << 6, 1 << 10] {
+        let p = UniPoly::rand(degree, rng);
+        let point = Fr::rand(rng);
+
+        let allocating = || {
+            let (_, rand) = KZG::commit(&powers, &p, None, None).unwrap();
+            KZG::open(&powers, &p, point, &rand).unwrap()
+        };
+        // Warm up `scratch`, so that only the steady state is counted.
+        let (_, rand) = KZG::commit_with_scratch(&powers, &p, None, None, &mut scratch).unwrap();
+        KZG::open_with_scratch(&powers, &p, point, &rand, &mut scratch).unwrap();
+        println!(
+            "degree {}: {} allocations with commit and open, {} with scratch",
+            degree,
+            count_allocations(allocating),
+            count_allocations(|| {
+                let (_, rand) =
+                    KZG::commit_with_scratch(&powers, &p, None, None, &mut scratch).unwrap();
+                KZG::open_with_scratch(&powers, &p, point, &rand, &mut scratch).unwrap()
+            }),
+        );
+
+        group.bench_with_input(BenchmarkId::new("allocating", degree), &p, |b, p| {
+            b.iter(|| {
+                let (_, rand) = KZG::commit(&powers, p, None, None).unwrap();
+                KZG::open(&powers, p, point, &rand).unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("scratch", degree), &p, |b, p| {
+            b.iter(|| {
+                let (_, rand) =
+                    KZG::commit_with_scratch(&powers, p, None, None, &mut scratch).unwrap();
+                KZG::open_with_scratch(&powers, p, point, &rand, &mut scratch).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, commit_and_open);
+criterion_main!(benches);
@@ -1,15 +1,57 @@
-use crate::{Polynomial, Rc, String, Vec};
+use crate::{BTreeMap, BTreeSet, Error, Polynomial, QuerySet, Rc, String, UVPolynomial, Vec};
 use ark_ff::Field;
 use ark_std::{
     borrow::Borrow,
+    iter::FromIterator,
     marker::PhantomData,
-    ops::{AddAssign, MulAssign, SubAssign},
+    ops::{AddAssign, MulAssign, Neg, Sub, SubAssign},
+    vec,
 };
 use rand_core::RngCore;
 
 /// Labels a `LabeledPolynomial` or a `LabeledCommitment`.
 pub type PolynomialLabel = String;
 
+/// Helpers for building a `QuerySet` and for iterating over it point by point.
+pub trait QuerySetExt<T> {
+    /// Adds the query of the polynomial `label` at `point`, labeled
+    /// `point_label`. Returns `false` if the query was already present.
+    fn insert_point(
+        &mut self,
+        label: impl Into<PolynomialLabel>,
+        point_label: impl Into<String>,
+        point: T,
+    ) -> bool;
+
+    /// Groups the queries by point label, mapping each point label to its
+    /// point and the labels of the polynomials queried there. Both levels are
+    /// sorted, matching the order in which the batch opening and checking
+    /// methods of `PolynomialCommitment` fold queries.
+    fn group_by_point(&self) -> BTreeMap<&String, (&T, BTreeSet<&PolynomialLabel>)>;
+}
+
+impl<T: Ord> QuerySetExt<T> for QuerySet<T> {
+    fn insert_point(
+        &mut self,
+        label: impl Into<PolynomialLabel>,
+        point_label: impl Into<String>,
+        point: T,
+    ) -> bool {
+        self.insert((label.into(), (point_label.into(), point)))
+    }
+
+    fn group_by_point(&self) -> BTreeMap<&String, (&T, BTreeSet<&PolynomialLabel>)> {
+        let mut query_to_labels_map = BTreeMap::new();
+        for (label, (point_label, point)) in self.iter() {
+            let labels = query_to_labels_map
+                .entry(point_label)
+                .or_insert((point, BTreeSet::new()));
+            labels.1.insert(label);
+        }
+        query_to_labels_map
+    }
+}
+
 /// Defines the minimal interface for public params for any polynomial
 /// commitment scheme.
 pub trait PCUniversalParams: Clone + core::fmt::Debug {
@@ -52,6 +94,16 @@ pub trait PCCommitment: Clone + ark_ff::ToBytes {
     /// Outputs a non-hiding commitment to the zero polynomial.
     fn empty() -> Self;
 
+    /// Is this a non-hiding commitment to the zero polynomial, i.e., are all
+    /// of its group elements zero? The default implementation compares the
+    /// encoding of `self` with that of `Self::empty()`.
+    fn is_zero(&self) -> bool {
+        match (ark_ff::to_bytes![self], ark_ff::to_bytes![Self::empty()]) {
+            (Ok(bytes), Ok(empty_bytes)) => bytes == empty_bytes,
+            _ => false,
+        }
+    }
+
     /// Does this commitment have a degree bound?
     fn has_degree_bound(&self) -> bool;
 
@@ -92,6 +144,17 @@ pub trait PCProof: Clone + ark_ff::ToBytes {
     fn size_in_bytes(&self) -> usize;
 }
 
+/// Lossless lowercase hex encodings of commitments and proofs, for logging and
+/// debugging.
+pub trait HexCodec: Sized {
+    /// Encodes `self` as a lowercase hex string.
+    fn to_hex(&self) -> String;
+
+    /// Decodes a hex string, in either case, produced by `to_hex`. Fails with
+    /// `Error::MalformedHex` if `hex` does not encode a valid value.
+    fn from_hex(hex: &str) -> Result<Self, Error>;
+}
+
 /// A polynomial along with information about its degree bound (if any), and the
 /// maximum number of queries that will be made to it. This latter number determines
 /// the amount of protection that will be provided to a commitment for this polynomial.
@@ -149,6 +212,11 @@ impl<'a, F: Field, P: Polynomial<F>> LabeledPolynomial<F, P> {
         self.polynomial.degree()
     }
 
+    /// Is the polynomial in `self` zero?
+    pub fn is_zero(&self) -> bool {
+        self.polynomial.is_zero()
+    }
+
     /// Retrieve the degree bound in `self`.
     pub fn degree_bound(&self) -> Option<usize> {
         self.degree_bound
@@ -163,6 +231,67 @@ impl<'a, F: Field, P: Polynomial<F>> LabeledPolynomial<F, P> {
     pub fn hiding_bound(&self) -> Option<usize> {
         self.hiding_bound
     }
+
+    /// Returns `self` with its label replaced by `label`. The polynomial is
+    /// not cloned.
+    pub fn with_label(self, label: PolynomialLabel) -> Self {
+        Self { label, ..self }
+    }
+}
+
+impl<F: Field, P: UVPolynomial<F>> LabeledPolynomial<F, P> {
+    /// Construct a labeled polynomial from its coefficients, in order of
+    /// increasing degree, with its degree bound set to its degree.
+    ///
+    /// ```
+    /// use ark_bls12_381::Fr;
+    /// use ark_ff::{One, Zero};
+    /// use ark_poly::univariate::DensePolynomial;
+    /// use ark_poly_commit::LabeledPolynomial;
+    ///
+    /// let coeffs = vec![Fr::one(), Fr::zero(), Fr::one(), Fr::zero()];
+    /// let p = LabeledPolynomial::<Fr, DensePolynomial<Fr>>::from_coeffs("p", coeffs, Some(1));
+    /// assert_eq!(p.degree(), 2);
+    /// assert_eq!(p.degree_bound(), Some(p.degree()));
+    /// assert_eq!(p.hiding_bound(), Some(1));
+    /// ```
+    pub fn from_coeffs(
+        label: impl Into<PolynomialLabel>,
+        coeffs: Vec<F>,
+        hiding_bound: Option<usize>,
+    ) -> Self {
+        let polynomial = P::from_coefficients_vec(coeffs);
+        let degree_bound = Some(polynomial.degree());
+        Self::new(label.into(), polynomial, degree_bound, hiding_bound)
+    }
+}
+
+impl<F: Field, P: Polynomial<F>> From<LabeledPolynomial<F, P>>
+    for (PolynomialLabel, P, Option<usize>, Option<usize>)
+{
+    /// Splits a labeled polynomial into its label, polynomial, degree bound and
+    /// hiding bound. The polynomial is cloned only if it is shared with
+    /// another `LabeledPolynomial`.
+    fn from(p: LabeledPolynomial<F, P>) -> Self {
+        let polynomial = Rc::try_unwrap(p.polynomial).unwrap_or_else(|p| (*p).clone());
+        (p.label, polynomial, p.degree_bound, p.hiding_bound)
+    }
+}
+
+impl<F: Field, P: Polynomial<F>> From<(PolynomialLabel, P, Option<usize>, Option<usize>)>
+    for LabeledPolynomial<F, P>
+{
+    /// Equivalent to `LabeledPolynomial::new`.
+    fn from(
+        (label, polynomial, degree_bound, hiding_bound): (
+            PolynomialLabel,
+            P,
+            Option<usize>,
+            Option<usize>,
+        ),
+    ) -> Self {
+        Self::new(label, polynomial, degree_bound, hiding_bound)
+    }
 }
 
 /// A commitment along with information about its degree bound (if any).
@@ -197,6 +326,17 @@ impl<C: PCCommitment> LabeledCommitment<C> {
     pub fn degree_bound(&self) -> Option<usize> {
         self.degree_bound
     }
+
+    /// Is the labeled commitment a non-hiding commitment to the zero
+    /// polynomial? See `PCCommitment::is_zero`.
+    pub fn is_zero(&self) -> bool {
+        self.commitment.is_zero()
+    }
+
+    /// Returns `self` with its label replaced by `label`.
+    pub fn with_label(self, label: PolynomialLabel) -> Self {
+        Self { label, ..self }
+    }
 }
 
 impl<C: PCCommitment> ark_ff::ToBytes for LabeledCommitment<C> {
@@ -206,6 +346,55 @@ impl<C: PCCommitment> ark_ff::ToBytes for LabeledCommitment<C> {
     }
 }
 
+/// An index of `LabeledCommitment`s by label.
+#[derive(Clone)]
+pub struct CommitmentMap<'a, C: PCCommitment> {
+    commitments: BTreeMap<PolynomialLabel, &'a LabeledCommitment<C>>,
+}
+
+impl<'a, C: PCCommitment> CommitmentMap<'a, C> {
+    /// Indexes `commitments` by label. If several commitments share a label,
+    /// the last one is kept.
+    pub fn new(commitments: impl IntoIterator<Item = &'a LabeledCommitment<C>>) -> Self {
+        let commitments = commitments
+            .into_iter()
+            .map(|c| (c.label().clone(), c))
+            .collect();
+        Self { commitments }
+    }
+
+    /// Returns the commitment labeled `label`, if any.
+    pub fn get(&self, label: &str) -> Option<&'a LabeledCommitment<C>> {
+        self.commitments.get(label).copied()
+    }
+
+    /// Returns the number of commitments in `self`.
+    pub fn len(&self) -> usize {
+        self.commitments.len()
+    }
+
+    /// Returns `true` if `self` contains no commitments.
+    pub fn is_empty(&self) -> bool {
+        self.commitments.is_empty()
+    }
+
+    /// Pairs each query in `query_set` with the commitment it refers to. The
+    /// iterator yields `Error::MissingPolynomial` for a query whose label has
+    /// no commitment.
+    pub fn iter_for_query_set<'q, T>(
+        &'q self,
+        query_set: &'q QuerySet<T>,
+    ) -> impl Iterator<Item = Result<(&'a LabeledCommitment<C>, &'q (String, T)), Error>> + 'q {
+        query_set.iter().map(move |(label, query)| {
+            self.get(label)
+                .map(|c| (c, query))
+                .ok_or(Error::MissingPolynomial {
+                    label: label.clone(),
+                })
+        })
+    }
+}
+
 /// A term in a linear combination.
 #[derive(Hash, Ord, PartialOrd, Clone, Eq, PartialEq, Debug)]
 pub enum LCTerm {
@@ -227,6 +416,15 @@ impl LCTerm {
     }
 }
 
+impl core::fmt::Display for LCTerm {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LCTerm::One => write!(f, "1"),
+            LCTerm::PolyLabel(label) => write!(f, "{}", label),
+        }
+    }
+}
+
 impl From<PolynomialLabel> for LCTerm {
     fn from(other: PolynomialLabel) -> Self {
         Self::PolyLabel(other)
@@ -297,6 +495,52 @@ impl<F: Field> LinearCombination<F> {
         }
     }
 
+    /// Construct a new labeled linear combination equal to the sum of the
+    /// terms in `added`, minus the sum of the terms in `subtracted`.
+    pub fn from_terms(
+        label: impl Into<String>,
+        added: Vec<(F, impl Into<LCTerm>)>,
+        subtracted: Vec<(F, impl Into<LCTerm>)>,
+    ) -> Self {
+        let mut lc = Self::new(label, added);
+        lc.terms
+            .extend(subtracted.into_iter().map(|(c, t)| (-c, t.into())));
+        lc
+    }
+
+    /// Starts building a labeled linear combination term by term.
+    ///
+    /// ```
+    /// use ark_bls12_381::Fr;
+    /// use ark_ff::One;
+    /// use ark_poly_commit::{LCTerm, LinearCombination};
+    ///
+    /// let two = Fr::one() + Fr::one();
+    /// let built = LinearCombination::builder("lc")
+    ///     .add(Fr::one(), "poly_a")
+    ///     .add(two, "poly_b")
+    ///     .add_constant(-two)
+    ///     .build();
+    /// let expected = LinearCombination::new(
+    ///     "lc",
+    ///     vec![
+    ///         (Fr::one(), LCTerm::from("poly_a")),
+    ///         (two, LCTerm::from("poly_b")),
+    ///         (-two, LCTerm::One),
+    ///     ],
+    /// );
+    /// assert_eq!(built.label(), expected.label());
+    /// assert_eq!(built.terms, expected.terms);
+    ///
+    /// let empty = LinearCombination::<Fr>::builder("empty").build();
+    /// assert!(empty.is_empty());
+    /// ```
+    pub fn builder(label: impl Into<String>) -> LinearCombinationBuilder<F> {
+        LinearCombinationBuilder {
+            lc: Self::empty(label),
+        }
+    }
+
     /// Returns the label of the linear combination.
     pub fn label(&self) -> &String {
         &self.label
@@ -307,11 +551,155 @@ impl<F: Field> LinearCombination<F> {
         self.terms.is_empty()
     }
 
+    /// Returns the sum of the coefficients of the terms equal to `term`, or
+    /// zero if `term` does not appear in `self`.
+    pub fn coefficient_of(&self, term: &LCTerm) -> F {
+        self.terms
+            .iter()
+            .filter(|(_, t)| t == term)
+            .map(|(c, _)| *c)
+            .sum()
+    }
+
+    /// Returns `true` if some term of `self` is equal to `term`.
+    pub fn contains_term(&self, term: &LCTerm) -> bool {
+        self.terms.iter().any(|(_, t)| t == term)
+    }
+
     /// Add a term to the linear combination.
     pub fn push(&mut self, term: (F, LCTerm)) -> &mut Self {
         self.terms.push(term);
         self
     }
+
+    /// Converts `self` into a dense coefficient vector whose `i`-th entry is
+    /// the sum of the coefficients of `label_order[i]`, along with the sum of
+    /// the coefficients of the constant terms.
+    ///
+    /// Returns the offending term if `self` refers to a label that does not
+    /// appear in `label_order`.
+    pub fn to_dense(&self, label_order: &[PolynomialLabel]) -> Result<(Vec<F>, F), LCTerm> {
+        let indices: BTreeMap<_, _> = label_order
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label, i))
+            .collect();
+        let mut coeffs = vec![F::zero(); label_order.len()];
+        let mut constant = F::zero();
+        for (coeff, term) in self.terms.iter() {
+            match term {
+                LCTerm::One => constant += coeff,
+                LCTerm::PolyLabel(label) => {
+                    let i = indices.get(label).ok_or_else(|| term.clone())?;
+                    coeffs[*i] += coeff;
+                }
+            }
+        }
+        Ok((coeffs, constant))
+    }
+
+    /// Evaluates `self` given the evaluations `evals` of the polynomials it
+    /// refers to, i.e. computes `\sum_i coeff_i value_i`, where constant terms
+    /// have value one. Fails with `Error::MissingEvaluation` if the value of a
+    /// referenced polynomial is missing from `evals`.
+    pub fn evaluate(&self, evals: &BTreeMap<PolynomialLabel, F>) -> Result<F, Error> {
+        let mut result = F::zero();
+        for (coeff, term) in self.terms.iter() {
+            match term {
+                LCTerm::One => result += coeff,
+                LCTerm::PolyLabel(label) => {
+                    let value = evals.get(label).ok_or_else(|| Error::MissingEvaluation {
+                        label: label.clone(),
+                    })?;
+                    result += &(*coeff * value);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Merges the terms of `self` that refer to the same `LCTerm` by summing
+    /// their coefficients, and drops the terms whose coefficient is zero. The
+    /// remaining terms are sorted by `LCTerm`, with the constant term first.
+    pub fn simplify(&mut self) {
+        let mut coeffs = BTreeMap::new();
+        for (coeff, term) in self.terms.drain(..) {
+            *coeffs.entry(term).or_insert_with(F::zero) += &coeff;
+        }
+        self.terms = coeffs
+            .into_iter()
+            .filter(|(_, coeff)| !coeff.is_zero())
+            .map(|(term, coeff)| (coeff, term))
+            .collect();
+    }
+
+    /// Replaces the label of `self` and the label of every polynomial term
+    /// with its image under `f`, e.g., to prefix them with the name of a
+    /// sub-protocol. Constant terms are left untouched.
+    pub fn rename_labels(&mut self, f: impl Fn(&str) -> String) {
+        self.label = f(&self.label);
+        for (_, term) in self.terms.iter_mut() {
+            if let LCTerm::PolyLabel(label) = term {
+                *label = f(label);
+            }
+        }
+    }
+}
+
+/// Computes the commitment to the linear combination `lc` of the committed
+/// polynomials in `commitments`, i.e. `\sum_i coeff_i C_i`, where the constant
+/// term is committed to as a multiple of `g`. The degree bounds of the
+/// commitments are ignored. Fails with `Error::MissingPolynomial` if `lc`
+/// refers to a label that is not in `commitments`.
+pub fn evaluate_linear_combination_commitment<F, C>(
+    commitments: &BTreeMap<PolynomialLabel, LabeledCommitment<C>>,
+    lc: &LinearCombination<F>,
+    g: &C,
+) -> Result<C, Error>
+where
+    F: Field,
+    C: PCCommitment + for<'a> AddAssign<(F, &'a C)>,
+{
+    let mut result = C::empty();
+    for (coeff, term) in lc.terms.iter() {
+        let comm = match term {
+            LCTerm::One => g,
+            LCTerm::PolyLabel(label) => commitments
+                .get(label)
+                .ok_or_else(|| Error::MissingPolynomial {
+                    label: label.clone(),
+                })?
+                .commitment(),
+        };
+        result += (*coeff, comm);
+    }
+    Ok(result)
+}
+
+/// Builds a `LinearCombination` with chained calls; see
+/// `LinearCombination::builder`.
+#[derive(Clone, Debug)]
+pub struct LinearCombinationBuilder<F> {
+    lc: LinearCombination<F>,
+}
+
+impl<F: Field> LinearCombinationBuilder<F> {
+    /// Adds the term `coeff * term`.
+    pub fn add(mut self, coeff: F, term: impl Into<LCTerm>) -> Self {
+        self.lc.terms.push((coeff, term.into()));
+        self
+    }
+
+    /// Adds the constant term `coeff`, i.e. `(coeff, LCTerm::One)`.
+    pub fn add_constant(mut self, coeff: F) -> Self {
+        self.lc.terms.push((coeff, LCTerm::One));
+        self
+    }
+
+    /// Outputs the linear combination built so far.
+    pub fn build(self) -> LinearCombination<F> {
+        self.lc
+    }
 }
 
 impl<'a, F: Field> AddAssign<(F, &'a LinearCombination<F>)> for LinearCombination<F> {
@@ -341,6 +729,24 @@ impl<'a, F: Field> SubAssign<&'a LinearCombination<F>> for LinearCombination<F>
     }
 }
 
+impl<'a, F: Field> Sub<&'a LinearCombination<F>> for LinearCombination<F> {
+    type Output = Self;
+
+    fn sub(mut self, other: &'a LinearCombination<F>) -> Self {
+        self -= other;
+        self
+    }
+}
+
+impl<F: Field> Neg for LinearCombination<F> {
+    type Output = Self;
+
+    fn neg(mut self) -> Self {
+        self.terms.iter_mut().for_each(|(c, _)| *c = -*c);
+        self
+    }
+}
+
 impl<F: Field> AddAssign<F> for LinearCombination<F> {
     fn add_assign(&mut self, coeff: F) {
         self.terms.push((coeff, LCTerm::One));
@@ -359,6 +765,40 @@ impl<F: Field> MulAssign<F> for LinearCombination<F> {
     }
 }
 
+impl<F: Field> core::fmt::Display for LinearCombination<F> {
+    /// Formats `self` as `label = c0*t0 + c1*t1 + ...`, or `label = 0` if
+    /// `self` has no terms.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} = ", self.label)?;
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+        for (i, (coeff, term)) in self.terms.iter().enumerate() {
+            if i > 0 {
+                write!(f, " + ")?;
+            }
+            write!(f, "{}*{}", coeff, term)?;
+        }
+        Ok(())
+    }
+}
+
+impl<F: Field> FromIterator<(F, LCTerm)> for LinearCombination<F> {
+    /// Collects the terms into a linear combination with an empty label.
+    fn from_iter<I: IntoIterator<Item = (F, LCTerm)>>(iter: I) -> Self {
+        Self {
+            label: String::new(),
+            terms: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<F: Field> Extend<(F, LCTerm)> for LinearCombination<F> {
+    fn extend<I: IntoIterator<Item = (F, LCTerm)>>(&mut self, iter: I) {
+        self.terms.extend(iter);
+    }
+}
+
 impl<F: Field> core::ops::Deref for LinearCombination<F> {
     type Target = [(F, LCTerm)];
 
@@ -366,3 +806,416 @@ impl<F: Field> core::ops::Deref for LinearCombination<F> {
         &self.terms
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use ark_bls12_381::Fr;
+    use ark_ff::{One, Zero};
+
+    #[test]
+    fn linear_combination_to_dense_test() {
+        let two = Fr::one() + Fr::one();
+        let three = two + Fr::one();
+        let lc = LinearCombination::new(
+            "lc",
+            vec![
+                (Fr::one(), LCTerm::from("b")),
+                (two, LCTerm::One),
+                (three, LCTerm::from("a")),
+                (two, LCTerm::from("b")),
+                (Fr::one(), LCTerm::One),
+            ],
+        );
+
+        let label_order = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let (coeffs, constant) = lc.to_dense(&label_order).unwrap();
+        assert_eq!(coeffs, vec![three, three, Fr::zero()]);
+        assert_eq!(constant, three);
+
+        let err = lc.to_dense(&label_order[..1]).unwrap_err();
+        assert_eq!(err, LCTerm::from("b"));
+    }
+
+    #[test]
+    fn linear_combination_simplify_test() {
+        let two = Fr::one() + Fr::one();
+        let mut lc = LinearCombination::new(
+            "lc",
+            vec![
+                (two, LCTerm::from("b")),
+                (Fr::one(), LCTerm::One),
+                (Fr::one(), LCTerm::from("a")),
+                (-two, LCTerm::from("b")),
+                (two, LCTerm::from("a")),
+                (Fr::one(), LCTerm::One),
+                (Fr::one(), LCTerm::from("c")),
+            ],
+        );
+        let label_order = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let dense = lc.to_dense(&label_order).unwrap();
+
+        lc.simplify();
+        assert_eq!(
+            lc.terms,
+            vec![
+                (two, LCTerm::One),
+                (two + Fr::one(), LCTerm::from("a")),
+                (Fr::one(), LCTerm::from("c")),
+            ]
+        );
+        assert_eq!(lc.to_dense(&label_order).unwrap(), dense);
+
+        let mut cancelling = LinearCombination::new(
+            "zero",
+            vec![(two, LCTerm::from("a")), (-two, LCTerm::from("a"))],
+        );
+        cancelling.simplify();
+        assert!(cancelling.is_empty());
+    }
+
+    #[test]
+    fn linear_combination_evaluate_test() {
+        let two = Fr::one() + Fr::one();
+        let three = two + Fr::one();
+        let lc = LinearCombination::new(
+            "lc",
+            vec![
+                (two, LCTerm::from("a")),
+                (three, LCTerm::One),
+                (-Fr::one(), LCTerm::from("b")),
+            ],
+        );
+        let mut evals = BTreeMap::new();
+        evals.insert("a".to_string(), three);
+        evals.insert("b".to_string(), two);
+        evals.insert("unused".to_string(), two);
+        // 2 * 3 + 3 - 2
+        assert_eq!(lc.evaluate(&evals).unwrap(), three * two + Fr::one());
+
+        evals.remove("b");
+        match lc.evaluate(&evals) {
+            Err(Error::MissingEvaluation { label }) => assert_eq!(label, "b"),
+            _ => panic!("expected a missing evaluation"),
+        }
+    }
+
+    #[test]
+    fn linear_combination_neg_test() {
+        let two = Fr::one() + Fr::one();
+        let lc = LinearCombination::from_terms(
+            "lc",
+            vec![(two, LCTerm::from("a")), (Fr::one(), LCTerm::One)],
+            vec![(Fr::one(), LCTerm::from("b"))],
+        );
+        assert_eq!(
+            lc.terms,
+            vec![
+                (two, LCTerm::from("a")),
+                (Fr::one(), LCTerm::One),
+                (-Fr::one(), LCTerm::from("b")),
+            ]
+        );
+
+        let neg = -lc.clone();
+        assert_eq!(neg.label(), lc.label());
+        for ((c, t), (neg_c, neg_t)) in lc.terms.iter().zip(&neg.terms) {
+            assert_eq!(-*c, *neg_c);
+            assert_eq!(t, neg_t);
+        }
+
+        let mut evals = BTreeMap::new();
+        evals.insert("a".to_string(), two + two);
+        evals.insert("b".to_string(), two);
+        let value = lc.evaluate(&evals).unwrap();
+        assert_eq!(neg.evaluate(&evals).unwrap(), -value);
+        assert!((lc.clone() - &lc).evaluate(&evals).unwrap().is_zero());
+        let mut diff = lc.clone() - &lc;
+        diff.simplify();
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn linear_combination_from_iter_test() {
+        let two = Fr::one() + Fr::one();
+        let mut lc = ["a", "b"]
+            .iter()
+            .zip(vec![Fr::one(), two])
+            .map(|(l, c)| (c, LCTerm::from(*l)))
+            .collect::<LinearCombination<_>>();
+        assert_eq!(lc.label(), "");
+        lc.label = "lc".to_string();
+        lc.extend(vec![(two, LCTerm::One)]);
+
+        assert_eq!(lc.label(), "lc");
+        assert_eq!(
+            lc.terms,
+            vec![
+                (Fr::one(), LCTerm::from("a")),
+                (two, LCTerm::from("b")),
+                (two, LCTerm::One),
+            ]
+        );
+    }
+
+    #[test]
+    fn linear_combination_coefficient_of_test() {
+        let two = Fr::one() + Fr::one();
+        let lc = LinearCombination::new(
+            "lc",
+            vec![
+                (two, LCTerm::from("a")),
+                (Fr::one(), LCTerm::One),
+                (two, LCTerm::from("b")),
+                (Fr::one(), LCTerm::from("a")),
+                (two, LCTerm::One),
+                (-two, LCTerm::from("b")),
+            ],
+        );
+        assert_eq!(lc.coefficient_of(&LCTerm::from("a")), two + Fr::one());
+        assert_eq!(lc.coefficient_of(&LCTerm::One), two + Fr::one());
+        assert!(lc.coefficient_of(&LCTerm::from("b")).is_zero());
+        assert!(lc.coefficient_of(&LCTerm::from("c")).is_zero());
+
+        assert!(lc.contains_term(&LCTerm::from("a")));
+        assert!(lc.contains_term(&LCTerm::from("b")));
+        assert!(lc.contains_term(&LCTerm::One));
+        assert!(!lc.contains_term(&LCTerm::from("c")));
+        assert!(!LinearCombination::<Fr>::empty("empty").contains_term(&LCTerm::One));
+    }
+
+    #[test]
+    fn linear_combination_display_test() {
+        let two = Fr::one() + Fr::one();
+        let lc = LinearCombination::new(
+            "lc",
+            vec![(Fr::one(), LCTerm::from("a")), (two, LCTerm::One)],
+        );
+        assert_eq!(format!("{}", LCTerm::One), "1");
+        assert_eq!(format!("{}", LCTerm::from("a")), "a");
+        assert_eq!(
+            format!("{}", lc),
+            format!("lc = {}*a + {}*1", Fr::one(), two)
+        );
+        assert_eq!(
+            format!("{}", LinearCombination::<Fr>::empty("empty")),
+            "empty = 0"
+        );
+    }
+
+    #[test]
+    fn evaluate_linear_combination_commitment_test() {
+        use crate::kzg10::Commitment;
+        use ark_bls12_381::{Bls12_381, G1Projective};
+        use ark_ec::ProjectiveCurve;
+        use ark_ff::{PrimeField, UniformRand};
+
+        let rng = &mut ark_ff::test_rng();
+        let g = G1Projective::rand(rng);
+        let commit = |s: Fr| Commitment::<Bls12_381>(g.mul(s.into_repr()).into_affine());
+        let (a, b) = (Fr::rand(rng), Fr::rand(rng));
+        let mut comms = BTreeMap::new();
+        comms.insert(
+            "a".to_string(),
+            LabeledCommitment::new("a".to_string(), commit(a), None),
+        );
+        comms.insert(
+            "b".to_string(),
+            LabeledCommitment::new("b".to_string(), commit(b), None),
+        );
+        let generator = commit(Fr::one());
+
+        let two = Fr::one() + Fr::one();
+        let mut lc = LinearCombination::new(
+            "lc",
+            vec![(two, LCTerm::from("a")), (-Fr::one(), LCTerm::from("b"))],
+        );
+        let combined = evaluate_linear_combination_commitment(&comms, &lc, &generator).unwrap();
+        assert_eq!(combined, commit(two * a - b));
+
+        lc += two;
+        let combined = evaluate_linear_combination_commitment(&comms, &lc, &generator).unwrap();
+        assert_eq!(combined, commit(two * a - b + two));
+
+        lc.push((Fr::one(), LCTerm::from("c")));
+        assert!(matches!(
+            evaluate_linear_combination_commitment(&comms, &lc, &generator),
+            Err(Error::MissingPolynomial { label }) if label == "c"
+        ));
+    }
+
+    #[test]
+    fn commitment_map_test() {
+        use crate::kzg10::Commitment;
+        use ark_bls12_381::Bls12_381;
+
+        let comms = ["a", "b", "c"]
+            .iter()
+            .map(|l| LabeledCommitment::new(l.to_string(), Commitment::<Bls12_381>::empty(), None))
+            .collect::<Vec<_>>();
+        let map = CommitmentMap::new(&comms);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("b").unwrap().label(), "b");
+        assert!(map.get("d").is_none());
+
+        let mut query_set = QuerySet::new();
+        query_set.insert(("a".to_string(), ("x".to_string(), Fr::one())));
+        query_set.insert(("c".to_string(), ("y".to_string(), Fr::zero())));
+        let resolved = map
+            .iter_for_query_set(&query_set)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved[0].0.label(), "a");
+        assert_eq!(resolved[0].1, &("x".to_string(), Fr::one()));
+        assert_eq!(resolved[1].0.label(), "c");
+
+        query_set.insert(("d".to_string(), ("x".to_string(), Fr::one())));
+        let err = map
+            .iter_for_query_set(&query_set)
+            .collect::<Result<Vec<_>, _>>()
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::MissingPolynomial { label } if label == "d"));
+    }
+
+    #[test]
+    fn labeled_polynomial_tuple_round_trip_test() {
+        use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+
+        let rng = &mut ark_ff::test_rng();
+        let p = DensePolynomial::<Fr>::rand(10, rng);
+        let labeled = LabeledPolynomial::new("p".to_string(), p.clone(), Some(12), Some(2));
+
+        let (label, polynomial, degree_bound, hiding_bound) = labeled.clone().into();
+        assert_eq!(label, "p");
+        assert_eq!(polynomial, p);
+        assert_eq!(degree_bound, Some(12));
+        assert_eq!(hiding_bound, Some(2));
+
+        let round_tripped: LabeledPolynomial<Fr, _> =
+            (label, polynomial, degree_bound, hiding_bound).into();
+        assert_eq!(round_tripped.label(), labeled.label());
+        assert_eq!(round_tripped.polynomial(), labeled.polynomial());
+        assert_eq!(round_tripped.degree_bound(), labeled.degree_bound());
+        assert_eq!(round_tripped.hiding_bound(), labeled.hiding_bound());
+    }
+
+    #[test]
+    fn with_label_test() {
+        use crate::kzg10::Commitment;
+        use ark_bls12_381::Bls12_381;
+        use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+
+        let rng = &mut ark_ff::test_rng();
+        let p = LabeledPolynomial::new(
+            "p".to_string(),
+            DensePolynomial::<Fr>::rand(10, rng),
+            Some(12),
+            Some(2),
+        );
+        let relabeled = p.clone().with_label("sub/p".to_string());
+        assert_eq!(relabeled.label(), "sub/p");
+        assert!(Rc::ptr_eq(&p.polynomial, &relabeled.polynomial));
+        assert_eq!(relabeled.degree_bound(), p.degree_bound());
+        assert_eq!(relabeled.hiding_bound(), p.hiding_bound());
+
+        let comm =
+            LabeledCommitment::new("c".to_string(), Commitment::<Bls12_381>::empty(), Some(5));
+        let relabeled = comm.with_label("sub/c".to_string());
+        assert_eq!(relabeled.label(), "sub/c");
+        assert_eq!(relabeled.degree_bound(), Some(5));
+        assert!(relabeled.is_zero());
+    }
+
+    #[test]
+    fn linear_combination_rename_labels_test() {
+        let two = Fr::one() + Fr::one();
+        let mut lc = LinearCombination::new(
+            "lc",
+            vec![
+                (Fr::one(), LCTerm::from("a")),
+                (two, LCTerm::One),
+                (two, LCTerm::from("b")),
+            ],
+        );
+        lc.rename_labels(|l| format!("sub/{}", l));
+
+        assert_eq!(lc.label(), "sub/lc");
+        assert_eq!(
+            lc.terms,
+            vec![
+                (Fr::one(), LCTerm::from("sub/a")),
+                (two, LCTerm::One),
+                (two, LCTerm::from("sub/b")),
+            ]
+        );
+    }
+
+    #[test]
+    fn labeled_is_zero_test() {
+        use crate::kzg10::{Commitment, KZG10};
+        use ark_bls12_381::Bls12_381;
+        use ark_ff::test_rng;
+        use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+        type UniPoly = DensePolynomial<Fr>;
+
+        let rng = &mut test_rng();
+        let zero = LabeledPolynomial::new("zero".to_string(), UniPoly::zero(), None, None);
+        let p = LabeledPolynomial::new("p".to_string(), UniPoly::rand(4, rng), None, None);
+        assert!(zero.is_zero());
+        assert!(!p.is_zero());
+
+        let pp = KZG10::<Bls12_381, UniPoly>::setup(4, false, rng).unwrap();
+        let powers = kzg10::Powers::<Bls12_381> {
+            powers_of_g: pp.powers_of_g[..].into(),
+            powers_of_gamma_g: pp
+                .powers_of_gamma_g
+                .values()
+                .cloned()
+                .collect::<Vec<_>>()
+                .into(),
+            parallel_threshold: kzg10::DEFAULT_PARALLEL_THRESHOLD,
+        };
+        let (comm, _) = KZG10::commit(&powers, p.polynomial(), None, None).unwrap();
+        let (hiding_zero_comm, _) =
+            KZG10::commit(&powers, zero.polynomial(), Some(1), Some(rng)).unwrap();
+        assert!(
+            LabeledCommitment::new("zero".to_string(), Commitment::<Bls12_381>::empty(), None)
+                .is_zero()
+        );
+        assert!(!LabeledCommitment::new("p".to_string(), comm, None).is_zero());
+        assert!(!LabeledCommitment::new("zero".to_string(), hiding_zero_comm, None).is_zero());
+    }
+
+    #[test]
+    fn query_set_group_by_point_test() {
+        let mut query_set = QuerySet::new();
+        assert!(query_set.insert_point("b", "beta", 2u64));
+        assert!(query_set.insert_point("a", "beta", 2u64));
+        assert!(query_set.insert_point("a", "alpha", 1u64));
+        assert!(query_set.insert_point("c", "gamma", 3u64));
+        assert!(!query_set.insert_point("a", "beta", 2u64));
+        assert_eq!(query_set.len(), 4);
+
+        let grouped = query_set
+            .group_by_point()
+            .into_iter()
+            .map(|(point_label, (point, labels))| {
+                (
+                    point_label.as_str(),
+                    *point,
+                    labels.into_iter().map(|l| l.as_str()).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            grouped,
+            vec![
+                ("alpha", 1, vec!["a"]),
+                ("beta", 2, vec!["a", "b"]),
+                ("gamma", 3, vec!["c"]),
+            ]
+        );
+    }
+}
@@ -50,8 +50,14 @@ pub enum Error {
         num_powers: usize,
     },
 
-    /// The degree provided to `trim` was too large.
-    TrimmingDegreeTooLarge,
+    /// The degree, or an enforced degree bound, provided to `trim` was larger
+    /// than the maximum degree supported by the public parameters.
+    TrimmingDegreeTooLarge {
+        /// The requested degree or degree bound.
+        supported: usize,
+        /// The maximum degree supported by the public parameters.
+        max: usize,
+    },
 
     /// The provided `enforced_degree_bounds` was `Some<&[]>`.
     EmptyDegreeBounds,
@@ -79,6 +85,59 @@ pub enum Error {
 
     /// The inputs to `commit`, `open` or `verify` had incorrect lengths.
     IncorrectInputLength(String),
+
+    /// A group element supplied to `check` was not on the curve or not in the
+    /// prime-order subgroup.
+    InvalidGroupElement,
+
+    /// A scalar supplied as a `BigInt` was not the canonical representation of
+    /// a field element.
+    InvalidFieldElement,
+
+    /// Two of the input polynomials have the same label.
+    DuplicateLabel {
+        /// The duplicated label.
+        label: String,
+    },
+
+    /// `commit` or `open` was called with `Powers` that contain no elements.
+    EmptyPowers,
+
+    /// A hiding evaluation proof was supplied to a check that only supports
+    /// non-hiding proofs.
+    HidingProofUnsupported,
+
+    /// Two of the points at which a polynomial is opened are equal.
+    RepeatedPoint,
+
+    /// `setup` or `trim` would allocate more than the allocation cap of the
+    /// universal parameters, in bytes.
+    AllocationTooLarge {
+        /// The estimated size of the allocation, in bytes.
+        requested_bytes: usize,
+    },
+
+    /// Verifier keys that are checked together come from different setups:
+    /// their `h` or `beta_h` differ.
+    MismatchedVerifierKeys,
+
+    /// A labeled commitment has a degree bound but no shifted commitment, or
+    /// a shifted commitment but no degree bound.
+    DegreeBoundMismatch {
+        /// The label of the malformed commitment.
+        label: String,
+    },
+
+    /// A hex string passed to `HexCodec::from_hex` was malformed.
+    MalformedHex(String),
+
+    /// A fixed-size encoding passed to a `from_compressed_bytes` method was
+    /// malformed.
+    MalformedEncoding(String),
+
+    /// The offset of a coset passed to `commit_from_coset_evaluations` was
+    /// zero.
+    ZeroCosetOffset,
 }
 
 impl core::fmt::Display for Error {
@@ -123,9 +182,11 @@ impl core::fmt::Display for Error {
                 "the degree of the hiding poly ({:?}) is not less than the maximum number of powers in `Powers` ({:?})",
                 hiding_poly_degree, num_powers
             ),
-            Error::TrimmingDegreeTooLarge => {
-                write!(f, "the degree provided to `trim` was too large")
-            }
+            Error::TrimmingDegreeTooLarge { supported, max } => write!(
+                f,
+                "the degree {} provided to `trim` is larger than the maximum degree {}",
+                supported, max
+            ),
             Error::EmptyDegreeBounds => {
                 write!(f, "provided `enforced_degree_bounds` was `Some<&[]>`")
             }
@@ -152,6 +213,39 @@ impl core::fmt::Display for Error {
                 degree_bound, label, poly_degree, supported_degree
             ),
             Error::IncorrectInputLength(err) => write!(f, "{}", err),
+            Error::InvalidGroupElement => write!(
+                f,
+                "a supplied group element is not in the prime-order subgroup"
+            ),
+            Error::InvalidFieldElement => write!(
+                f,
+                "a supplied scalar is not the canonical representation of a field element"
+            ),
+            Error::DuplicateLabel { label } => {
+                write!(f, "more than one polynomial is labeled \"{}\"", label)
+            }
+            Error::EmptyPowers => write!(f, "the supplied `Powers` are empty"),
+            Error::HidingProofUnsupported => {
+                write!(f, "this check does not support hiding evaluation proofs")
+            }
+            Error::RepeatedPoint => write!(f, "the opening points are not distinct"),
+            Error::AllocationTooLarge { requested_bytes } => write!(
+                f,
+                "the parameters would take {} bytes, more than the allocation cap",
+                requested_bytes
+            ),
+            Error::MismatchedVerifierKeys => write!(
+                f,
+                "the verifier keys do not share the same `h` and `beta_h`"
+            ),
+            Error::DegreeBoundMismatch { label } => write!(
+                f,
+                "the degree bound of \"{}\" does not match its shifted commitment",
+                label
+            ),
+            Error::MalformedHex(err) => write!(f, "malformed hex encoding: {}", err),
+            Error::MalformedEncoding(err) => write!(f, "malformed encoding: {}", err),
+            Error::ZeroCosetOffset => write!(f, "the coset offset is zero"),
         }
     }
 }
@@ -0,0 +1,78 @@
+//! Lowercase hex encodings of canonically serialized values, shared by
+//! `HexCodec` and the optional `serde` support.
+
+use crate::{Error, String, Vec};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::format;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Encodes `bytes` as a lowercase hex string.
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 * bytes.len());
+    for byte in bytes {
+        hex.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        hex.push(HEX_DIGITS[(byte & 0xf) as usize] as char);
+    }
+    hex
+}
+
+/// Decodes a hex string, in either case.
+pub(crate) fn decode(hex: &str) -> Result<Vec<u8>, Error> {
+    fn digit(c: u8) -> Option<u8> {
+        match c {
+            b'0'..=b'9' => Some(c - b'0'),
+            b'a'..=b'f' => Some(c - b'a' + 10),
+            b'A'..=b'F' => Some(c - b'A' + 10),
+            _ => None,
+        }
+    }
+    let bytes = hex.as_bytes();
+    if bytes.len() % 2 != 0 {
+        return Err(Error::MalformedHex(format!(
+            "hex string has odd length {}",
+            bytes.len()
+        )));
+    }
+    bytes
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| match (digit(pair[0]), digit(pair[1])) {
+            (Some(hi), Some(lo)) => Ok((hi << 4) | lo),
+            _ => Err(Error::MalformedHex(format!(
+                "invalid hex digit at position {}",
+                if digit(pair[0]).is_none() {
+                    2 * i
+                } else {
+                    2 * i + 1
+                }
+            ))),
+        })
+        .collect()
+}
+
+/// Encodes the canonical (compressed) serialization of `value` as hex.
+pub(crate) fn canonical_to_hex<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = Vec::with_capacity(value.serialized_size());
+    value
+        .serialize(&mut bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    encode(&bytes)
+}
+
+/// Decodes a hex string produced by `canonical_to_hex`, rejecting encodings
+/// with trailing bytes.
+pub(crate) fn canonical_from_hex<T: CanonicalDeserialize>(hex: &str) -> Result<T, Error> {
+    let bytes = decode(hex)?;
+    let mut reader = &bytes[..];
+    let value = T::deserialize(&mut reader).map_err(|e| {
+        Error::MalformedHex(format!("hex string does not encode a valid value: {}", e))
+    })?;
+    if !reader.is_empty() {
+        return Err(Error::MalformedHex(format!(
+            "{} trailing bytes after the encoded value",
+            reader.len()
+        )));
+    }
+    Ok(value)
+}
@@ -112,6 +112,10 @@ impl<G: AffineCurve> PCCommitment for Commitment<G> {
         }
     }
 
+    fn is_zero(&self) -> bool {
+        self.comm.is_zero() && self.shifted_comm.map_or(true, |c| c.is_zero())
+    }
+
     fn has_degree_bound(&self) -> bool {
         false
     }
@@ -354,7 +354,10 @@ where
         // Ensure that supported_degree + 1 is a power of two
         let supported_degree = (supported_degree + 1).next_power_of_two() - 1;
         if supported_degree > pp.max_degree() {
-            return Err(Error::TrimmingDegreeTooLarge);
+            return Err(Error::TrimmingDegreeTooLarge {
+                supported: supported_degree,
+                max: pp.max_degree(),
+            });
         }
 
         let trim_time =
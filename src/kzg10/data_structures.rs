@@ -1,10 +1,27 @@
 use crate::*;
-use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
-use ark_ff::{PrimeField, ToBytes, Zero};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, ToBytes, Zero};
+#[cfg(feature = "zeroize")]
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::EvaluationDomain;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use ark_std::{
     borrow::Cow,
+    convert::TryFrom,
+    format,
+    hash::{Hash, Hasher},
+    io::{Read, Write},
     marker::PhantomData,
-    ops::{Add, AddAssign},
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+use blake2::Blake2s;
+use digest::Digest;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "std")]
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    sync::Arc,
 };
 
 /// `UniversalParams` are the universal parameters for the KZG10 scheme.
@@ -20,6 +37,9 @@ pub struct UniversalParams<E: PairingEngine> {
     /// \beta times the above generator of G2.
     pub beta_h: E::G2Affine,
     /// Group elements of the form `{ \beta^i G2 }`, where `i` ranges from `0` to `-degree`.
+    pub neg_powers_of_h: BTreeMap<usize, E::G2Affine>,
+    /// The above negative powers of `h`, prepared for use in pairings.
+    #[derivative(Debug = "ignore")]
     pub prepared_neg_powers_of_h: BTreeMap<usize, E::G2Prepared>,
     /// The generator of G2, prepared for use in pairings.
     #[derivative(Debug = "ignore")]
@@ -27,6 +47,163 @@ pub struct UniversalParams<E: PairingEngine> {
     /// \beta times the above generator of G2, prepared for use in pairings.
     #[derivative(Debug = "ignore")]
     pub prepared_beta_h: E::G2Prepared,
+    /// The maximum number of bytes that `trim` may allocate for the keys
+    /// derived from these parameters; larger requests fail with
+    /// `Error::AllocationTooLarge` before allocating. It is set by
+    /// `KZG10::setup_with_allocation_cap`, and is not serialized: it is
+    /// unbounded for deserialized parameters.
+    pub allocation_cap: usize,
+}
+
+impl<E: PairingEngine> UniversalParams<E> {
+    /// Returns an iterator over `powers_of_gamma_g`, whose `i`-th element is
+    /// `\beta^i \gamma G`, if the map contains exactly the powers for `i` in
+    /// `0..n` for some `n`. Returns `None` if the powers are sparse, in which
+    /// case callers should look the powers up in the map.
+    pub fn dense_powers_of_gamma_g(
+        &self,
+    ) -> Option<impl ExactSizeIterator<Item = &E::G1Affine> + Clone + '_> {
+        let is_dense = self
+            .powers_of_gamma_g
+            .keys()
+            .next_back()
+            .map_or(true, |&k| k + 1 == self.powers_of_gamma_g.len());
+        if is_dense {
+            Some(self.powers_of_gamma_g.values())
+        } else {
+            None
+        }
+    }
+}
+
+impl<E: PairingEngine> CanonicalSerialize for UniversalParams<E> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.powers_of_g.serialize(&mut writer)?;
+        self.powers_of_gamma_g.serialize(&mut writer)?;
+        self.h.serialize(&mut writer)?;
+        self.beta_h.serialize(&mut writer)?;
+        self.neg_powers_of_h.serialize(&mut writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.powers_of_g.serialized_size()
+            + self.powers_of_gamma_g.serialized_size()
+            + self.h.serialized_size()
+            + self.beta_h.serialized_size()
+            + self.neg_powers_of_h.serialized_size()
+    }
+
+    fn serialize_uncompressed<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.powers_of_g.serialize_uncompressed(&mut writer)?;
+        self.powers_of_gamma_g.serialize_uncompressed(&mut writer)?;
+        self.h.serialize_uncompressed(&mut writer)?;
+        self.beta_h.serialize_uncompressed(&mut writer)?;
+        self.neg_powers_of_h.serialize_uncompressed(&mut writer)
+    }
+
+    fn serialize_unchecked<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.powers_of_g.serialize_unchecked(&mut writer)?;
+        self.powers_of_gamma_g.serialize_unchecked(&mut writer)?;
+        self.h.serialize_unchecked(&mut writer)?;
+        self.beta_h.serialize_unchecked(&mut writer)?;
+        self.neg_powers_of_h.serialize_unchecked(&mut writer)
+    }
+
+    fn uncompressed_size(&self) -> usize {
+        self.powers_of_g.uncompressed_size()
+            + self.powers_of_gamma_g.uncompressed_size()
+            + self.h.uncompressed_size()
+            + self.beta_h.uncompressed_size()
+            + self.neg_powers_of_h.uncompressed_size()
+    }
+}
+
+/// Rejects deserialized universal parameters without powers of `G`, whose
+/// maximum degree is undefined.
+fn check_powers_of_g_not_empty<G>(powers_of_g: &[G]) -> Result<(), SerializationError> {
+    if powers_of_g.is_empty() {
+        Err(SerializationError::InvalidData)
+    } else {
+        Ok(())
+    }
+}
+
+impl<E: PairingEngine> CanonicalDeserialize for UniversalParams<E> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let powers_of_g = Vec::deserialize(&mut reader)?;
+        check_powers_of_g_not_empty(&powers_of_g)?;
+        let powers_of_gamma_g = BTreeMap::deserialize(&mut reader)?;
+        let h = E::G2Affine::deserialize(&mut reader)?;
+        let beta_h = E::G2Affine::deserialize(&mut reader)?;
+        let neg_powers_of_h = BTreeMap::deserialize(&mut reader)?;
+        Ok(Self::from_parts(
+            powers_of_g,
+            powers_of_gamma_g,
+            h,
+            beta_h,
+            neg_powers_of_h,
+        ))
+    }
+
+    fn deserialize_uncompressed<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let powers_of_g = Vec::deserialize_uncompressed(&mut reader)?;
+        check_powers_of_g_not_empty(&powers_of_g)?;
+        let powers_of_gamma_g = BTreeMap::deserialize_uncompressed(&mut reader)?;
+        let h = E::G2Affine::deserialize_uncompressed(&mut reader)?;
+        let beta_h = E::G2Affine::deserialize_uncompressed(&mut reader)?;
+        let neg_powers_of_h = BTreeMap::deserialize_uncompressed(&mut reader)?;
+        Ok(Self::from_parts(
+            powers_of_g,
+            powers_of_gamma_g,
+            h,
+            beta_h,
+            neg_powers_of_h,
+        ))
+    }
+
+    fn deserialize_unchecked<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let powers_of_g = Vec::deserialize_unchecked(&mut reader)?;
+        check_powers_of_g_not_empty(&powers_of_g)?;
+        let powers_of_gamma_g = BTreeMap::deserialize_unchecked(&mut reader)?;
+        let h = E::G2Affine::deserialize_unchecked(&mut reader)?;
+        let beta_h = E::G2Affine::deserialize_unchecked(&mut reader)?;
+        let neg_powers_of_h = BTreeMap::deserialize_unchecked(&mut reader)?;
+        Ok(Self::from_parts(
+            powers_of_g,
+            powers_of_gamma_g,
+            h,
+            beta_h,
+            neg_powers_of_h,
+        ))
+    }
+}
+
+impl<E: PairingEngine> UniversalParams<E> {
+    /// Builds universal parameters from their group elements, preparing the
+    /// elements of G2 for use in pairings.
+    pub(crate) fn from_parts(
+        powers_of_g: Vec<E::G1Affine>,
+        powers_of_gamma_g: BTreeMap<usize, E::G1Affine>,
+        h: E::G2Affine,
+        beta_h: E::G2Affine,
+        neg_powers_of_h: BTreeMap<usize, E::G2Affine>,
+    ) -> Self {
+        let prepared_neg_powers_of_h = neg_powers_of_h
+            .iter()
+            .map(|(i, a)| (*i, (*a).into()))
+            .collect();
+        Self {
+            powers_of_g,
+            powers_of_gamma_g,
+            h,
+            beta_h,
+            neg_powers_of_h,
+            prepared_neg_powers_of_h,
+            prepared_h: h.into(),
+            prepared_beta_h: beta_h.into(),
+            allocation_cap: usize::MAX,
+        }
+    }
 }
 
 impl<E: PairingEngine> PCUniversalParams for UniversalParams<E> {
@@ -49,6 +226,12 @@ pub struct Powers<'a, E: PairingEngine> {
     pub powers_of_g: Cow<'a, [E::G1Affine]>,
     /// Group elements of the form `β^i γG`, for different values of `i`.
     pub powers_of_gamma_g: Cow<'a, [E::G1Affine]>,
+    /// The number of coefficients below which commitments and proofs computed
+    /// with `self` stay on the current thread even when the `parallel` feature
+    /// is enabled. Setting it to zero always runs in parallel; it has no
+    /// effect without the `parallel` feature.
+    #[derivative(Default(value = "super::DEFAULT_PARALLEL_THRESHOLD"))]
+    pub parallel_threshold: usize,
 }
 
 impl<E: PairingEngine> Powers<'_, E> {
@@ -56,6 +239,223 @@ impl<E: PairingEngine> Powers<'_, E> {
     pub fn size(&self) -> usize {
         self.powers_of_g.len()
     }
+
+    /// Returns `true` if `self` contains no powers of `G`.
+    pub fn is_empty(&self) -> bool {
+        self.powers_of_g.is_empty()
+    }
+
+    /// Transforms the first `domain.size()` powers of `G` to the Lagrange basis
+    /// over `domain`: the `i`-th output is `L_i(β) G`, where `L_i` is the
+    /// Lagrange polynomial that is one at `\omega^i` and zero elsewhere on
+    /// `domain`. These are the input to `KZG10::commit_lagrange`.
+    ///
+    /// This is an inverse FFT over the group, so `domain.size()` must be a power
+    /// of two.
+    pub fn to_lagrange_basis<D: EvaluationDomain<E::Fr>>(
+        &self,
+        domain: &D,
+    ) -> Result<Vec<E::G1Affine>, Error> {
+        let size = domain.size();
+        if !size.is_power_of_two() {
+            return Err(Error::IncorrectInputLength(format!(
+                "the Lagrange basis requires a domain of power-of-two size, but got {}",
+                size
+            )));
+        }
+        if size > self.size() {
+            return Err(Error::TooManyCoefficients {
+                num_coefficients: size,
+                num_powers: self.size(),
+            });
+        }
+        let lagrange_time = start_timer!(|| format!("Computing Lagrange basis of size {}", size));
+        let mut points = self.powers_of_g[..size]
+            .iter()
+            .map(|g| g.into_projective())
+            .collect::<Vec<_>>();
+        ifft_in_group(&mut points, domain.element(1).inverse().unwrap());
+        let size_inv = domain.size_as_field_element().inverse().unwrap();
+        for point in &mut points {
+            *point *= size_inv;
+        }
+        let lagrange_basis = E::G1Projective::batch_normalization_into_affine(&points);
+        end_timer!(lagrange_time);
+        Ok(lagrange_basis)
+    }
+}
+
+/// Computes, in place, `points[j] = \sum_i \omega_inv^{ij} points[i]` for
+/// `points.len()` a power of two and `omega_inv` of that order, as an iterative
+/// radix-2 FFT.
+fn ifft_in_group<G: ProjectiveCurve>(points: &mut [G], omega_inv: G::ScalarField) {
+    let n = points.len();
+    if n <= 1 {
+        return;
+    }
+    let shift = n.leading_zeros() + 1;
+    for i in 0..n {
+        let j = i.reverse_bits() >> shift;
+        if i < j {
+            points.swap(i, j);
+        }
+    }
+
+    let mut m = 1;
+    while m < n {
+        let omega_m = omega_inv.pow(&[(n / (2 * m)) as u64]);
+        for k in (0..n).step_by(2 * m) {
+            let mut omega = G::ScalarField::one();
+            for j in 0..m {
+                let mut t = points[k + j + m];
+                t *= omega;
+                points[k + j + m] = points[k + j] - &t;
+                points[k + j] += &t;
+                omega *= &omega_m;
+            }
+        }
+        m *= 2;
+    }
+}
+
+/// `SharedPowers` holds the same group elements as `Powers`, but behind `Arc`s,
+/// so that clones (e.g., one per thread) share a single allocation.
+#[cfg(feature = "std")]
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct SharedPowers<E: PairingEngine> {
+    /// Group elements of the form `β^i G`, for different values of `i`.
+    pub powers_of_g: Arc<[E::G1Affine]>,
+    /// Group elements of the form `β^i γG`, for different values of `i`.
+    pub powers_of_gamma_g: Arc<[E::G1Affine]>,
+    /// See `Powers::parallel_threshold`.
+    pub parallel_threshold: usize,
+}
+
+#[cfg(feature = "std")]
+impl<E: PairingEngine> SharedPowers<E> {
+    /// Borrows the shared powers as `Powers`, without copying them.
+    pub fn powers(&self) -> Powers<E> {
+        Powers {
+            powers_of_g: Cow::Borrowed(&self.powers_of_g),
+            powers_of_gamma_g: Cow::Borrowed(&self.powers_of_gamma_g),
+            parallel_threshold: self.parallel_threshold,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, E: PairingEngine> From<Powers<'a, E>> for SharedPowers<E> {
+    fn from(powers: Powers<'a, E>) -> Self {
+        Self {
+            powers_of_g: powers.powers_of_g.into_owned().into(),
+            powers_of_gamma_g: powers.powers_of_gamma_g.into_owned().into(),
+            parallel_threshold: powers.parallel_threshold,
+        }
+    }
+}
+
+/// `Scratch` holds buffers that `KZG10::commit_with_scratch` and
+/// `KZG10::open_with_scratch` reuse across calls instead of allocating.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""), Clone(bound = ""), Debug(bound = ""))]
+pub struct Scratch<E: PairingEngine> {
+    /// Coefficients of the witness polynomial.
+    pub(crate) witness_coeffs: Vec<E::Fr>,
+    /// Coefficients converted to their bigint representation for an MSM.
+    pub(crate) bigints: Vec<<E::Fr as PrimeField>::BigInt>,
+}
+
+impl<E: PairingEngine> Scratch<E> {
+    /// Creates empty scratch buffers; they grow to the largest polynomial seen.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// `CommitCache` memoizes the non-hiding commitments made by
+/// `KZG10::commit_cached`, keyed by a hash of the polynomial's coefficients.
+///
+/// Entries are only meaningful for the `Powers` they were computed with, so a
+/// cache must not be shared between committer keys. It holds at most
+/// `capacity` entries; once full, the oldest entry is evicted first.
+#[cfg(feature = "std")]
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct CommitCache<E: PairingEngine> {
+    /// The maximum number of cached commitments.
+    pub(crate) capacity: usize,
+    /// The cached coefficients and commitments, keyed by the coefficients' hash.
+    pub(crate) entries: HashMap<u64, (Vec<E::Fr>, Commitment<E>)>,
+    /// The keys of `entries`, from oldest to newest.
+    pub(crate) order: VecDeque<u64>,
+    /// The number of lookups that were answered from the cache.
+    pub(crate) hits: usize,
+}
+
+#[cfg(feature = "std")]
+impl<E: PairingEngine> CommitCache<E> {
+    /// Creates an empty cache that holds at most `capacity` commitments.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            hits: 0,
+        }
+    }
+
+    /// The number of cached commitments.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no commitments are cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of lookups that were answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    fn key(coeffs: &[E::Fr]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        coeffs.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Looks up the commitment to the polynomial with coefficients `coeffs`.
+    /// The stored coefficients are compared too, so a hash collision is a miss.
+    pub(crate) fn get(&mut self, coeffs: &[E::Fr]) -> Option<Commitment<E>> {
+        let comm = self
+            .entries
+            .get(&Self::key(coeffs))
+            .filter(|(cached, _)| cached.as_slice() == coeffs)
+            .map(|(_, comm)| *comm);
+        if comm.is_some() {
+            self.hits += 1;
+        }
+        comm
+    }
+
+    /// Caches `comm` as the commitment to the polynomial with coefficients
+    /// `coeffs`, evicting the oldest entry if the cache is full.
+    pub(crate) fn insert(&mut self, coeffs: &[E::Fr], comm: Commitment<E>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = Self::key(coeffs);
+        if self.entries.insert(key, (coeffs.to_vec(), comm)).is_none() {
+            if self.order.len() == self.capacity {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.entries.remove(&oldest);
+                }
+            }
+            self.order.push_back(key);
+        }
+    }
 }
 
 /// `VerifierKey` is used to check evaluation proofs for a given commitment.
@@ -80,7 +480,7 @@ pub struct VerifierKey<E: PairingEngine> {
 
 impl<E: PairingEngine> ToBytes for VerifierKey<E> {
     #[inline]
-    fn write<W: ark_std::io::Write>(&self, mut writer: W) -> ark_std::io::Result<()> {
+    fn write<W: Write>(&self, mut writer: W) -> ark_std::io::Result<()> {
         self.g.write(&mut writer)?;
         self.gamma_g.write(&mut writer)?;
         self.h.write(&mut writer)?;
@@ -90,6 +490,89 @@ impl<E: PairingEngine> ToBytes for VerifierKey<E> {
     }
 }
 
+impl<E: PairingEngine> CanonicalSerialize for VerifierKey<E> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.g.serialize(&mut writer)?;
+        self.gamma_g.serialize(&mut writer)?;
+        self.h.serialize(&mut writer)?;
+        self.beta_h.serialize(&mut writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.g.serialized_size()
+            + self.gamma_g.serialized_size()
+            + self.h.serialized_size()
+            + self.beta_h.serialized_size()
+    }
+
+    fn serialize_uncompressed<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.g.serialize_uncompressed(&mut writer)?;
+        self.gamma_g.serialize_uncompressed(&mut writer)?;
+        self.h.serialize_uncompressed(&mut writer)?;
+        self.beta_h.serialize_uncompressed(&mut writer)
+    }
+
+    fn serialize_unchecked<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.g.serialize_unchecked(&mut writer)?;
+        self.gamma_g.serialize_unchecked(&mut writer)?;
+        self.h.serialize_unchecked(&mut writer)?;
+        self.beta_h.serialize_unchecked(&mut writer)
+    }
+
+    fn uncompressed_size(&self) -> usize {
+        self.g.uncompressed_size()
+            + self.gamma_g.uncompressed_size()
+            + self.h.uncompressed_size()
+            + self.beta_h.uncompressed_size()
+    }
+}
+
+impl<E: PairingEngine> CanonicalDeserialize for VerifierKey<E> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let g = E::G1Affine::deserialize(&mut reader)?;
+        let gamma_g = E::G1Affine::deserialize(&mut reader)?;
+        let h = E::G2Affine::deserialize(&mut reader)?;
+        let beta_h = E::G2Affine::deserialize(&mut reader)?;
+        Ok(Self::from_parts(g, gamma_g, h, beta_h))
+    }
+
+    fn deserialize_uncompressed<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let g = E::G1Affine::deserialize_uncompressed(&mut reader)?;
+        let gamma_g = E::G1Affine::deserialize_uncompressed(&mut reader)?;
+        let h = E::G2Affine::deserialize_uncompressed(&mut reader)?;
+        let beta_h = E::G2Affine::deserialize_uncompressed(&mut reader)?;
+        Ok(Self::from_parts(g, gamma_g, h, beta_h))
+    }
+
+    fn deserialize_unchecked<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let g = E::G1Affine::deserialize_unchecked(&mut reader)?;
+        let gamma_g = E::G1Affine::deserialize_unchecked(&mut reader)?;
+        let h = E::G2Affine::deserialize_unchecked(&mut reader)?;
+        let beta_h = E::G2Affine::deserialize_unchecked(&mut reader)?;
+        Ok(Self::from_parts(g, gamma_g, h, beta_h))
+    }
+}
+
+impl<E: PairingEngine> VerifierKey<E> {
+    /// Builds a verifier key from its group elements, preparing `h` and
+    /// `beta_h` for use in pairings.
+    fn from_parts(
+        g: E::G1Affine,
+        gamma_g: E::G1Affine,
+        h: E::G2Affine,
+        beta_h: E::G2Affine,
+    ) -> Self {
+        Self {
+            g,
+            gamma_g,
+            h,
+            beta_h,
+            prepared_h: h.into(),
+            prepared_beta_h: beta_h.into(),
+        }
+    }
+}
+
 /// `PreparedVerifierKey` is the fully prepared version for checking evaluation proofs for a given commitment.
 /// We omit gamma here for simplicity.
 #[derive(Derivative)]
@@ -123,8 +606,28 @@ impl<E: PairingEngine> PreparedVerifierKey<E> {
     }
 }
 
+/// Compares two affine points as group elements: an affine point at infinity
+/// may carry arbitrary coordinates, so all of them compare equal. Points that
+/// differ in the sign of `y` are distinct group elements, and compare unequal.
+fn eq_canonical<G: AffineCurve>(a: &G, b: &G) -> bool {
+    if a.is_zero() || b.is_zero() {
+        a.is_zero() && b.is_zero()
+    } else {
+        a == b
+    }
+}
+
+/// Hashes an affine point consistently with `eq_canonical`.
+fn hash_canonical<G: AffineCurve, H: Hasher>(g: &G, state: &mut H) {
+    if g.is_zero() {
+        Hash::hash(&G::zero(), state)
+    } else {
+        Hash::hash(g, state)
+    }
+}
+
 /// `Commitment` commits to a polynomial. It is output by `KZG10::commit`.
-#[derive(Derivative)]
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
 #[derivative(
     Default(bound = ""),
     Hash(bound = ""),
@@ -136,22 +639,120 @@ impl<E: PairingEngine> PreparedVerifierKey<E> {
 )]
 pub struct Commitment<E: PairingEngine>(
     /// The commitment is a group element.
+    #[derivative(
+        Hash(hash_with = "hash_canonical"),
+        PartialEq(compare_with = "eq_canonical")
+    )]
     pub E::G1Affine,
 );
 
 impl<E: PairingEngine> ToBytes for Commitment<E> {
     #[inline]
-    fn write<W: ark_std::io::Write>(&self, writer: W) -> ark_std::io::Result<()> {
+    fn write<W: Write>(&self, writer: W) -> ark_std::io::Result<()> {
         self.0.write(writer)
     }
 }
 
+impl<E: PairingEngine> Commitment<E> {
+    /// Serializes `self` into a freshly allocated byte vector.
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.serialized_size());
+        self.serialize(&mut bytes)
+            .expect("serializing into a `Vec` cannot fail");
+        bytes
+    }
+
+    /// Returns a short tag for `self`: the first 8 bytes of the BLAKE2s hash
+    /// of its canonical serialization.
+    ///
+    /// Equal commitments always have equal tags, so peers can compare tags
+    /// before exchanging full commitments. Distinct commitments collide with
+    /// probability about `2^-64`, and a collision can be found with about
+    /// `2^32` work, so a matching tag is not proof of equality: this is an
+    /// optimization, not a security boundary.
+    pub fn tag(&self) -> [u8; 8] {
+        let hash = Blake2s::digest(&self.to_vec());
+        let mut tag = [0u8; 8];
+        tag.copy_from_slice(&hash[..8]);
+        tag
+    }
+
+    /// Returns `\sum_i coeffs[i] comms[i]`, computed with a single
+    /// multi-scalar multiplication. Fails with `Error::IncorrectInputLength`
+    /// if `comms` and `coeffs` have different lengths.
+    pub fn combine_with_coeffs(comms: &[Self], coeffs: &[E::Fr]) -> Result<Self, Error> {
+        if comms.len() != coeffs.len() {
+            return Err(Error::IncorrectInputLength(format!(
+                "{} commitments, but {} coefficients",
+                comms.len(),
+                coeffs.len()
+            )));
+        }
+        let bases = comms.iter().map(|c| c.0).collect::<Vec<_>>();
+        let scalars = coeffs.iter().map(|c| c.into_repr()).collect::<Vec<_>>();
+        Ok(Commitment(
+            VariableBaseMSM::multi_scalar_mul(&bases, &scalars).into_affine(),
+        ))
+    }
+
+    /// The length of the fixed-size encoding of `to_compressed_bytes`: the
+    /// size of a compressed `E::G1Affine`.
+    pub fn compressed_size() -> usize {
+        E::G1Affine::zero().serialized_size()
+    }
+
+    /// Encodes `self` as the compressed group element, with no length prefix.
+    /// `N` must equal `Self::compressed_size()`.
+    pub fn to_compressed_bytes<const N: usize>(&self) -> Result<[u8; N], Error> {
+        check_compressed_size(N, Self::compressed_size())?;
+        let mut bytes = [0u8; N];
+        self.0
+            .serialize(&mut bytes[..])
+            .expect("the buffer has the compressed size");
+        Ok(bytes)
+    }
+
+    /// Decodes a commitment encoded by `to_compressed_bytes`, rejecting
+    /// encodings of points that are not on the curve or not in the prime-order
+    /// subgroup.
+    pub fn from_compressed_bytes<const N: usize>(bytes: &[u8; N]) -> Result<Self, Error> {
+        check_compressed_size(N, Self::compressed_size())?;
+        E::G1Affine::deserialize(&bytes[..])
+            .map(Commitment)
+            .map_err(|_| Error::InvalidGroupElement)
+    }
+}
+
+/// Checks that the length `N` of a fixed-size encoding is `expected`.
+fn check_compressed_size(n: usize, expected: usize) -> Result<(), Error> {
+    if n != expected {
+        return Err(Error::IncorrectInputLength(format!(
+            "fixed-size encoding has length {}, expected {}",
+            n, expected
+        )));
+    }
+    Ok(())
+}
+
+impl<'a, E: PairingEngine> TryFrom<&'a [u8]> for Commitment<E> {
+    type Error = SerializationError;
+
+    #[inline]
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        Self::deserialize(bytes)
+    }
+}
+
 impl<E: PairingEngine> PCCommitment for Commitment<E> {
     #[inline]
     fn empty() -> Self {
         Commitment(E::G1Affine::zero())
     }
 
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
     fn has_degree_bound(&self) -> bool {
         false
     }
@@ -161,6 +762,33 @@ impl<E: PairingEngine> PCCommitment for Commitment<E> {
     }
 }
 
+/// Compares the compressed encodings of the commitments in constant time, for
+/// comparisons against untrusted input. Unlike `==`, the running time does not
+/// depend on where the commitments first differ.
+#[cfg(feature = "subtle")]
+impl<E: PairingEngine> subtle::ConstantTimeEq for Commitment<E> {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        let mut bytes = Vec::with_capacity(self.serialized_size());
+        self.serialize(&mut bytes)
+            .expect("serializing into a `Vec` cannot fail");
+        let mut other_bytes = Vec::with_capacity(other.serialized_size());
+        other
+            .serialize(&mut other_bytes)
+            .expect("serializing into a `Vec` cannot fail");
+        bytes.as_slice().ct_eq(other_bytes.as_slice())
+    }
+}
+
+impl<E: PairingEngine> HexCodec for Commitment<E> {
+    fn to_hex(&self) -> String {
+        crate::hex::canonical_to_hex(self)
+    }
+
+    fn from_hex(hex: &str) -> Result<Self, Error> {
+        crate::hex::canonical_from_hex(hex)
+    }
+}
+
 impl<'a, E: PairingEngine> AddAssign<(E::Fr, &'a Commitment<E>)> for Commitment<E> {
     #[inline]
     fn add_assign(&mut self, (f, other): (E::Fr, &'a Commitment<E>)) {
@@ -170,6 +798,108 @@ impl<'a, E: PairingEngine> AddAssign<(E::Fr, &'a Commitment<E>)> for Commitment<
     }
 }
 
+impl<E: PairingEngine> Mul<E::Fr> for Commitment<E> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, f: E::Fr) -> Self {
+        self *= f;
+        self
+    }
+}
+
+impl<E: PairingEngine> MulAssign<E::Fr> for Commitment<E> {
+    #[inline]
+    fn mul_assign(&mut self, f: E::Fr) {
+        self.0 = self.0.mul(f.into_repr()).into();
+    }
+}
+
+impl<E: PairingEngine> Neg for Commitment<E> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Commitment(-self.0)
+    }
+}
+
+impl<E: PairingEngine> Sub for Commitment<E> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: Self) -> Self {
+        self -= &other;
+        self
+    }
+}
+
+impl<'a, E: PairingEngine> SubAssign<&'a Commitment<E>> for Commitment<E> {
+    #[inline]
+    fn sub_assign(&mut self, other: &'a Commitment<E>) {
+        let mut result = self.0.into_projective();
+        result -= &other.0.into_projective();
+        self.0 = result.into();
+    }
+}
+
+/// `ProjectiveCommitment` is a `Commitment` in projective coordinates. Sums of
+/// many commitments should be accumulated in this form and converted back to a
+/// `Commitment` once, as each addition of affine commitments converts the
+/// result to affine coordinates.
+#[derive(Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Hash(bound = ""),
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct ProjectiveCommitment<E: PairingEngine>(
+    /// The commitment is a group element.
+    pub E::G1Projective,
+);
+
+impl<E: PairingEngine> From<Commitment<E>> for ProjectiveCommitment<E> {
+    #[inline]
+    fn from(comm: Commitment<E>) -> Self {
+        ProjectiveCommitment(comm.0.into_projective())
+    }
+}
+
+impl<E: PairingEngine> From<ProjectiveCommitment<E>> for Commitment<E> {
+    #[inline]
+    fn from(comm: ProjectiveCommitment<E>) -> Self {
+        Commitment(comm.0.into_affine())
+    }
+}
+
+impl<E: PairingEngine> Add for ProjectiveCommitment<E> {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: Self) -> Self {
+        self += &other;
+        self
+    }
+}
+
+impl<'a, E: PairingEngine> AddAssign<&'a ProjectiveCommitment<E>> for ProjectiveCommitment<E> {
+    #[inline]
+    fn add_assign(&mut self, other: &'a ProjectiveCommitment<E>) {
+        self.0 += &other.0;
+    }
+}
+
+impl<'a, E: PairingEngine> AddAssign<&'a Commitment<E>> for ProjectiveCommitment<E> {
+    #[inline]
+    fn add_assign(&mut self, other: &'a Commitment<E>) {
+        self.0.add_assign_mixed(&other.0);
+    }
+}
+
 /// `PreparedCommitment` commits to a polynomial and prepares for mul_bits.
 #[derive(Derivative)]
 #[derivative(
@@ -200,9 +930,26 @@ impl<E: PairingEngine> PreparedCommitment<E> {
 
         Self { 0: prepared_comm }
     }
+
+    /// Prepares each of `comms`, as `prepare` does. With the `parallel`
+    /// feature, the commitments are prepared concurrently.
+    pub fn prepare_batch(comms: &[Commitment<E>]) -> Vec<Self> {
+        #[cfg(feature = "parallel")]
+        let iter = comms.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let iter = comms.iter();
+        iter.map(Self::prepare).collect()
+    }
 }
 
 /// `Randomness` hides the polynomial inside a commitment. It is output by `KZG10::commit`.
+///
+/// Randomness sampled for `num_queries` queries has a blinding polynomial of degree
+/// `num_queries + 1`. Each opening reveals one evaluation of the blinding polynomial,
+/// and the commitment and proofs reveal a further evaluation at the (secret) trapdoor
+/// `\beta`, so as long as the commitment is opened at no more than `num_queries`
+/// distinct points, the revealed evaluations are uniformly distributed and
+/// independent of the committed polynomial beyond the claimed values.
 #[derive(Derivative)]
 #[derivative(
     Hash(bound = ""),
@@ -217,6 +964,18 @@ pub struct Randomness<F: PrimeField, P: UVPolynomial<F>> {
     _field: PhantomData<F>,
 }
 
+/// Wipes the coefficients of the blinding polynomial, leaving the empty
+/// randomness. This is only available for `DensePolynomial`, as
+/// `UVPolynomial` gives no mutable access to the coefficients. For the same
+/// reason `Randomness` cannot implement `Drop` for every `P`; wrap it in
+/// `zeroize::Zeroizing` to wipe it on drop.
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> zeroize::Zeroize for Randomness<F, DensePolynomial<F>> {
+    fn zeroize(&mut self) {
+        self.blinding_polynomial.coeffs.zeroize();
+    }
+}
+
 impl<F: PrimeField, P: UVPolynomial<F>> Randomness<F, P> {
     /// Does `self` provide any hiding properties to the corresponding commitment?
     /// `self.is_hiding() == true` only if the underlying polynomial is non-zero.
@@ -225,10 +984,35 @@ impl<F: PrimeField, P: UVPolynomial<F>> Randomness<F, P> {
         !self.blinding_polynomial.is_zero()
     }
 
-    /// What is the degree of the hiding polynomial for a given hiding bound?
+    /// What is the degree of the hiding polynomial that supports hiding
+    /// openings at `num_queries` points?
     #[inline]
-    pub fn calculate_hiding_polynomial_degree(hiding_bound: usize) -> usize {
-        hiding_bound + 1
+    pub fn calculate_hiding_polynomial_degree(num_queries: usize) -> usize {
+        num_queries + 1
+    }
+
+    /// Commits to `self.blinding_polynomial` against `powers.powers_of_gamma_g`.
+    /// This is the hiding part of a commitment made with `self`, i.e., the
+    /// difference between the hiding and the non-hiding commitment to the same
+    /// polynomial.
+    pub fn commit_blinding<E: PairingEngine<Fr = F>>(
+        &self,
+        powers: &Powers<E>,
+    ) -> Result<Commitment<E>, Error> {
+        let coeffs = self.blinding_polynomial.coeffs();
+        if coeffs.len() > powers.powers_of_gamma_g.len() {
+            return Err(Error::HidingBoundToolarge {
+                hiding_poly_degree: self.blinding_polynomial.degree(),
+                num_powers: powers.powers_of_gamma_g.len(),
+            });
+        }
+        let coeffs = coeffs.iter().map(|c| c.into_repr()).collect::<Vec<_>>();
+        let comm = super::multi_scalar_mul(
+            &powers.powers_of_gamma_g,
+            &coeffs,
+            powers.parallel_threshold,
+        );
+        Ok(Commitment(comm.into_affine()))
     }
 }
 
@@ -240,9 +1024,9 @@ impl<F: PrimeField, P: UVPolynomial<F>> PCRandomness for Randomness<F, P> {
         }
     }
 
-    fn rand<R: RngCore>(hiding_bound: usize, _: bool, _: Option<usize>, rng: &mut R) -> Self {
+    fn rand<R: RngCore>(num_queries: usize, _: bool, _: Option<usize>, rng: &mut R) -> Self {
         let mut randomness = Randomness::empty();
-        let hiding_poly_degree = Self::calculate_hiding_polynomial_degree(hiding_bound);
+        let hiding_poly_degree = Self::calculate_hiding_polynomial_degree(num_queries);
         randomness.blinding_polynomial = P::rand(hiding_poly_degree, rng);
         randomness
     }
@@ -285,7 +1069,7 @@ impl<'a, F: PrimeField, P: UVPolynomial<F>> AddAssign<(F, &'a Randomness<F, P>)>
 }
 
 /// `Proof` is an evaluation proof that is output by `KZG10::open`.
-#[derive(Derivative)]
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
 #[derivative(
     Default(bound = ""),
     Hash(bound = ""),
@@ -297,12 +1081,128 @@ impl<'a, F: PrimeField, P: UVPolynomial<F>> AddAssign<(F, &'a Randomness<F, P>)>
 )]
 pub struct Proof<E: PairingEngine> {
     /// This is a commitment to the witness polynomial; see [KZG10] for more details.
+    #[derivative(
+        Hash(hash_with = "hash_canonical"),
+        PartialEq(compare_with = "eq_canonical")
+    )]
     pub w: E::G1Affine,
     /// This is the evaluation of the random polynomial at the point for which
     /// the evaluation proof was produced.
     pub random_v: Option<E::Fr>,
 }
 
+impl<E: PairingEngine> Proof<E> {
+    /// Serializes `self` into a freshly allocated byte vector.
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.serialized_size());
+        self.serialize(&mut bytes)
+            .expect("serializing into a `Vec` cannot fail");
+        bytes
+    }
+
+    /// The length of the fixed-size encoding of `to_compressed_bytes`: a
+    /// compressed `E::G1Affine`, a presence byte and an `E::Fr`.
+    pub fn compressed_size() -> usize {
+        E::G1Affine::zero().serialized_size() + 1 + E::Fr::zero().serialized_size()
+    }
+
+    /// Encodes `self` with a fixed layout and no length prefix: the compressed
+    /// witness `w`, then `1` if `random_v` is present and `0` otherwise, then
+    /// `random_v` (all zeros if absent). `N` must equal
+    /// `Self::compressed_size()`.
+    pub fn to_compressed_bytes<const N: usize>(&self) -> Result<[u8; N], Error> {
+        check_compressed_size(N, Self::compressed_size())?;
+        let mut bytes = [0u8; N];
+        let w_size = E::G1Affine::zero().serialized_size();
+        self.w
+            .serialize(&mut bytes[..w_size])
+            .expect("the buffer has the compressed size");
+        if let Some(random_v) = self.random_v {
+            bytes[w_size] = 1;
+            random_v
+                .serialize(&mut bytes[w_size + 1..])
+                .expect("the buffer has the compressed size");
+        }
+        Ok(bytes)
+    }
+
+    /// Decodes a proof encoded by `to_compressed_bytes`. Invalid points,
+    /// presence bytes other than `0` and `1`, non-canonical field elements and
+    /// non-zero padding after an absent `random_v` are all rejected; the
+    /// presence byte and padding with `Error::MalformedEncoding`.
+    pub fn from_compressed_bytes<const N: usize>(bytes: &[u8; N]) -> Result<Self, Error> {
+        check_compressed_size(N, Self::compressed_size())?;
+        let w_size = E::G1Affine::zero().serialized_size();
+        let w =
+            E::G1Affine::deserialize(&bytes[..w_size]).map_err(|_| Error::InvalidGroupElement)?;
+        let random_v_bytes = &bytes[w_size + 1..];
+        let random_v = match bytes[w_size] {
+            0 if random_v_bytes.iter().all(|b| *b == 0) => None,
+            0 => {
+                return Err(Error::MalformedEncoding(
+                    "non-zero padding after an absent `random_v`".to_string(),
+                ))
+            }
+            1 => Some(E::Fr::deserialize(random_v_bytes).map_err(|_| Error::InvalidFieldElement)?),
+            b => {
+                return Err(Error::MalformedEncoding(format!(
+                    "presence byte {} is neither 0 nor 1",
+                    b
+                )))
+            }
+        };
+        Ok(Proof { w, random_v })
+    }
+
+    /// Folds `proofs` with the verifier-chosen `weights` into the proof
+    /// `(\sum_i weights[i] w_i, \sum_i weights[i] random_v_i)`, as
+    /// `KZG10::batch_check` does, treating an absent `random_v_i` as zero. The
+    /// folded `random_v` is absent only if it is absent in every proof. Fails
+    /// with `Error::IncorrectInputLength` if `proofs` and `weights` have
+    /// different lengths.
+    pub fn fold(proofs: &[Self], weights: &[E::Fr]) -> Result<Self, Error> {
+        if proofs.len() != weights.len() {
+            return Err(Error::IncorrectInputLength(format!(
+                "{} proofs, but {} weights",
+                proofs.len(),
+                weights.len()
+            )));
+        }
+        let bases = proofs.iter().map(|p| p.w).collect::<Vec<_>>();
+        let scalars = weights.iter().map(|r| r.into_repr()).collect::<Vec<_>>();
+        let w = VariableBaseMSM::multi_scalar_mul(&bases, &scalars).into_affine();
+        let random_v = proofs
+            .iter()
+            .zip(weights)
+            .filter_map(|(p, r)| p.random_v.map(|v| v * r))
+            .fold(None, |acc, v| Some(acc.unwrap_or_else(E::Fr::zero) + v));
+        Ok(Proof { w, random_v })
+    }
+}
+
+impl<E: PairingEngine> Neg for Proof<E> {
+    type Output = Self;
+
+    /// Negates `w` and, if present, `random_v`. The result is a proof for the
+    /// negated commitment and value.
+    #[inline]
+    fn neg(self) -> Self {
+        Proof {
+            w: -self.w,
+            random_v: self.random_v.map(|v| -v),
+        }
+    }
+}
+
+impl<'a, E: PairingEngine> TryFrom<&'a [u8]> for Proof<E> {
+    type Error = SerializationError;
+
+    #[inline]
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        Self::deserialize(bytes)
+    }
+}
+
 impl<E: PairingEngine> PCProof for Proof<E> {
     fn size_in_bytes(&self) -> usize {
         let hiding_size = if self.random_v.is_some() {
@@ -314,9 +1214,19 @@ impl<E: PairingEngine> PCProof for Proof<E> {
     }
 }
 
+impl<E: PairingEngine> HexCodec for Proof<E> {
+    fn to_hex(&self) -> String {
+        crate::hex::canonical_to_hex(self)
+    }
+
+    fn from_hex(hex: &str) -> Result<Self, Error> {
+        crate::hex::canonical_from_hex(hex)
+    }
+}
+
 impl<E: PairingEngine> ToBytes for Proof<E> {
     #[inline]
-    fn write<W: ark_std::io::Write>(&self, mut writer: W) -> ark_std::io::Result<()> {
+    fn write<W: Write>(&self, mut writer: W) -> ark_std::io::Result<()> {
         self.w.write(&mut writer)?;
         self.random_v
             .as_ref()
@@ -324,3 +1234,214 @@ impl<E: PairingEngine> ToBytes for Proof<E> {
             .write(&mut writer)
     }
 }
+
+/// `AggregateProof` bundles several `Proof`s, e.g. the proofs of a batch that
+/// is verified with `KZG10::batch_check`, so that their combined size can be
+/// reported.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Default(bound = ""),
+    Hash(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct AggregateProof<E: PairingEngine> {
+    /// The individual evaluation proofs.
+    pub proofs: Vec<Proof<E>>,
+}
+
+impl<E: PairingEngine> From<Vec<Proof<E>>> for AggregateProof<E> {
+    fn from(proofs: Vec<Proof<E>>) -> Self {
+        Self { proofs }
+    }
+}
+
+impl<E: PairingEngine> PCProof for AggregateProof<E> {
+    /// The length of the canonical serialization of `self`: a length prefix
+    /// and, for every proof, its compressed `w`, a presence flag and
+    /// `random_v` if present.
+    fn size_in_bytes(&self) -> usize {
+        let w_size = E::G1Affine::zero().serialized_size();
+        let v_size = E::Fr::zero().serialized_size();
+        let len_size = (self.proofs.len() as u64).serialized_size();
+        len_size
+            + self
+                .proofs
+                .iter()
+                .map(|proof| w_size + 1 + proof.random_v.map_or(0, |_| v_size))
+                .sum::<usize>()
+    }
+}
+
+impl<E: PairingEngine> ToBytes for AggregateProof<E> {
+    /// Writes the number of proofs as a `u64`, as the canonical serialization
+    /// does, followed by each proof.
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> ark_std::io::Result<()> {
+        (self.proofs.len() as u64).write(&mut writer)?;
+        for proof in &self.proofs {
+            proof.write(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// The part of the verification equation of `KZG10::check` that failed, as
+/// reported by `KZG10::check_hiding_detailed`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HidingCheckFailure {
+    /// `random_v` is missing, unexpected, or is not the evaluation of the
+    /// blinding polynomial at the point.
+    HidingTerm,
+    /// The hiding term is consistent, so `value` or the witness `w` is wrong.
+    BindingTerm,
+}
+
+/// The inputs to the pairings of the equation checked by `KZG10::check`,
+/// `e(comm - value G - random_v γG, H) = e(w, βH - point H)`, together with
+/// the part of the equation that failed (if any).
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct HidingCheckDetails<E: PairingEngine> {
+    /// The contribution of the evaluation, `value G`.
+    pub evaluation_term: E::G1Affine,
+    /// The contribution of the hiding randomness, `random_v γG`. This is zero
+    /// if the proof is not hiding.
+    pub hiding_term: E::G1Affine,
+    /// The G1 input to the left-hand pairing, `comm - value G - random_v γG`.
+    pub lhs_g1: E::G1Affine,
+    /// The G1 input to the right-hand pairing, i.e., the witness `w`.
+    pub rhs_g1: E::G1Affine,
+    /// The G2 input to the right-hand pairing, `βH - point H`.
+    pub rhs_g2: E::G2Affine,
+    /// `None` if the equation holds, and otherwise the part that failed.
+    pub failure: Option<HidingCheckFailure>,
+}
+
+/// An evaluation proof bound to a context tag by `KZG10::open_bound`.
+#[derive(Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Hash(bound = ""),
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct BoundProof<E: PairingEngine> {
+    /// The proof of evaluation at the queried point.
+    pub proof: Proof<E>,
+    /// The evaluation of the polynomial at the point derived from the tag.
+    pub tag_value: E::Fr,
+    /// The proof of evaluation at the point derived from the tag.
+    pub tag_proof: Proof<E>,
+}
+
+/// Evaluation proofs for the chunks of a polynomial, output by
+/// `KZG10::open_chunked`.
+#[derive(Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Hash(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct ChunkedProof<E: PairingEngine> {
+    /// The evaluation of each chunk at the queried point.
+    pub chunk_values: Vec<E::Fr>,
+    /// The proof of evaluation of each chunk at the queried point.
+    pub proofs: Vec<Proof<E>>,
+}
+
+/// A proof, output by `KZG10::open_nested`, that a polynomial `p` evaluates
+/// at `z` to the same (hidden) value as a polynomial `V` at `x_i`.
+#[derive(Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Hash(bound = ""),
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct NestedProof<E: PairingEngine> {
+    /// The hiding commitment `v G + p_r(z) \gamma G` to the common value `v`,
+    /// where `p_r` is the blinding polynomial of `p`.
+    pub value_comm: E::G1Affine,
+    /// The witness for the opening of `p` at `z`.
+    pub w_p: E::G1Affine,
+    /// The witness for the opening of `V` at `x_i`.
+    pub w_values: E::G1Affine,
+    /// `p_r(z) - V_r(x_i)`, where `V_r` is the blinding polynomial of `V`.
+    pub randomness_diff: E::Fr,
+}
+
+/// A Schnorr-style proof of knowledge of the coefficients committed in a
+/// `Commitment`, output by `KZG10::commit_with_pok`.
+#[derive(Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Hash(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct PokProof<E: PairingEngine> {
+    /// The commitment `\sum_i k_i \beta^i G` to the random masks `k_i`.
+    pub mask_comm: E::G1Affine,
+    /// The responses `k_i + c a_i`, where `a_i` are the committed coefficients
+    /// and `c` is the challenge.
+    pub responses: Vec<E::Fr>,
+}
+
+/// An aggregated proof, output by `KZG10::open_rotations`, of the evaluations
+/// of a polynomial `p` at the rotations `z, \omega z, ..., \omega^{n-1} z`.
+#[derive(Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Hash(bound = ""),
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct RotationsProof<E: PairingEngine> {
+    /// The commitment to the quotient `q` of `p` by the vanishing polynomial
+    /// `Z` of the rotations.
+    pub quotient_comm: Commitment<E>,
+    /// The proof that `p - Z(x) q` evaluates to `I(x)` at the point `x`
+    /// derived from the challenge, where `I` interpolates the evaluations.
+    pub proof: Proof<E>,
+}
+
+/// A proof, output by `KZG10::prove_scalar_multiple`, that a committed
+/// polynomial `p2` equals `c p1` for a committed polynomial `p1` and a hidden
+/// scalar `c`.
+#[derive(Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Hash(bound = ""),
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct ScalarMultipleProof<E: PairingEngine> {
+    /// A hiding commitment to `c`, as a constant polynomial.
+    pub scalar_comm: Commitment<E>,
+    /// The evaluation of `p1` at the challenge point.
+    pub p1_value: E::Fr,
+    /// The proof of evaluation of `p1` at the challenge point.
+    pub p1_proof: Proof<E>,
+    /// The proof that `p2 - p1_value c` evaluates to zero at the challenge point.
+    pub linearized_proof: Proof<E>,
+}
@@ -5,13 +5,17 @@
 //! proposed by Kate, Zaverucha, and Goldberg ([KZG11](http://cacr.uwaterloo.ca/techreports/2010/cacr2010-10.pdf)).
 //! This construction achieves extractability in the algebraic group model (AGM).
 
-use crate::{BTreeMap, Error, LabeledPolynomial, PCRandomness, ToString, Vec};
+use crate::{
+    BTreeMap, Error, LabeledPolynomial, PCCommitment, PCRandomness, PCUniversalParams, ToString,
+    Vec,
+};
 use ark_ec::msm::{FixedBaseMSM, VariableBaseMSM};
 use ark_ec::{group::Group, AffineCurve, PairingEngine, ProjectiveCurve};
-use ark_ff::{One, PrimeField, UniformRand, Zero};
-use ark_poly::UVPolynomial;
-use ark_std::{format, marker::PhantomData, ops::Div, vec};
+use ark_ff::{BigInteger, Field, FpParameters, One, PrimeField, UniformRand, Zero};
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain, UVPolynomial};
+use ark_std::{format, marker::PhantomData, mem::size_of, ops::Div, vec};
 
+use digest::Digest;
 use rand_core::RngCore;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -40,10 +44,28 @@ where
         max_degree: usize,
         produce_g2_powers: bool,
         rng: &mut R,
+    ) -> Result<UniversalParams<E>, Error> {
+        Self::setup_with_allocation_cap(max_degree, produce_g2_powers, usize::MAX, rng)
+    }
+
+    /// Constructs public parameters as `setup` does, but fails with
+    /// `Error::AllocationTooLarge` before allocating if they would take more
+    /// than `allocation_cap` bytes, e.g. on a memory-constrained target. The
+    /// cap is stored in `UniversalParams::allocation_cap`, and also bounds the
+    /// keys output by `trim`.
+    pub fn setup_with_allocation_cap<R: RngCore>(
+        max_degree: usize,
+        produce_g2_powers: bool,
+        allocation_cap: usize,
+        rng: &mut R,
     ) -> Result<UniversalParams<E>, Error> {
         if max_degree < 1 {
             return Err(Error::DegreeIsZero);
         }
+        check_allocation(
+            setup_allocation_bytes::<E>(max_degree, produce_g2_powers),
+            allocation_cap,
+        )?;
         let setup_time = start_timer!(|| format!("KZG10::Setup with degree {}", max_degree));
         let beta = E::Fr::rand(rng);
         let g = E::G1Projective::rand(rng);
@@ -92,7 +114,7 @@ where
 
         let prepared_neg_powers_of_h_time =
             start_timer!(|| "Generating negative powers of h in G2");
-        let prepared_neg_powers_of_h = if produce_g2_powers {
+        let neg_powers_of_h = if produce_g2_powers {
             let mut neg_powers_of_beta = vec![E::Fr::one()];
             let mut cur = E::Fr::one() / &beta;
             for _ in 0..max_degree {
@@ -108,48 +130,44 @@ where
                 &neg_powers_of_beta,
             );
 
-            let affines = E::G2Projective::batch_normalization_into_affine(&neg_powers_of_h);
-            let mut affines_map = BTreeMap::new();
-            affines
+            E::G2Projective::batch_normalization_into_affine(&neg_powers_of_h)
                 .into_iter()
                 .enumerate()
-                .map(|(i, a)| (i, a.into()))
-                .for_each(|(i, a)| {
-                    affines_map.insert(i, a);
-                });
-            affines_map
+                .collect()
         } else {
             BTreeMap::new()
         };
-
-        end_timer!(prepared_neg_powers_of_h_time);
-
         let beta_h = h.mul(beta).into_affine();
         let h = h.into_affine();
-        let prepared_h = h.into();
-        let prepared_beta_h = beta_h.into();
-
-        let pp = UniversalParams {
-            powers_of_g,
-            powers_of_gamma_g,
-            h,
-            beta_h,
-            prepared_neg_powers_of_h,
-            prepared_h,
-            prepared_beta_h,
-        };
+        let mut pp =
+            UniversalParams::from_parts(powers_of_g, powers_of_gamma_g, h, beta_h, neg_powers_of_h);
+        pp.allocation_cap = allocation_cap;
+        end_timer!(prepared_neg_powers_of_h_time);
         end_timer!(setup_time);
         Ok(pp)
     }
 
     /// Outputs a commitment to `polynomial`.
+    ///
+    /// If `hiding_bound` is `Some(k)`, the commitment is hiding, and remains so
+    /// when opened at up to `k` distinct points; see `Randomness`. This requires
+    /// `powers.powers_of_gamma_g` to contain at least `k + 2` elements. Fails
+    /// with `Error::EmptyPowers` if `powers` is empty.
     pub fn commit(
         powers: &Powers<E>,
         polynomial: &P,
         hiding_bound: Option<usize>,
         rng: Option<&mut dyn RngCore>,
     ) -> Result<(Commitment<E>, Randomness<E::Fr, P>), Error> {
-        Self::check_degree_is_within_bounds(polynomial.degree(), powers.size())?;
+        if powers.is_empty() {
+            return Err(Error::EmptyPowers);
+        }
+        let is_constant = polynomial.degree() == 0;
+        if is_constant {
+            Self::check_degree_is_too_large(1, powers.size())?;
+        } else {
+            Self::check_degree_is_within_bounds(polynomial.degree(), powers.size())?;
+        }
 
         let commit_time = start_timer!(|| format!(
             "Committing to polynomial of degree {} with hiding_bound: {:?}",
@@ -157,45 +175,255 @@ where
             hiding_bound,
         ));
 
-        let (num_leading_zeros, plain_coeffs) =
-            skip_leading_zeros_and_convert_to_bigints(polynomial);
+        let mut commitment = if is_constant {
+            // A constant polynomial `c` (including the zero polynomial) commits
+            // to `c * G`, so a single scalar multiplication suffices.
+            let c = polynomial
+                .coeffs()
+                .first()
+                .cloned()
+                .unwrap_or_else(E::Fr::zero);
+            powers.powers_of_g[0].mul(c)
+        } else {
+            let (num_leading_zeros, plain_coeffs) =
+                skip_leading_zeros_and_convert_to_bigints(polynomial, powers.parallel_threshold);
 
-        let msm_time = start_timer!(|| "MSM to compute commitment to plaintext poly");
-        let mut commitment = VariableBaseMSM::multi_scalar_mul(
-            &powers.powers_of_g[num_leading_zeros..],
-            &plain_coeffs,
-        );
-        end_timer!(msm_time);
+            let msm_time = start_timer!(|| "MSM to compute commitment to plaintext poly");
+            let commitment = multi_scalar_mul(
+                &powers.powers_of_g[num_leading_zeros..],
+                &plain_coeffs,
+                powers.parallel_threshold,
+            );
+            end_timer!(msm_time);
+            commitment
+        };
 
         let mut randomness = Randomness::<E::Fr, P>::empty();
-        if let Some(hiding_degree) = hiding_bound {
+        if let Some(num_queries) = hiding_bound {
             let mut rng = rng.ok_or(Error::MissingRng)?;
+            let hiding_poly_degree =
+                Randomness::<E::Fr, P>::calculate_hiding_polynomial_degree(num_queries);
+            Self::check_hiding_bound(hiding_poly_degree, powers.powers_of_gamma_g.len())?;
             let sample_random_poly_time = start_timer!(|| format!(
                 "Sampling a random polynomial of degree {}",
-                hiding_degree
+                hiding_poly_degree
             ));
 
-            randomness = Randomness::rand(hiding_degree, false, None, &mut rng);
+            randomness = Randomness::rand(num_queries, false, None, &mut rng);
+            end_timer!(sample_random_poly_time);
+        }
+
+        if !randomness.blinding_polynomial.is_zero() {
+            let random_ints = convert_to_bigints(
+                &randomness.blinding_polynomial.coeffs(),
+                powers.parallel_threshold,
+            );
+            let msm_time = start_timer!(|| "MSM to compute commitment to random poly");
+            let random_commitment = multi_scalar_mul(
+                &powers.powers_of_gamma_g,
+                random_ints.as_slice(),
+                powers.parallel_threshold,
+            )
+            .into_affine();
+            end_timer!(msm_time);
+
+            commitment.add_assign_mixed(&random_commitment);
+        }
+
+        end_timer!(commit_time);
+        Ok((Commitment(commitment.into()), randomness))
+    }
+
+    /// Outputs a commitment to `polynomial`, as `commit` does, but converts
+    /// coefficients into the buffers of `scratch` instead of allocating.
+    pub fn commit_with_scratch(
+        powers: &Powers<E>,
+        polynomial: &P,
+        hiding_bound: Option<usize>,
+        rng: Option<&mut dyn RngCore>,
+        scratch: &mut Scratch<E>,
+    ) -> Result<(Commitment<E>, Randomness<E::Fr, P>), Error> {
+        if powers.is_empty() {
+            return Err(Error::EmptyPowers);
+        }
+        Self::check_degree_is_within_bounds(polynomial.degree(), powers.size())?;
+        let commit_time = start_timer!(|| format!(
+            "Committing to polynomial of degree {} with scratch",
+            polynomial.degree(),
+        ));
+
+        let threshold = powers.parallel_threshold;
+        convert_to_bigints_into(&polynomial.coeffs(), &mut scratch.bigints, threshold);
+        let mut commitment = multi_scalar_mul(&powers.powers_of_g, &scratch.bigints, threshold);
+
+        let mut randomness = Randomness::<E::Fr, P>::empty();
+        if let Some(num_queries) = hiding_bound {
+            let mut rng = rng.ok_or(Error::MissingRng)?;
             Self::check_hiding_bound(
-                randomness.blinding_polynomial.degree(),
+                Randomness::<E::Fr, P>::calculate_hiding_polynomial_degree(num_queries),
                 powers.powers_of_gamma_g.len(),
             )?;
-            end_timer!(sample_random_poly_time);
+            randomness = Randomness::rand(num_queries, false, None, &mut rng);
         }
 
-        let random_ints = convert_to_bigints(&randomness.blinding_polynomial.coeffs());
-        let msm_time = start_timer!(|| "MSM to compute commitment to random poly");
-        let random_commitment =
-            VariableBaseMSM::multi_scalar_mul(&powers.powers_of_gamma_g, random_ints.as_slice())
-                .into_affine();
-        end_timer!(msm_time);
-
-        commitment.add_assign_mixed(&random_commitment);
+        convert_to_bigints_into(
+            &randomness.blinding_polynomial.coeffs(),
+            &mut scratch.bigints,
+            threshold,
+        );
+        commitment += &multi_scalar_mul(&powers.powers_of_gamma_g, &scratch.bigints, threshold);
 
         end_timer!(commit_time);
         Ok((Commitment(commitment.into()), randomness))
     }
 
+    /// Outputs a (non-hiding) commitment to the polynomial whose coefficients,
+    /// from lowest to highest degree, are given by `reprs`, without converting
+    /// them to and from field elements.
+    ///
+    /// Each entry must be the canonical representation of its coefficient, as
+    /// returned by `PrimeField::into_repr`, and not the Montgomery form that
+    /// field elements use internally; `Error::InvalidFieldElement` is returned
+    /// if an entry is not less than the modulus.
+    pub fn commit_from_reprs(
+        powers: &Powers<E>,
+        reprs: &[<E::Fr as PrimeField>::BigInt],
+    ) -> Result<Commitment<E>, Error> {
+        Self::check_degree_is_too_large(reprs.len(), powers.size())?;
+        let modulus = <E::Fr as PrimeField>::Params::MODULUS;
+        if reprs.iter().any(|r| *r >= modulus) {
+            return Err(Error::InvalidFieldElement);
+        }
+
+        let commit_time = start_timer!(|| format!(
+            "Committing to polynomial of degree {} from reprs",
+            reprs.len().saturating_sub(1)
+        ));
+        let commitment = multi_scalar_mul(&powers.powers_of_g, reprs, powers.parallel_threshold);
+        end_timer!(commit_time);
+        Ok(Commitment(commitment.into()))
+    }
+
+    /// Outputs a (non-hiding) commitment to `x^k * p(x)`.
+    ///
+    /// Instead of materializing the shifted polynomial, the coefficients of
+    /// `p` are aligned against `powers[k..]`.
+    pub fn commit_shifted_by(powers: &Powers<E>, p: &P, k: usize) -> Result<Commitment<E>, Error> {
+        Self::check_degree_is_too_large(p.degree() + k + 1, powers.size())?;
+        if p.is_zero() {
+            return Ok(Commitment::empty());
+        }
+
+        let commit_time = start_timer!(|| format!(
+            "Committing to polynomial of degree {} shifted by x^{}",
+            p.degree(),
+            k
+        ));
+        let (num_leading_zeros, plain_coeffs) =
+            skip_leading_zeros_and_convert_to_bigints(p, powers.parallel_threshold);
+        let commitment = multi_scalar_mul(
+            &powers.powers_of_g[k + num_leading_zeros..],
+            &plain_coeffs,
+            powers.parallel_threshold,
+        );
+        end_timer!(commit_time);
+        Ok(Commitment(commitment.into()))
+    }
+
+    /// Outputs a (non-hiding) commitment to the polynomial `p` of degree less
+    /// than `domain.size()` whose evaluations over the coset `offset * H` are
+    /// `evals`, where `H` is `domain`; i.e., `evals[i] = p(offset * \omega^i)`.
+    ///
+    /// An inverse FFT over `domain` yields the coefficients of `p(offset * x)`,
+    /// and the `i`-th of these is divided by `offset^i` to recover `p`. Fails
+    /// with `Error::ZeroCosetOffset` if `offset` is zero.
+    pub fn commit_from_coset_evaluations<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        domain: &D,
+        offset: E::Fr,
+        evals: &[E::Fr],
+    ) -> Result<Commitment<E>, Error> {
+        if evals.len() != domain.size() {
+            return Err(Error::IncorrectInputLength(format!(
+                "expected {} coset evaluations, but got {}",
+                domain.size(),
+                evals.len()
+            )));
+        }
+        let offset_inv = offset.inverse().ok_or(Error::ZeroCosetOffset)?;
+        let interpolate_time = start_timer!(|| "Interpolating coset evaluations");
+        let mut coeffs = domain.ifft(evals);
+        let mut offset_inv_power = E::Fr::one();
+        for coeff in &mut coeffs {
+            *coeff *= &offset_inv_power;
+            offset_inv_power *= &offset_inv;
+        }
+        let p = P::from_coefficients_vec(coeffs);
+        end_timer!(interpolate_time);
+
+        Self::commit_shifted_by(powers, &p, 0)
+    }
+
+    /// Outputs a (non-hiding) commitment to the polynomial of degree less than
+    /// `domain.size()` whose evaluations over `domain` are `evals`, given the
+    /// Lagrange basis over `domain` output by `Powers::to_lagrange_basis`.
+    ///
+    /// Unlike `commit_from_coset_evaluations`, no interpolation is needed: the
+    /// commitment is a single multi-scalar multiplication of `evals` against
+    /// `lagrange_basis`.
+    pub fn commit_lagrange(
+        lagrange_basis: &[E::G1Affine],
+        evals: &[E::Fr],
+    ) -> Result<Commitment<E>, Error> {
+        if evals.len() != lagrange_basis.len() {
+            return Err(Error::IncorrectInputLength(format!(
+                "expected {} evaluations, but got {}",
+                lagrange_basis.len(),
+                evals.len()
+            )));
+        }
+        let commit_time = start_timer!(|| format!(
+            "Committing to {} evaluations in the Lagrange basis",
+            evals.len()
+        ));
+        let evals = convert_to_bigints(evals, DEFAULT_PARALLEL_THRESHOLD);
+        let commitment = multi_scalar_mul(lagrange_basis, &evals, DEFAULT_PARALLEL_THRESHOLD);
+        end_timer!(commit_time);
+        Ok(Commitment(commitment.into()))
+    }
+
+    /// Outputs (non-hiding) commitments to every prefix of `coeffs`, that is,
+    /// the `k`-th commitment commits to the polynomial with coefficients
+    /// `coeffs[..=k]`.
+    ///
+    /// The commitments are accumulated as running sums in a single pass, and
+    /// are normalized to affine form together at the end.
+    pub fn commit_prefixes(
+        powers: &Powers<E>,
+        coeffs: &[E::Fr],
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        Self::check_degree_is_too_large(coeffs.len(), powers.size())?;
+        let commit_time =
+            start_timer!(|| format!("Committing to {} polynomial prefixes", coeffs.len()));
+
+        let mut prefix_comm = E::G1Projective::zero();
+        let prefix_comms = coeffs
+            .iter()
+            .zip(powers.powers_of_g.iter())
+            .map(|(coeff, power)| {
+                prefix_comm += &power.mul(coeff.into_repr());
+                prefix_comm
+            })
+            .collect::<Vec<_>>();
+        let prefix_comms = E::G1Projective::batch_normalization_into_affine(&prefix_comms)
+            .into_iter()
+            .map(Commitment)
+            .collect();
+
+        end_timer!(commit_time);
+        Ok(prefix_comms)
+    }
+
     /// Compute witness polynomial.
     ///
     /// The witness polynomial w(x) the quotient of the division (p(x) - p(z)) / (x - z)
@@ -234,13 +462,16 @@ where
         hiding_witness_polynomial: Option<&P>,
     ) -> Result<Proof<E>, Error> {
         Self::check_degree_is_too_large(witness_polynomial.degree(), powers.size())?;
-        let (num_leading_zeros, witness_coeffs) =
-            skip_leading_zeros_and_convert_to_bigints(witness_polynomial);
+        let (num_leading_zeros, witness_coeffs) = skip_leading_zeros_and_convert_to_bigints(
+            witness_polynomial,
+            powers.parallel_threshold,
+        );
 
         let witness_comm_time = start_timer!(|| "Computing commitment to witness polynomial");
-        let mut w = VariableBaseMSM::multi_scalar_mul(
+        let mut w = multi_scalar_mul(
             &powers.powers_of_g[num_leading_zeros..],
             &witness_coeffs,
+            powers.parallel_threshold,
         );
         end_timer!(witness_comm_time);
 
@@ -250,12 +481,16 @@ where
             let blinding_evaluation = blinding_p.evaluate(&point);
             end_timer!(blinding_eval_time);
 
-            let random_witness_coeffs = convert_to_bigints(&hiding_witness_polynomial.coeffs());
+            let random_witness_coeffs = convert_to_bigints(
+                &hiding_witness_polynomial.coeffs(),
+                powers.parallel_threshold,
+            );
             let witness_comm_time =
                 start_timer!(|| "Computing commitment to random witness polynomial");
-            w += &VariableBaseMSM::multi_scalar_mul(
+            w += &multi_scalar_mul(
                 &powers.powers_of_gamma_g,
                 &random_witness_coeffs,
+                powers.parallel_threshold,
             );
             end_timer!(witness_comm_time);
             Some(blinding_evaluation)
@@ -269,13 +504,75 @@ where
         })
     }
 
+    /// Outputs a proof for the evaluation of `p` at the domain point
+    /// `\omega^index` of `domain`, which `check` verifies as a proof produced by
+    /// `open` at that point.
+    ///
+    /// As the divisor `x - \omega^index` is monic and linear, the witness
+    /// polynomials are computed by synthetic division rather than by generic
+    /// polynomial division. Both take linear time, so the cost of either
+    /// opening is dominated by the multi-scalar multiplication that commits to
+    /// the witness.
+    pub fn open_at_domain_point<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        p: &P,
+        domain: &D,
+        index: usize,
+        rand: &Randomness<E::Fr, P>,
+    ) -> Result<Proof<E>, Error> {
+        if powers.is_empty() {
+            return Err(Error::EmptyPowers);
+        }
+        if index >= domain.size() {
+            return Err(Error::IncorrectInputLength(format!(
+                "index {} is out of range for a domain of size {}",
+                index,
+                domain.size()
+            )));
+        }
+        Self::check_degree_is_within_bounds(p.degree(), powers.size())?;
+        let open_time = start_timer!(|| format!(
+            "Opening polynomial of degree {} at domain point {}",
+            p.degree(),
+            index
+        ));
+        let point = domain.element(index);
+
+        let witness_time = start_timer!(|| "Computing witness polynomials");
+        let witness_poly = P::from_coefficients_vec(divide_by_linear(p.coeffs(), point));
+        let hiding_witness_poly = if rand.is_hiding() {
+            let random_p = &rand.blinding_polynomial;
+            Some(P::from_coefficients_vec(divide_by_linear(
+                random_p.coeffs(),
+                point,
+            )))
+        } else {
+            None
+        };
+        end_timer!(witness_time);
+
+        let proof = Self::open_with_witness_polynomial(
+            powers,
+            point,
+            rand,
+            &witness_poly,
+            hiding_witness_poly.as_ref(),
+        );
+        end_timer!(open_time);
+        proof
+    }
+
     /// On input a polynomial `p` and a point `point`, outputs a proof for the same.
-    pub(crate) fn open<'a>(
+    /// Fails with `Error::EmptyPowers` if `powers` is empty.
+    pub fn open(
         powers: &Powers<E>,
         p: &P,
         point: P::Point,
         rand: &Randomness<E::Fr, P>,
     ) -> Result<Proof<E>, Error> {
+        if powers.is_empty() {
+            return Err(Error::EmptyPowers);
+        }
         Self::check_degree_is_within_bounds(p.degree(), powers.size())?;
         let open_time = start_timer!(|| format!("Opening polynomial of degree {}", p.degree()));
 
@@ -295,8 +592,151 @@ where
         proof
     }
 
+    /// Outputs a commitment to `polynomial` using `SharedPowers`; see `commit`.
+    #[cfg(feature = "std")]
+    pub fn commit_shared(
+        powers: &SharedPowers<E>,
+        polynomial: &P,
+        hiding_bound: Option<usize>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<(Commitment<E>, Randomness<E::Fr, P>), Error> {
+        Self::commit(&powers.powers(), polynomial, hiding_bound, rng)
+    }
+
+    /// On input a polynomial `p` and a point `point`, outputs a proof for the
+    /// same using `SharedPowers`.
+    #[cfg(feature = "std")]
+    pub fn open_shared(
+        powers: &SharedPowers<E>,
+        p: &P,
+        point: P::Point,
+        rand: &Randomness<E::Fr, P>,
+    ) -> Result<Proof<E>, Error> {
+        Self::open(&powers.powers(), p, point, rand)
+    }
+
+    /// Outputs a commitment to `polynomial`, as `commit` does, reusing the
+    /// commitment in `cache` if the same polynomial was committed to before.
+    ///
+    /// Hiding commitments are randomized, so if `hiding_bound` is `Some`, the
+    /// cache is neither consulted nor updated.
+    #[cfg(feature = "std")]
+    pub fn commit_cached(
+        powers: &Powers<E>,
+        polynomial: &P,
+        hiding_bound: Option<usize>,
+        rng: Option<&mut dyn RngCore>,
+        cache: &mut CommitCache<E>,
+    ) -> Result<(Commitment<E>, Randomness<E::Fr, P>), Error> {
+        if hiding_bound.is_some() {
+            return Self::commit(powers, polynomial, hiding_bound, rng);
+        }
+        if let Some(comm) = cache.get(polynomial.coeffs()) {
+            return Ok((comm, Randomness::empty()));
+        }
+        let (comm, rand) = Self::commit(powers, polynomial, None, rng)?;
+        cache.insert(polynomial.coeffs(), comm);
+        Ok((comm, rand))
+    }
+
+    /// Outputs a proof for `p` at `point`, as `open` does, but computes the
+    /// witness polynomials in the buffers of `scratch` instead of allocating.
+    pub fn open_with_scratch(
+        powers: &Powers<E>,
+        p: &P,
+        point: P::Point,
+        rand: &Randomness<E::Fr, P>,
+        scratch: &mut Scratch<E>,
+    ) -> Result<Proof<E>, Error> {
+        if powers.is_empty() {
+            return Err(Error::EmptyPowers);
+        }
+        Self::check_degree_is_within_bounds(p.degree(), powers.size())?;
+        let open_time =
+            start_timer!(|| format!("Opening polynomial of degree {} with scratch", p.degree()));
+
+        let threshold = powers.parallel_threshold;
+        divide_by_linear_into(&p.coeffs(), point, &mut scratch.witness_coeffs);
+        convert_to_bigints_into(&scratch.witness_coeffs, &mut scratch.bigints, threshold);
+        let mut w = multi_scalar_mul(&powers.powers_of_g, &scratch.bigints, threshold);
+
+        let random_v = if rand.is_hiding() {
+            let blinding_p = &rand.blinding_polynomial;
+            divide_by_linear_into(&blinding_p.coeffs(), point, &mut scratch.witness_coeffs);
+            convert_to_bigints_into(&scratch.witness_coeffs, &mut scratch.bigints, threshold);
+            w += &multi_scalar_mul(&powers.powers_of_gamma_g, &scratch.bigints, threshold);
+            Some(blinding_p.evaluate(&point))
+        } else {
+            None
+        };
+
+        end_timer!(open_time);
+        Ok(Proof {
+            w: w.into_affine(),
+            random_v,
+        })
+    }
+
+    /// Outputs a proof that the polynomial `p` committed in `comm` evaluates to
+    /// `p(point)` at `point`, bound to `context_tag`.
+    ///
+    /// This is domain separation rather than a new assumption: the tag, `comm`,
+    /// `point` and the evaluation are hashed with `D` to derive a second point,
+    /// and `p` is opened there too. A proof for one tag therefore cannot be
+    /// replayed under another without opening `p` at a fresh point, which is
+    /// as hard as producing an evaluation proof in the first place.
+    pub fn open_bound<D: Digest>(
+        powers: &Powers<E>,
+        p: &P,
+        comm: &Commitment<E>,
+        point: E::Fr,
+        context_tag: &[u8],
+        rand: &Randomness<E::Fr, P>,
+    ) -> Result<BoundProof<E>, Error> {
+        let value = p.evaluate(&point);
+        let proof = Self::open(powers, p, point, rand)?;
+        let tag_point = Self::compute_tag_point::<D>(context_tag, comm, point, value);
+        let tag_value = p.evaluate(&tag_point);
+        let tag_proof = Self::open(powers, p, tag_point, rand)?;
+        Ok(BoundProof {
+            proof,
+            tag_value,
+            tag_proof,
+        })
+    }
+
+    /// Verifies that `value` is the evaluation at `point` of the polynomial
+    /// committed inside `comm`, and that `proof` was produced by `open_bound`
+    /// for the same `context_tag`.
+    pub fn check_bound<D: Digest>(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        context_tag: &[u8],
+        proof: &BoundProof<E>,
+    ) -> Result<bool, Error> {
+        let tag_point = Self::compute_tag_point::<D>(context_tag, comm, point, value);
+        Ok(Self::check(vk, comm, point, value, &proof.proof)?
+            && Self::check(vk, comm, tag_point, proof.tag_value, &proof.tag_proof)?)
+    }
+
+    /// Hashes `context_tag` and the claimed evaluation to a point.
+    fn compute_tag_point<D: Digest>(
+        context_tag: &[u8],
+        comm: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+    ) -> E::Fr {
+        let bytes = ark_ff::to_bytes![comm, point, value].unwrap();
+        hash_to_field::<D, _>(context_tag, &bytes)
+    }
+
     /// Verifies that `value` is the evaluation at `point` of the polynomial
     /// committed inside `comm`.
+    ///
+    /// The group elements of `comm` and `proof` are validated before any
+    /// pairing is computed; see `check_with_validation`.
     pub fn check(
         vk: &VerifierKey<E>,
         comm: &Commitment<E>,
@@ -304,6 +744,28 @@ where
         value: E::Fr,
         proof: &Proof<E>,
     ) -> Result<bool, Error> {
+        Self::check_with_validation(vk, comm, point, value, proof, true)
+    }
+
+    /// Verifies that `value` is the evaluation at `point` of the polynomial
+    /// committed inside `comm`.
+    ///
+    /// If `validate_inputs` is set, the group elements of `comm` and `proof`
+    /// are first checked to lie on the curve and in the prime-order subgroup,
+    /// and `Error::InvalidGroupElement` is returned otherwise. This should
+    /// only be disabled when the inputs are known to be well-formed.
+    pub fn check_with_validation(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+        validate_inputs: bool,
+    ) -> Result<bool, Error> {
+        if validate_inputs && !(is_valid_group_element(&comm.0) && is_valid_group_element(&proof.w))
+        {
+            return Err(Error::InvalidGroupElement);
+        }
         let check_time = start_timer!(|| "Checking evaluation");
         let mut inner = comm.0.into_projective() - &vk.g.into_projective().mul(value);
         if let Some(random_v) = proof.random_v {
@@ -318,231 +780,3463 @@ where
         Ok(lhs == rhs)
     }
 
-    /// Check that each `proof_i` in `proofs` is a valid proof of evaluation for
-    /// `commitment_i` at `point_i`.
-    pub fn batch_check<R: RngCore>(
-        vk: &VerifierKey<E>,
-        commitments: &[Commitment<E>],
-        points: &[E::Fr],
-        values: &[E::Fr],
-        proofs: &[Proof<E>],
-        rng: &mut R,
+    /// Verifies that `value` is the evaluation at `point` of the polynomial
+    /// committed inside `comm`, as `check` does, using a verifier key that has
+    /// already been prepared.
+    ///
+    /// Preparing a verifier key computes `E::Fr::size_in_bits()` doublings of
+    /// `g`, so when many proofs are checked against the same key,
+    /// `PreparedVerifierKey::prepare` should be called once, outside the loop.
+    /// The group elements of `comm` and `proof` are validated as in `check`.
+    /// Since `PreparedVerifierKey` omits `gamma_g`, hiding proofs are rejected
+    /// with `Error::HidingProofUnsupported`.
+    pub fn check_prepared(
+        prepared_vk: &PreparedVerifierKey<E>,
+        comm: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
     ) -> Result<bool, Error> {
-        let check_time =
-            start_timer!(|| format!("Checking {} evaluation proofs", commitments.len()));
-        let g = vk.g.into_projective();
-        let gamma_g = vk.gamma_g.into_projective();
+        if !is_valid_group_element(&comm.0) {
+            return Err(Error::InvalidGroupElement);
+        }
+        Self::check_prepared_inner(prepared_vk, &comm.0, point, value, proof)
+    }
 
-        let mut total_c = <E::G1Projective>::zero();
-        let mut total_w = <E::G1Projective>::zero();
+    /// Verifies that `value` is the evaluation at `point` of the polynomial
+    /// committed inside `prepared_comm`, as `check_prepared` does, for repeated
+    /// checks against the same verifier key and commitment.
+    ///
+    /// `value * g` is computed from the doublings of `g` in `prepared_vk`, and
+    /// the check is rearranged as `e(comm - value * g + point * w, h) = e(w, \beta h)`
+    /// so that both pairings use the prepared G2 elements. The commitment is
+    /// taken from the first entry of `prepared_comm`, and is validated as in
+    /// `check_prepared`, as is `proof.w`; an empty `prepared_comm` is rejected
+    /// with `Error::InvalidGroupElement`. Since `PreparedVerifierKey` omits
+    /// `gamma_g`, hiding proofs are rejected with `Error::HidingProofUnsupported`.
+    pub fn check_fully_prepared(
+        prepared_vk: &PreparedVerifierKey<E>,
+        prepared_comm: &PreparedCommitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let comm = match prepared_comm.0.first() {
+            Some(comm) if is_valid_group_element(comm) => comm,
+            _ => return Err(Error::InvalidGroupElement),
+        };
+        Self::check_prepared_inner(prepared_vk, comm, point, value, proof)
+    }
 
-        let combination_time = start_timer!(|| "Combining commitments and proofs");
-        let mut randomizer = E::Fr::one();
-        // Instead of multiplying g and gamma_g in each turn, we simply accumulate
-        // their coefficients and perform a final multiplication at the end.
-        let mut g_multiplier = E::Fr::zero();
-        let mut gamma_g_multiplier = E::Fr::zero();
-        for (((c, z), v), proof) in commitments.iter().zip(points).zip(values).zip(proofs) {
-            let w = proof.w;
-            let mut temp = w.mul(*z);
-            temp.add_assign_mixed(&c.0);
-            let c = temp;
-            g_multiplier += &(randomizer * v);
-            if let Some(random_v) = proof.random_v {
-                gamma_g_multiplier += &(randomizer * &random_v);
-            }
-            total_c += &c.mul(randomizer);
-            total_w += &w.mul(randomizer);
-            // We don't need to sample randomizers from the full field,
-            // only from 128-bit strings.
-            randomizer = u128::rand(rng).into();
+    fn check_prepared_inner(
+        prepared_vk: &PreparedVerifierKey<E>,
+        comm: &E::G1Affine,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        if proof.random_v.is_some() {
+            return Err(Error::HidingProofUnsupported);
         }
-        total_c -= &g.mul(g_multiplier);
-        total_c -= &gamma_g.mul(gamma_g_multiplier);
+        if !is_valid_group_element(&proof.w) {
+            return Err(Error::InvalidGroupElement);
+        }
+        let check_time = start_timer!(|| "Checking evaluation with prepared inputs");
+        let value_g = mul_by_doublings(&prepared_vk.prepared_g, value);
+        let inner = comm.into_projective() - &value_g + &proof.w.mul(point);
+        let result = E::product_of_pairings(&[
+            (inner.into_affine().into(), prepared_vk.prepared_h.clone()),
+            ((-proof.w).into(), prepared_vk.prepared_beta_h.clone()),
+        ])
+        .is_one();
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Verifies `proof` as in `check`, but also returns the inputs to the
+    /// pairings and, if the check fails, whether the hiding or the binding part
+    /// of the equation is at fault.
+    ///
+    /// A wrong `value` and a wrong `random_v` both shift the left-hand side by
+    /// a multiple of a fixed element of G1, so a verifier cannot tell them
+    /// apart. Hence this also takes the randomness `rand` that was used to
+    /// commit, and compares `proof.random_v` against the evaluation of its
+    /// blinding polynomial at `point`. It is a debugging aid for the holder of
+    /// `rand`, not a replacement for `check`.
+    pub fn check_hiding_detailed(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+        rand: &Randomness<E::Fr, P>,
+    ) -> Result<HidingCheckDetails<E>, Error> {
+        if !(is_valid_group_element(&comm.0) && is_valid_group_element(&proof.w)) {
+            return Err(Error::InvalidGroupElement);
+        }
+        let check_time = start_timer!(|| "Checking evaluation with details");
+        let evaluation_term = vk.g.mul(value);
+        let hiding_term = proof
+            .random_v
+            .map_or(E::G1Projective::zero(), |random_v| vk.gamma_g.mul(random_v));
+        let lhs_g1 = comm.0.into_projective() - &evaluation_term - &hiding_term;
+        let rhs_g2 = vk.beta_h.into_projective() - &vk.h.mul(point);
+        let lhs = E::pairing(lhs_g1, vk.h);
+        let rhs = E::pairing(proof.w, rhs_g2);
+
+        let failure = if lhs == rhs {
+            None
+        } else {
+            let expected_random_v = if rand.is_hiding() {
+                Some(rand.blinding_polynomial.evaluate(&point))
+            } else {
+                None
+            };
+            if proof.random_v != expected_random_v {
+                Some(HidingCheckFailure::HidingTerm)
+            } else {
+                Some(HidingCheckFailure::BindingTerm)
+            }
+        };
+        end_timer!(check_time, || format!("Failure: {:?}", failure));
+
+        Ok(HidingCheckDetails {
+            evaluation_term: evaluation_term.into_affine(),
+            hiding_term: hiding_term.into_affine(),
+            lhs_g1: lhs_g1.into_affine(),
+            rhs_g1: proof.w,
+            rhs_g2: rhs_g2.into_affine(),
+            failure,
+        })
+    }
+
+    /// Checks that `comm2` commits to `c p1`, where `p1` is the polynomial
+    /// committed in `comm1`, for a public scalar `c`.
+    ///
+    /// As commitments are linear, this holds exactly when `comm2 = c comm1`
+    /// (for non-hiding commitments), and no opening is needed. When `c` must
+    /// stay hidden, use `prove_scalar_multiple` instead.
+    pub fn check_public_scalar_multiple(
+        comm1: &Commitment<E>,
+        comm2: &Commitment<E>,
+        c: E::Fr,
+    ) -> bool {
+        comm1.0.mul(c).into_affine() == comm2.0
+    }
+
+    /// Outputs a proof that `p2 = c p1`, where `p1` is committed with
+    /// randomness `rand_p1` and `p2` with `rand_p2`, without revealing `c`.
+    ///
+    /// The prover commits to `c`, as a constant polynomial, with a hiding
+    /// commitment `C_c`, and derives a point `x` by hashing `challenge` and
+    /// `C_c` with `D`; `challenge` should bind the commitments to `p1` and
+    /// `p2`. It then reveals `v = p1(x)` with an opening of `p1`, and opens the
+    /// linearized polynomial `p2 - v c` at `x` to zero. Its commitment
+    /// `comm2 - v C_c` can be computed by the verifier, so the two openings
+    /// show `p2(x) = c p1(x)` at a random point, and thus `p2 = c p1`.
+    pub fn prove_scalar_multiple<D: Digest>(
+        powers: &Powers<E>,
+        p1: &P,
+        rand_p1: &Randomness<E::Fr, P>,
+        rand_p2: &Randomness<E::Fr, P>,
+        c: E::Fr,
+        challenge: E::Fr,
+        rng: &mut dyn RngCore,
+    ) -> Result<ScalarMultipleProof<E>, Error> {
+        let prove_time = start_timer!(|| "Proving scalar multiple");
+        let scalar_poly = P::from_coefficients_vec(vec![c]);
+        let (scalar_comm, scalar_rand) = Self::commit(powers, &scalar_poly, Some(1), Some(rng))?;
+        let point = Self::compute_scalar_multiple_point::<D>(challenge, &scalar_comm);
+
+        let p1_value = p1.evaluate(&point);
+        let p1_proof = Self::open(powers, p1, point, rand_p1)?;
+
+        let mut linearized = P::zero();
+        linearized += (c, p1);
+        linearized += (-p1_value, &scalar_poly);
+        let mut linearized_rand = rand_p2.clone();
+        linearized_rand += (-p1_value, &scalar_rand);
+        let linearized_proof = Self::open(powers, &linearized, point, &linearized_rand)?;
+        end_timer!(prove_time);
+
+        Ok(ScalarMultipleProof {
+            scalar_comm,
+            p1_value,
+            p1_proof,
+            linearized_proof,
+        })
+    }
+
+    /// Verifies a proof, output by `prove_scalar_multiple`, that the
+    /// polynomial committed in `comm2` is a multiple, by a hidden scalar, of
+    /// the polynomial committed in `comm1`.
+    pub fn check_scalar_multiple<D: Digest>(
+        vk: &VerifierKey<E>,
+        comm1: &Commitment<E>,
+        comm2: &Commitment<E>,
+        challenge: E::Fr,
+        proof: &ScalarMultipleProof<E>,
+    ) -> Result<bool, Error> {
+        let check_time = start_timer!(|| "Checking scalar multiple");
+        let point = Self::compute_scalar_multiple_point::<D>(challenge, &proof.scalar_comm);
+        let linearized_comm = comm2.0.into_projective() - &proof.scalar_comm.0.mul(proof.p1_value);
+        let result = Self::check(vk, comm1, point, proof.p1_value, &proof.p1_proof)?
+            && Self::check(
+                vk,
+                &Commitment(linearized_comm.into_affine()),
+                point,
+                E::Fr::zero(),
+                &proof.linearized_proof,
+            )?;
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Hashes the challenge and the commitment to the scalar to the point at
+    /// which `prove_scalar_multiple` opens.
+    fn compute_scalar_multiple_point<D: Digest>(
+        challenge: E::Fr,
+        scalar_comm: &Commitment<E>,
+    ) -> E::Fr {
+        let bytes = ark_ff::to_bytes![challenge, scalar_comm].unwrap();
+        hash_to_field::<D, _>(b"KZG10::prove_scalar_multiple", &bytes)
+    }
+
+    /// Verifies that the polynomial committed in `comm1` evaluates at `z` to
+    /// the same value `v` as the polynomial committed in `comm2` at `z_prime`,
+    /// where `v` is given only through `value_comm = v G`.
+    ///
+    /// `proof1` and `proof2` are ordinary proofs output by `open`; this checks
+    /// that both `comm1 - value_comm` and `comm2 - value_comm` open to zero, at
+    /// `z` and `z_prime` respectively. Checking only that `comm1 - comm2`
+    /// satisfies the combined equation would not suffice: a prover can choose
+    /// the two witnesses to satisfy it for any pair of polynomials, so a
+    /// commitment binding the common value is necessary.
+    ///
+    /// `v G` hides `v` only if `v` is unpredictable; `open_nested` hides it
+    /// unconditionally, using hiding commitments.
+    pub fn check_linked(
+        vk: &VerifierKey<E>,
+        comm1: &Commitment<E>,
+        z: E::Fr,
+        proof1: &Proof<E>,
+        comm2: &Commitment<E>,
+        z_prime: E::Fr,
+        proof2: &Proof<E>,
+        value_comm: &E::G1Affine,
+    ) -> Result<bool, Error> {
+        if !is_valid_group_element(value_comm) {
+            return Err(Error::InvalidGroupElement);
+        }
+        let check_time = start_timer!(|| "Checking linked evaluations");
+        let value_comm = value_comm.into_projective();
+        let mut result = true;
+        for (comm, point, proof) in [(comm1, z, proof1), (comm2, z_prime, proof2)].iter() {
+            let shifted = Commitment((comm.0.into_projective() - &value_comm).into_affine());
+            result &= Self::check(vk, &shifted, *point, E::Fr::zero(), proof)?;
+        }
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Proves that `p(z)` equals `values_poly(index_point)`, where, e.g.,
+    /// `values_poly` interpolates a vector of values over a domain and
+    /// `index_point` is the `i`-th element of that domain, without revealing
+    /// the common value `v`.
+    ///
+    /// Each of the two openings reveals, instead of its evaluation, a hiding
+    /// commitment to it that uses the evaluation of the blinding polynomial as
+    /// randomness. The proof contains the commitment `v G + p_r(z) \gamma G`
+    /// from the opening of `p`, and the difference between the two blinding
+    /// evaluations, from which the verifier derives the commitment
+    /// `v G + V_r(x_i) \gamma G` for the opening of `values_poly`; see
+    /// `check_nested`. Since the two evaluations are revealed only through
+    /// their difference, `v` stays hidden as long as both commitments are hiding.
+    pub fn open_nested(
+        powers: &Powers<E>,
+        p: &P,
+        rand_p: &Randomness<E::Fr, P>,
+        z: E::Fr,
+        values_poly: &P,
+        rand_values: &Randomness<E::Fr, P>,
+        index_point: E::Fr,
+    ) -> Result<NestedProof<E>, Error> {
+        let proof_p = Self::open(powers, p, z, rand_p)?;
+        let proof_values = Self::open(powers, values_poly, index_point, rand_values)?;
+        let r_p = proof_p.random_v.unwrap_or_else(E::Fr::zero);
+        let r_values = proof_values.random_v.unwrap_or_else(E::Fr::zero);
+
+        let mut value_comm = powers.powers_of_g[0].mul(p.evaluate(&z));
+        if let Some(gamma_g) = powers.powers_of_gamma_g.first() {
+            value_comm += &gamma_g.mul(r_p);
+        }
+        Ok(NestedProof {
+            value_comm: value_comm.into_affine(),
+            w_p: proof_p.w,
+            w_values: proof_values.w,
+            randomness_diff: r_p - &r_values,
+        })
+    }
+
+    /// Checks the output of `open_nested`: `comm_p` must open at `z` to the
+    /// value committed in `proof.value_comm`, and `comm_values` must open at
+    /// `index_point` to the same value.
+    ///
+    /// With `D = proof.value_comm`, this checks `e(comm_p - D, h) = e(w_p, \beta h - z h)`
+    /// and `e(comm_values - D + randomness_diff \gamma G, h) = e(w_values, \beta h - x_i h)`.
+    /// Both equations are those of `check` with the evaluation term replaced by
+    /// a commitment to it, and `D` binds both openings to the same value.
+    pub fn check_nested(
+        vk: &VerifierKey<E>,
+        comm_p: &Commitment<E>,
+        z: E::Fr,
+        comm_values: &Commitment<E>,
+        index_point: E::Fr,
+        proof: &NestedProof<E>,
+    ) -> Result<bool, Error> {
+        if ![
+            comm_p.0,
+            comm_values.0,
+            proof.value_comm,
+            proof.w_p,
+            proof.w_values,
+        ]
+        .iter()
+        .all(is_valid_group_element)
+        {
+            return Err(Error::InvalidGroupElement);
+        }
+        let check_time = start_timer!(|| "Checking nested evaluation");
+        let value_comm = proof.value_comm.into_projective();
+        let values_value_comm = value_comm - &vk.gamma_g.mul(proof.randomness_diff);
+
+        // e(comm - D + x w, h) = e(w, \beta h), for each of the two openings.
+        let mut result = true;
+        for (comm, d, x, w) in [
+            (comm_p.0, value_comm, z, proof.w_p),
+            (
+                comm_values.0,
+                values_value_comm,
+                index_point,
+                proof.w_values,
+            ),
+        ]
+        .iter()
+        {
+            let inner = comm.into_projective() - d + &w.mul(*x);
+            result &= E::product_of_pairings(&[
+                (inner.into_affine().into(), vk.prepared_h.clone()),
+                ((-*w).into(), vk.prepared_beta_h.clone()),
+            ])
+            .is_one();
+        }
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Proves that two commitments, made to `p` with `rand_a` under `powers_a`
+    /// and with `rand_b` under `powers_b`, commit to the same polynomial, by
+    /// opening both at `challenge`. Outputs both proofs and the common value.
+    ///
+    /// This is only sound if `challenge` is sampled after both commitments are
+    /// fixed (e.g., by hashing them), since two distinct polynomials of degree
+    /// at most `d` agree on at most `d` points.
+    pub fn prove_consistent_commitments(
+        powers_a: &Powers<E>,
+        powers_b: &Powers<E>,
+        p: &P,
+        rand_a: &Randomness<E::Fr, P>,
+        rand_b: &Randomness<E::Fr, P>,
+        challenge: E::Fr,
+    ) -> Result<(Proof<E>, Proof<E>, E::Fr), Error> {
+        let proof_a = Self::open(powers_a, p, challenge, rand_a)?;
+        let proof_b = Self::open(powers_b, p, challenge, rand_b)?;
+        Ok((proof_a, proof_b, p.evaluate(&challenge)))
+    }
+
+    /// Checks the output of `prove_consistent_commitments`: both `comm_a` and
+    /// `comm_b` must open to `value` at `challenge`, under `vk_a` and `vk_b`
+    /// respectively. See `prove_consistent_commitments` for how `challenge`
+    /// must be chosen.
+    pub fn check_consistent_commitments(
+        vk_a: &VerifierKey<E>,
+        vk_b: &VerifierKey<E>,
+        comm_a: &Commitment<E>,
+        comm_b: &Commitment<E>,
+        challenge: E::Fr,
+        value: E::Fr,
+        proof_a: &Proof<E>,
+        proof_b: &Proof<E>,
+    ) -> Result<bool, Error> {
+        Ok(Self::check(vk_a, comm_a, challenge, value, proof_a)?
+            && Self::check(vk_b, comm_b, challenge, value, proof_b)?)
+    }
+
+    /// Verifies the openings of `comm` at `z` and at `z * omega`, e.g. for a
+    /// permutation argument that relates the evaluations of a polynomial at
+    /// consecutive elements of a domain generated by `omega`.
+    ///
+    /// If both openings verify, returns `Some((value_at_z, value_at_zw))`, on
+    /// which the caller should then check the protocol-specific relation.
+    /// Otherwise, returns `None`.
+    pub fn check_with_shift(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        z: E::Fr,
+        omega: E::Fr,
+        value_at_z: E::Fr,
+        value_at_zw: E::Fr,
+        proof_z: &Proof<E>,
+        proof_zw: &Proof<E>,
+    ) -> Result<Option<(E::Fr, E::Fr)>, Error> {
+        let verified = Self::check(vk, comm, z, value_at_z, proof_z)?
+            && Self::check(vk, comm, z * &omega, value_at_zw, proof_zw)?;
+        Ok(if verified {
+            Some((value_at_z, value_at_zw))
+        } else {
+            None
+        })
+    }
+
+    /// Evaluates `p` at the `num_rotations` points `z, \omega z, ...,
+    /// \omega^{num_rotations - 1} z`, and outputs the evaluations together
+    /// with a single proof for all of them.
+    ///
+    /// Following [[BDFG20]][bdfg], `p` is divided once by the vanishing
+    /// polynomial `Z` of the rotations, and the quotient `q` is committed.
+    /// The linearized polynomial `p - Z(x) q` is then opened at a point `x`
+    /// derived by hashing `challenge`, the commitment to `q` and the
+    /// evaluations with `D`, so the proof consists of two group elements
+    /// regardless of `num_rotations`. `challenge` should bind the commitment
+    /// to `p` and the transcript so far.
+    ///
+    /// [bdfg]: https://eprint.iacr.org/2020/081
+    pub fn open_rotations<D: Digest>(
+        powers: &Powers<E>,
+        p: &P,
+        z: E::Fr,
+        omega: E::Fr,
+        num_rotations: usize,
+        challenge: E::Fr,
+    ) -> Result<(Vec<E::Fr>, RotationsProof<E>), Error> {
+        let open_time = start_timer!(|| format!("Opening {} rotations", num_rotations));
+        let points = Self::rotation_points(z, omega, num_rotations)?;
+        let values = points
+            .iter()
+            .map(|point| p.evaluate(point))
+            .collect::<Vec<_>>();
+
+        let vanishing_poly = P::from_coefficients_vec(vanishing_poly_coeffs(&points));
+        let quotient = p / &vanishing_poly;
+        let (quotient_comm, _) = Self::commit(powers, &quotient, None, None)?;
+
+        let point =
+            Self::compute_rotations_point::<D>(challenge, &quotient_comm, z, omega, &values);
+        let mut linearized = p.clone();
+        linearized += (-vanishing_poly.evaluate(&point), &quotient);
+        let proof = Self::open(powers, &linearized, point, &Randomness::empty())?;
+        end_timer!(open_time);
+
+        Ok((
+            values,
+            RotationsProof {
+                quotient_comm,
+                proof,
+            },
+        ))
+    }
+
+    /// Verifies that `values` are the evaluations of the polynomial committed
+    /// in `comm` at `z, \omega z, ..., \omega^{n-1} z`, where `n` is the
+    /// number of `values`, given a proof output by `open_rotations`.
+    pub fn check_rotations<D: Digest>(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        z: E::Fr,
+        omega: E::Fr,
+        values: &[E::Fr],
+        challenge: E::Fr,
+        proof: &RotationsProof<E>,
+    ) -> Result<bool, Error> {
+        let check_time = start_timer!(|| format!("Checking {} rotations", values.len()));
+        let points = Self::rotation_points(z, omega, values.len())?;
+        let point =
+            Self::compute_rotations_point::<D>(challenge, &proof.quotient_comm, z, omega, values);
+
+        let vanishing_at_point = points
+            .iter()
+            .fold(E::Fr::one(), |acc, z_i| acc * &(point - z_i));
+        // The evaluation at `point` of the polynomial interpolating `values`.
+        let mut interpolated_value = E::Fr::zero();
+        for (i, (z_i, v_i)) in points.iter().zip(values).enumerate() {
+            if point == *z_i {
+                interpolated_value = *v_i;
+                break;
+            }
+            let denominator = points
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(point - z_i, |acc, (_, z_j)| acc * &(*z_i - z_j));
+            interpolated_value += &(vanishing_at_point * v_i * &denominator.inverse().unwrap());
+        }
+
+        let linearized = comm.0.into_projective() - &proof.quotient_comm.0.mul(vanishing_at_point);
+        let result = Self::check(
+            vk,
+            &Commitment(linearized.into_affine()),
+            point,
+            interpolated_value,
+            &proof.proof,
+        )?;
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Returns the points `z, \omega z, ..., \omega^{n-1} z`, checking that
+    /// they are distinct.
+    fn rotation_points(z: E::Fr, omega: E::Fr, n: usize) -> Result<Vec<E::Fr>, Error> {
+        if n == 0 {
+            return Err(Error::IncorrectInputLength(
+                "at least one rotation must be opened".to_string(),
+            ));
+        }
+        let mut points = Vec::with_capacity(n);
+        let mut cur = z;
+        for _ in 0..n {
+            points.push(cur);
+            cur *= &omega;
+        }
+        // The points repeat exactly when `z` is zero or the order of `omega`
+        // is smaller than `n`.
+        if n > 1 && z.is_zero() {
+            return Err(Error::RepeatedPoint);
+        }
+        let mut omega_pow = omega;
+        for _ in 1..n {
+            if omega_pow.is_one() {
+                return Err(Error::RepeatedPoint);
+            }
+            omega_pow *= &omega;
+        }
+        Ok(points)
+    }
+
+    /// Hashes the challenge, the commitment to the quotient and the claimed
+    /// evaluations to the point at which `open_rotations` opens.
+    fn compute_rotations_point<D: Digest>(
+        challenge: E::Fr,
+        quotient_comm: &Commitment<E>,
+        z: E::Fr,
+        omega: E::Fr,
+        values: &[E::Fr],
+    ) -> E::Fr {
+        let bytes = ark_ff::to_bytes![challenge, quotient_comm, z, omega, values].unwrap();
+        hash_to_field::<D, _>(b"KZG10::open_rotations", &bytes)
+    }
+
+    /// Opens `p` at all of `points` with a single proof, and outputs the proof
+    /// along with the evaluations of `p` at `points`.
+    ///
+    /// The witness is a commitment to `(p - I) / Z`, where `Z = \prod_i (X - z_i)`
+    /// is the vanishing polynomial of the points and `I` interpolates the
+    /// evaluations; see `check_at_multiple_points`. Hiding polynomials are not
+    /// supported, and `Error::HidingProofUnsupported` is returned for them.
+    pub fn open_at_multiple_points(
+        powers: &Powers<E>,
+        p: &LabeledPolynomial<E::Fr, P>,
+        points: &[E::Fr],
+    ) -> Result<(Proof<E>, Vec<E::Fr>), Error> {
+        let open_time = start_timer!(|| format!("Opening at {} points", points.len()));
+        if p.hiding_bound().is_some() {
+            return Err(Error::HidingProofUnsupported);
+        }
+        let values = points.iter().map(|z| p.evaluate(z)).collect::<Vec<_>>();
+        let interpolation = P::from_coefficients_vec(interpolate_coeffs(points, &values)?);
+        let vanishing_poly = P::from_coefficients_vec(vanishing_poly_coeffs(points));
+        let mut numerator = p.polynomial().clone();
+        numerator += (-E::Fr::one(), &interpolation);
+        let witness_poly = &numerator / &vanishing_poly;
+        let (w, _) = Self::commit(powers, &witness_poly, None, None)?;
+        end_timer!(open_time);
+
+        Ok((
+            Proof {
+                w: w.0,
+                random_v: None,
+            },
+            values,
+        ))
+    }
+
+    /// Verifies that `values` are the evaluations at `points` of the
+    /// polynomial committed in `comm`, given a proof output by
+    /// `open_at_multiple_points`.
+    ///
+    /// With `k` points, the check `p(\beta) - I(\beta) = w(\beta) Z(\beta)` is
+    /// scaled by `\beta^{-k}`, so that `I` (of degree less than `k`) and `Z`
+    /// (of degree `k`) are evaluated at `\beta` in G2 using only
+    /// `neg_powers_of_h[0..=k]`, the negative powers of `h` produced by
+    /// `setup` with `produce_g2_powers`. A single product of three pairings
+    /// is then checked.
+    pub fn check_at_multiple_points(
+        vk: &VerifierKey<E>,
+        neg_powers_of_h: &BTreeMap<usize, E::G2Affine>,
+        comm: &Commitment<E>,
+        points: &[E::Fr],
+        values: &[E::Fr],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let check_time =
+            start_timer!(|| format!("Checking evaluations at {} points", points.len()));
+        if proof.random_v.is_some() {
+            return Err(Error::HidingProofUnsupported);
+        }
+        if points.len() != values.len() {
+            return Err(Error::IncorrectInputLength(format!(
+                "{} points, but {} values",
+                points.len(),
+                values.len()
+            )));
+        }
+        let k = points.len();
+        let neg_powers = (0..=k)
+            .map(|i| neg_powers_of_h.get(&i).copied())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                Error::IncorrectInputLength(format!(
+                    "checking {} evaluations requires the first {} negative powers of h",
+                    k,
+                    k + 1
+                ))
+            })?;
+        // `\sum_j c_j \beta^{j - k} H` for the coefficients `c_j` of a
+        // polynomial of degree at most `k`.
+        let scaled_eval_in_g2 = |coeffs: &[E::Fr]| {
+            coeffs
+                .iter()
+                .enumerate()
+                .fold(E::G2Projective::zero(), |acc, (j, c)| {
+                    acc + &neg_powers[k - j].mul(*c)
+                })
+                .into_affine()
+        };
+        let interpolation_in_g2 = scaled_eval_in_g2(&interpolate_coeffs(points, values)?);
+        let vanishing_in_g2 = scaled_eval_in_g2(&vanishing_poly_coeffs(points));
+
+        let result = E::product_of_pairings(&[
+            (comm.0.into(), neg_powers[k].into()),
+            ((-vk.g).into(), interpolation_in_g2.into()),
+            ((-proof.w).into(), vanishing_in_g2.into()),
+        ])
+        .is_one();
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// A prover-side assertion that `proof`, which the caller has just produced,
+    /// verifies against `comm`, `point` and `value`. `vk` is the verifier key
+    /// derived from the same `UniversalParams` as the prover's `Powers`.
+    ///
+    /// This costs as much as a verification, but catches bugs in the
+    /// computation of the witness before a proof is published. Since the
+    /// inputs come from the prover itself, they are not validated as in `check`.
+    pub fn self_check(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+    ) -> bool {
+        Self::check_with_validation(vk, comm, point, value, proof, false).unwrap_or(false)
+    }
+
+    /// Like `check`, but takes the commitment in projective form, so that it
+    /// is normalized only once, together with the other terms of the
+    /// left-hand side of the verification equation.
+    ///
+    /// As in `check`, the group elements are validated before any pairing is
+    /// computed. For the commitment this is done on the normalized left-hand
+    /// side, which is in the prime-order subgroup if and only if `comm` is.
+    pub fn check_projective(
+        vk: &VerifierKey<E>,
+        comm: E::G1Projective,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let check_time = start_timer!(|| "Checking evaluation of projective commitment");
+        let mut inner = comm - &vk.g.mul(value);
+        if let Some(random_v) = proof.random_v {
+            inner -= &vk.gamma_g.mul(random_v);
+        }
+        let inner = inner.into_affine();
+        if !(is_valid_group_element(&inner) && is_valid_group_element(&proof.w)) {
+            return Err(Error::InvalidGroupElement);
+        }
+        let lhs = E::pairing(inner, vk.h);
+
+        let inner = vk.beta_h.into_projective() - &vk.h.mul(point);
+        let rhs = E::pairing(proof.w, inner);
+
+        end_timer!(check_time, || format!("Result: {}", lhs == rhs));
+        Ok(lhs == rhs)
+    }
+
+    /// Check that each `proof_i` in `proofs` is a valid proof of evaluation for
+    /// `commitment_i` at `point_i`.
+    ///
+    /// The checks are combined by random 128-bit challenges sampled from `rng`
+    /// into a single product of two pairings, so the batch is rejected (except
+    /// with negligible probability) if any one of the proofs is invalid.
+    pub fn batch_check<R: RngCore>(
+        vk: &VerifierKey<E>,
+        commitments: &[Commitment<E>],
+        points: &[E::Fr],
+        values: &[E::Fr],
+        proofs: &[Proof<E>],
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        Self::batch_check_inner(
+            vk,
+            commitments.iter().map(|c| c.0.into_projective()),
+            points,
+            values,
+            proofs,
+            rng,
+        )
+    }
+
+    /// Checks a batch of evaluation proofs, each against its own verifier key,
+    /// with a single product of two pairings. Each entry is
+    /// `(vk, commitment, point, value, proof)`.
+    ///
+    /// The keys may come from different trims of the same setup (e.g. with
+    /// different supported degrees): each entry's `g` and `gamma_g` are used
+    /// to fold it, and the pairings use the `h` and `beta_h` shared by all the
+    /// keys. `Error::MismatchedVerifierKeys` is returned if these differ.
+    pub fn batch_check_multi_key<R: RngCore>(
+        entries: &[(&VerifierKey<E>, Commitment<E>, E::Fr, E::Fr, Proof<E>)],
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let check_time = start_timer!(|| format!(
+            "Checking {} evaluation proofs under multiple keys",
+            entries.len()
+        ));
+        let shared_vk = match entries.first() {
+            Some((vk, ..)) => *vk,
+            None => return Ok(true),
+        };
+        if entries
+            .iter()
+            .any(|(vk, ..)| vk.h != shared_vk.h || vk.beta_h != shared_vk.beta_h)
+        {
+            return Err(Error::MismatchedVerifierKeys);
+        }
+
+        let mut total_c = <E::G1Projective>::zero();
+        let mut total_w = <E::G1Projective>::zero();
+        let mut randomizer = E::Fr::one();
+        for (vk, comm, point, value, proof) in entries {
+            let mut c = proof.w.mul(*point);
+            c.add_assign_mixed(&comm.0);
+            c -= &vk.g.mul(*value);
+            if let Some(random_v) = proof.random_v {
+                c -= &vk.gamma_g.mul(random_v);
+            }
+            total_c += &c.mul(randomizer);
+            total_w += &proof.w.mul(randomizer);
+            randomizer = u128::rand(rng).into();
+        }
+
+        let affine_points = E::G1Projective::batch_normalization_into_affine(&[-total_w, total_c]);
+        let (total_w, total_c) = (affine_points[0], affine_points[1]);
+        let result = E::product_of_pairings(&[
+            (total_w.into(), shared_vk.prepared_beta_h.clone()),
+            (total_c.into(), shared_vk.prepared_h.clone()),
+        ])
+        .is_one();
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Like `batch_check`, but takes the commitments in projective form. The
+    /// commitments are only combined, and never normalized individually.
+    pub fn batch_check_projective<R: RngCore>(
+        vk: &VerifierKey<E>,
+        commitments: &[E::G1Projective],
+        points: &[E::Fr],
+        values: &[E::Fr],
+        proofs: &[Proof<E>],
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        Self::batch_check_inner(vk, commitments.iter().cloned(), points, values, proofs, rng)
+    }
+
+    /// Splits `p` into chunks `p_0, ..., p_{m-1}` of `chunk_degree + 1`
+    /// coefficients each, so that `p(x) = \sum_j x^{j * (chunk_degree + 1)} p_j(x)`.
+    fn split_into_chunks(p: &P, chunk_degree: usize) -> Vec<P> {
+        if p.coeffs().is_empty() {
+            return vec![P::zero()];
+        }
+        p.coeffs()
+            .chunks(chunk_degree + 1)
+            .map(P::from_coefficients_slice)
+            .collect()
+    }
+
+    /// Outputs (non-hiding) commitments to the chunks of `p`, each of degree at
+    /// most `chunk_degree`; see `open_chunked`. Unlike `commit`, this supports
+    /// polynomials of degree larger than that supported by `powers`.
+    pub fn commit_chunked(
+        powers: &Powers<E>,
+        p: &P,
+        chunk_degree: usize,
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        Self::check_degree_is_too_large(chunk_degree + 1, powers.size())?;
+        Self::split_into_chunks(p, chunk_degree)
+            .iter()
+            .map(|chunk| Self::commit(powers, chunk, None, None).map(|(comm, _)| comm))
+            .collect()
+    }
+
+    /// Opens each chunk `p_j` of `p` (as computed by `commit_chunked`) at `point`.
+    ///
+    /// The verifier recombines the chunk evaluations `v_j` into
+    /// `p(point) = \sum_j point^{j * (chunk_degree + 1)} v_j`, and checks the
+    /// chunk proofs against the chunk commitments; see `check_chunked`.
+    pub fn open_chunked(
+        powers: &Powers<E>,
+        p: &P,
+        chunk_degree: usize,
+        point: P::Point,
+    ) -> Result<ChunkedProof<E>, Error> {
+        Self::check_degree_is_too_large(chunk_degree + 1, powers.size())?;
+        let mut chunk_values = Vec::new();
+        let mut proofs = Vec::new();
+        for chunk in Self::split_into_chunks(p, chunk_degree) {
+            chunk_values.push(chunk.evaluate(&point));
+            // The witness polynomial of a constant chunk is zero.
+            let proof = if chunk.degree() == 0 {
+                Proof::default()
+            } else {
+                Self::open(powers, &chunk, point, &Randomness::empty())?
+            };
+            proofs.push(proof);
+        }
+        Ok(ChunkedProof {
+            chunk_values,
+            proofs,
+        })
+    }
+
+    /// Verifies that `value` is the evaluation at `point` of the polynomial
+    /// whose chunks of degree at most `chunk_degree` are committed in `comms`.
+    ///
+    /// This checks that `value` equals the recombination of the chunk
+    /// evaluations in `proof`, and then checks all chunk proofs at once with
+    /// `batch_check`. As for a single commitment, `point` must be chosen after
+    /// `comms` are fixed.
+    pub fn check_chunked<R: RngCore>(
+        vk: &VerifierKey<E>,
+        comms: &[Commitment<E>],
+        chunk_degree: usize,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &ChunkedProof<E>,
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        if comms.len() != proof.chunk_values.len() || comms.len() != proof.proofs.len() {
+            return Err(Error::IncorrectInputLength(format!(
+                "expected {} chunk values and proofs, but got {} and {}",
+                comms.len(),
+                proof.chunk_values.len(),
+                proof.proofs.len()
+            )));
+        }
+        let shift = point.pow(&[(chunk_degree + 1) as u64]);
+        let recombined = proof
+            .chunk_values
+            .iter()
+            .rev()
+            .fold(E::Fr::zero(), |acc, v| acc * &shift + v);
+        if recombined != value {
+            return Ok(false);
+        }
+        let points = vec![point; comms.len()];
+        Self::batch_check(vk, comms, &points, &proof.chunk_values, &proof.proofs, rng)
+    }
+
+    fn batch_check_inner<R: RngCore>(
+        vk: &VerifierKey<E>,
+        commitments: impl ExactSizeIterator<Item = E::G1Projective>,
+        points: &[E::Fr],
+        values: &[E::Fr],
+        proofs: &[Proof<E>],
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let num_proofs = commitments.len();
+        if points.len() != num_proofs || values.len() != num_proofs || proofs.len() != num_proofs {
+            return Err(Error::IncorrectInputLength(format!(
+                "{} commitments, {} points, {} values and {} proofs",
+                num_proofs,
+                points.len(),
+                values.len(),
+                proofs.len()
+            )));
+        }
+        let check_time = start_timer!(|| format!("Checking {} evaluation proofs", num_proofs));
+        let g = vk.g.into_projective();
+        let gamma_g = vk.gamma_g.into_projective();
+
+        let mut total_c = <E::G1Projective>::zero();
+        let mut total_w = <E::G1Projective>::zero();
+
+        let combination_time = start_timer!(|| "Combining commitments and proofs");
+        let mut randomizer = E::Fr::one();
+        // Instead of multiplying g and gamma_g in each turn, we simply accumulate
+        // their coefficients and perform a final multiplication at the end.
+        let mut g_multiplier = E::Fr::zero();
+        let mut gamma_g_multiplier = E::Fr::zero();
+        for (((c, z), v), proof) in commitments.zip(points).zip(values).zip(proofs) {
+            let w = proof.w;
+            let mut temp = w.mul(*z);
+            temp += &c;
+            let c = temp;
+            g_multiplier += &(randomizer * v);
+            if let Some(random_v) = proof.random_v {
+                gamma_g_multiplier += &(randomizer * &random_v);
+            }
+            total_c += &c.mul(randomizer);
+            total_w += &w.mul(randomizer);
+            // We don't need to sample randomizers from the full field,
+            // only from 128-bit strings.
+            randomizer = u128::rand(rng).into();
+        }
+        total_c -= &g.mul(g_multiplier);
+        total_c -= &gamma_g.mul(gamma_g_multiplier);
         end_timer!(combination_time);
 
-        let to_affine_time = start_timer!(|| "Converting results to affine for pairing");
-        let affine_points = E::G1Projective::batch_normalization_into_affine(&[-total_w, total_c]);
-        let (total_w, total_c) = (affine_points[0], affine_points[1]);
-        end_timer!(to_affine_time);
+        let to_affine_time = start_timer!(|| "Converting results to affine for pairing");
+        let affine_points = E::G1Projective::batch_normalization_into_affine(&[-total_w, total_c]);
+        let (total_w, total_c) = (affine_points[0], affine_points[1]);
+        end_timer!(to_affine_time);
+
+        let pairing_time = start_timer!(|| "Performing product of pairings");
+        let result = E::product_of_pairings(&[
+            (total_w.into(), vk.prepared_beta_h.clone()),
+            (total_c.into(), vk.prepared_h.clone()),
+        ])
+        .is_one();
+        end_timer!(pairing_time);
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Computes the quotient `q = p / Z_H` of `p` by the vanishing polynomial
+    /// `Z_H` of `domain`, and outputs a (non-hiding) commitment to `q` along
+    /// with `q` itself.
+    ///
+    /// This is the first message of the vanishing argument: the commitment to
+    /// `q` must be fixed before the verifier samples the challenge `point`
+    /// that is passed to `open_vanishes_on_domain`.
+    pub fn prove_vanishes_on_domain<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        p: &P,
+        domain: &D,
+    ) -> Result<(Commitment<E>, P), Error> {
+        let prove_time =
+            start_timer!(|| format!("Computing quotient by Z_H of size {}", domain.size()));
+        let z_h: DensePolynomial<E::Fr> = domain.vanishing_polynomial().into();
+        let quotient = p / &P::from_coefficients_vec(z_h.coeffs);
+        let (quotient_comm, _) = Self::commit(powers, &quotient, None, None)?;
+        end_timer!(prove_time);
+        Ok((quotient_comm, quotient))
+    }
+
+    /// Outputs a proof that `p - Z_H(point) * quotient` evaluates to zero at
+    /// `point`, where `quotient` is the polynomial output by
+    /// `prove_vanishes_on_domain`.
+    pub fn open_vanishes_on_domain<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        p: &P,
+        quotient: &P,
+        domain: &D,
+        point: E::Fr,
+    ) -> Result<Proof<E>, Error> {
+        let z_h_at_point = domain.evaluate_vanishing_polynomial(point);
+        let mut linearized = p.clone();
+        linearized += (-z_h_at_point, quotient);
+        Self::open(powers, &linearized, point, &Randomness::empty())
+    }
+
+    /// Verifies that the polynomial committed in `comm_p` vanishes on every
+    /// element of `domain`, that is, that it equals `q * Z_H` where `q` is the
+    /// polynomial committed in `comm_q`.
+    ///
+    /// Because `Z_H` is sparse, the verifier can evaluate it at `point` itself
+    /// and check the identity with a single opening of the linearized
+    /// commitment `comm_p - Z_H(point) * comm_q` to zero.
+    pub fn check_vanishes_on_domain<D: EvaluationDomain<E::Fr>>(
+        vk: &VerifierKey<E>,
+        comm_p: &Commitment<E>,
+        comm_q: &Commitment<E>,
+        domain: &D,
+        point: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let check_time = start_timer!(|| "Checking vanishing argument");
+        let z_h_at_point = domain.evaluate_vanishing_polynomial(point);
+        let linearized = comm_p.0.into_projective() - &comm_q.0.mul(z_h_at_point);
+        let result = Self::check(
+            vk,
+            &Commitment(linearized.into_affine()),
+            point,
+            E::Fr::zero(),
+            proof,
+        )?;
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Computes the quotient `q = (p - public_poly) / Z_H` by the vanishing
+    /// polynomial `Z_H` of `subdomain`, and outputs a (non-hiding) commitment
+    /// to `q` along with `q` itself. This is the vanishing argument of
+    /// `prove_vanishes_on_domain` applied to `p - public_poly`, and shows that
+    /// `p` agrees with the public polynomial `public_poly` on `subdomain`.
+    pub fn prove_agrees_on_subdomain<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        p: &P,
+        public_poly: &P,
+        subdomain: &D,
+    ) -> Result<(Commitment<E>, P), Error> {
+        let mut difference = p.clone();
+        difference -= public_poly;
+        Self::prove_vanishes_on_domain(powers, &difference, subdomain)
+    }
+
+    /// Outputs a proof that `p - Z_H(point) * quotient` evaluates to
+    /// `public_poly(point)` at `point`, where `quotient` is the polynomial
+    /// output by `prove_agrees_on_subdomain`.
+    pub fn open_agrees_on_subdomain<D: EvaluationDomain<E::Fr>>(
+        powers: &Powers<E>,
+        p: &P,
+        quotient: &P,
+        subdomain: &D,
+        point: E::Fr,
+    ) -> Result<Proof<E>, Error> {
+        // The witness does not depend on the claimed evaluation.
+        Self::open_vanishes_on_domain(powers, p, quotient, subdomain, point)
+    }
+
+    /// Verifies that the polynomial committed in `comm_p` agrees with
+    /// `public_poly` on every element of `subdomain`, that is, that
+    /// `p - public_poly` equals `q * Z_H`, where `q` is the polynomial committed
+    /// in `comm_q`.
+    ///
+    /// The verifier evaluates `public_poly` and `Z_H` at `point` itself, and
+    /// checks that the linearized commitment `comm_p - Z_H(point) * comm_q`
+    /// opens to `public_poly(point)`.
+    pub fn check_agrees_on_subdomain<D: EvaluationDomain<E::Fr>>(
+        vk: &VerifierKey<E>,
+        comm_p: &Commitment<E>,
+        public_poly: &P,
+        comm_q: &Commitment<E>,
+        subdomain: &D,
+        point: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let check_time = start_timer!(|| "Checking agreement on subdomain");
+        let z_h_at_point = subdomain.evaluate_vanishing_polynomial(point);
+        let linearized = comm_p.0.into_projective() - &comm_q.0.mul(z_h_at_point);
+        let result = Self::check(
+            vk,
+            &Commitment(linearized.into_affine()),
+            point,
+            public_poly.evaluate(&point),
+            proof,
+        )?;
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Outputs a (non-hiding) commitment to `polynomial`, together with a
+    /// proof of knowledge of its coefficients.
+    ///
+    /// The commitment `\sum_i a_i \beta^i G` is a Pedersen-style vector
+    /// commitment with the `powers_of_g` as bases, and the proof is a Schnorr
+    /// proof of knowledge of an opening `(a_0, ..., a_d)` of it, made
+    /// non-interactive by hashing the commitment and the masks with `D`. This
+    /// gives extractability without relying on the algebraic group model, at
+    /// the cost of a proof that is linear in the degree.
+    pub fn commit_with_pok<D: Digest, R: RngCore>(
+        powers: &Powers<E>,
+        polynomial: &P,
+        rng: &mut R,
+    ) -> Result<(Commitment<E>, PokProof<E>), Error> {
+        let (comm, _) = Self::commit(powers, polynomial, None, None)?;
+
+        let pok_time = start_timer!(|| "Computing proof of knowledge");
+        let coeffs = polynomial.coeffs();
+        let masks = (0..coeffs.len())
+            .map(|_| E::Fr::rand(rng))
+            .collect::<Vec<_>>();
+        let mask_comm = multi_scalar_mul(
+            &powers.powers_of_g[..masks.len()],
+            &convert_to_bigints(&masks, powers.parallel_threshold),
+            powers.parallel_threshold,
+        )
+        .into_affine();
+
+        let challenge = Self::compute_pok_challenge::<D>(&comm, &mask_comm, masks.len());
+        let responses = masks
+            .iter()
+            .zip(coeffs)
+            .map(|(k, a)| *k + &(challenge * a))
+            .collect();
+        end_timer!(pok_time);
+
+        Ok((
+            comm,
+            PokProof {
+                mask_comm,
+                responses,
+            },
+        ))
+    }
+
+    /// Verifies a proof of knowledge of the coefficients committed in `comm`,
+    /// output by `commit_with_pok`.
+    pub fn verify_pok<D: Digest>(
+        powers: &Powers<E>,
+        comm: &Commitment<E>,
+        pok: &PokProof<E>,
+    ) -> Result<bool, Error> {
+        Self::check_degree_is_too_large(pok.responses.len(), powers.size())?;
+        let check_time = start_timer!(|| "Checking proof of knowledge");
+        let challenge = Self::compute_pok_challenge::<D>(comm, &pok.mask_comm, pok.responses.len());
+        let lhs = multi_scalar_mul(
+            &powers.powers_of_g[..pok.responses.len()],
+            &convert_to_bigints(&pok.responses, powers.parallel_threshold),
+            powers.parallel_threshold,
+        );
+        let rhs = pok.mask_comm.into_projective() + &comm.0.mul(challenge);
+        let result = lhs == rhs;
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Hashes the commitment, the mask commitment and the number of
+    /// coefficients to the challenge of a proof of knowledge.
+    fn compute_pok_challenge<D: Digest>(
+        comm: &Commitment<E>,
+        mask_comm: &E::G1Affine,
+        num_coefficients: usize,
+    ) -> E::Fr {
+        let bytes = ark_ff::to_bytes![comm, mask_comm, num_coefficients as u64].unwrap();
+        hash_to_field::<D, _>(b"KZG10::commit_with_pok", &bytes)
+    }
+
+    // Functions for checking errors
+    pub(crate) fn check_degree_is_within_bounds(
+        num_coefficients: usize,
+        num_powers: usize,
+    ) -> Result<(), Error> {
+        if num_coefficients < 1 {
+            Err(Error::DegreeIsZero)
+        } else {
+            Self::check_degree_is_too_large(num_coefficients, num_powers)
+        }
+    }
+
+    pub(crate) fn check_degree_is_too_large(
+        num_coefficients: usize,
+        num_powers: usize,
+    ) -> Result<(), Error> {
+        if num_coefficients > num_powers {
+            Err(Error::TooManyCoefficients {
+                num_coefficients,
+                num_powers,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn check_hiding_bound(
+        hiding_poly_degree: usize,
+        num_powers: usize,
+    ) -> Result<(), Error> {
+        if hiding_poly_degree == 0 {
+            Err(Error::HidingBoundIsZero)
+        } else if hiding_poly_degree >= num_powers {
+            // The above check uses `>=` because committing to a hiding poly with
+            // degree `hiding_poly_degree` requires `hiding_poly_degree + 1`
+            // powers.
+            Err(Error::HidingBoundToolarge {
+                hiding_poly_degree,
+                num_powers,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn check_degrees_and_bounds<'a>(
+        supported_degree: usize,
+        max_degree: usize,
+        enforced_degree_bounds: Option<&[usize]>,
+        p: &'a LabeledPolynomial<E::Fr, P>,
+    ) -> Result<(), Error> {
+        if let Some(bound) = p.degree_bound() {
+            let enforced_degree_bounds =
+                enforced_degree_bounds.ok_or(Error::UnsupportedDegreeBound(bound))?;
+
+            if enforced_degree_bounds.binary_search(&bound).is_err() {
+                Err(Error::UnsupportedDegreeBound(bound))
+            } else if bound < p.degree() || bound > max_degree {
+                return Err(Error::IncorrectDegreeBound {
+                    poly_degree: p.degree(),
+                    degree_bound: p.degree_bound().unwrap(),
+                    supported_degree,
+                    label: p.label().to_string(),
+                });
+            } else {
+                Ok(())
+            }
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Checks that `g` lies on the curve and in the prime-order subgroup.
+///
+/// `AffineCurve` does not expose these checks directly, but compressed
+/// deserialization performs both: it recomputes the `y`-coordinate from `x`
+/// and rejects points outside the prime-order subgroup.
+fn is_valid_group_element<G: AffineCurve>(g: &G) -> bool {
+    let mut bytes = Vec::with_capacity(g.serialized_size());
+    g.serialize(&mut bytes).is_ok() && G::deserialize(bytes.as_slice()).map_or(false, |h| h == *g)
+}
+
+/// Hashes `domain` and `bytes` to an element of `F`: a counter is appended to
+/// the input and incremented until the digest is a valid field element.
+pub(crate) fn hash_to_field<D: Digest, F: Field>(domain: &[u8], bytes: &[u8]) -> F {
+    let mut i = 0u64;
+    loop {
+        let hash_input = ark_ff::to_bytes![domain, bytes, i].unwrap();
+        if let Some(f) = F::from_random_bytes(&D::digest(&hash_input)) {
+            return f;
+        }
+        i += 1;
+    }
+}
+
+/// Computes `scalar * g` from `doublings`, the successive doublings of `g`
+/// (as stored in prepared keys and commitments).
+pub(crate) fn mul_by_doublings<G: AffineCurve>(
+    doublings: &[G],
+    scalar: G::ScalarField,
+) -> G::Projective {
+    let scalar_repr = scalar.into_repr();
+    let mut result = G::Projective::zero();
+    for (i, g) in doublings.iter().enumerate() {
+        if scalar_repr.get_bit(i) {
+            result.add_assign_mixed(g);
+        }
+    }
+    result
+}
+
+/// Returns the quotient of the polynomial with coefficients `coeffs` by
+/// `x - point`, as `divide_by_linear_into` does.
+fn divide_by_linear<F: PrimeField>(coeffs: &[F], point: F) -> Vec<F> {
+    let mut quotient = Vec::new();
+    divide_by_linear_into(coeffs, point, &mut quotient);
+    quotient
+}
+
+fn skip_leading_zeros_and_convert_to_bigints<F: PrimeField, P: UVPolynomial<F>>(
+    p: &P,
+    parallel_threshold: usize,
+) -> (usize, Vec<F::BigInt>) {
+    let mut num_leading_zeros = 0;
+    while p.coeffs()[num_leading_zeros].is_zero() && num_leading_zeros < p.coeffs().len() {
+        num_leading_zeros += 1;
+    }
+    let coeffs = convert_to_bigints(&p.coeffs()[num_leading_zeros..], parallel_threshold);
+    (num_leading_zeros, coeffs)
+}
+
+/// Returns the coefficients of `\prod_i (X - points[i])`.
+fn vanishing_poly_coeffs<F: Field>(points: &[F]) -> Vec<F> {
+    let mut coeffs = vec![F::one()];
+    for point in points {
+        coeffs.insert(0, F::zero());
+        for i in 0..coeffs.len() - 1 {
+            let shifted = coeffs[i + 1] * point;
+            coeffs[i] -= &shifted;
+        }
+    }
+    coeffs
+}
+
+/// Returns the coefficients of the polynomial of degree less than
+/// `points.len()` that takes the value `values[i]` at `points[i]`.
+fn interpolate_coeffs<F: PrimeField>(points: &[F], values: &[F]) -> Result<Vec<F>, Error> {
+    if points.is_empty() {
+        return Err(Error::IncorrectInputLength(
+            "at least one point must be opened".to_string(),
+        ));
+    }
+    let vanishing = vanishing_poly_coeffs(points);
+    let mut coeffs = vec![F::zero(); points.len()];
+    let mut basis = Vec::new();
+    for (i, (z_i, v_i)) in points.iter().zip(values).enumerate() {
+        let denominator = points
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .fold(F::one(), |acc, (_, z_j)| acc * &(*z_i - z_j));
+        let scale = *v_i * &denominator.inverse().ok_or(Error::RepeatedPoint)?;
+        divide_by_linear_into(&vanishing, *z_i, &mut basis);
+        for (c, b) in coeffs.iter_mut().zip(&basis) {
+            *c += &(scale * b);
+        }
+    }
+    Ok(coeffs)
+}
+
+fn convert_to_bigints<F: PrimeField>(p: &[F], parallel_threshold: usize) -> Vec<F::BigInt> {
+    let to_bigint_time = start_timer!(|| "Converting polynomial coeffs to bigints");
+    let mut coeffs = Vec::new();
+    convert_to_bigints_into(p, &mut coeffs, parallel_threshold);
+    end_timer!(to_bigint_time);
+    coeffs
+}
+
+/// Writes the bigint representations of `p` into `bigints`, in parallel only
+/// if `p` has at least `parallel_threshold` coefficients.
+fn convert_to_bigints_into<F: PrimeField>(
+    p: &[F],
+    bigints: &mut Vec<F::BigInt>,
+    parallel_threshold: usize,
+) {
+    bigints.clear();
+    if p.len() < parallel_threshold {
+        bigints.extend(p.iter().map(|s| s.into_repr()));
+        return;
+    }
+    #[cfg(feature = "parallel")]
+    bigints.par_extend(p.par_iter().map(|s| s.into_repr()));
+    #[cfg(not(feature = "parallel"))]
+    bigints.extend(p.iter().map(|s| s.into_repr()));
+}
+
+/// Fails with `Error::AllocationTooLarge` if `requested_bytes` exceeds `cap`.
+pub(crate) fn check_allocation(requested_bytes: usize, cap: usize) -> Result<(), Error> {
+    if requested_bytes > cap {
+        Err(Error::AllocationTooLarge { requested_bytes })
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the powers of `\gamma G` that `trim` puts in a committer key for
+/// hiding commitments that are opened at up to `supported_hiding_bound` points,
+/// i.e. enough powers to commit to the blinding polynomial of degree
+/// `Randomness::calculate_hiding_polynomial_degree(supported_hiding_bound)`.
+/// Fails with `Error::HidingBoundToolarge` if `pp` has too few powers.
+pub(crate) fn trim_powers_of_gamma_g<E: PairingEngine>(
+    pp: &UniversalParams<E>,
+    supported_hiding_bound: usize,
+) -> Result<Vec<E::G1Affine>, Error> {
+    let hiding_poly_degree =
+        Randomness::<E::Fr, DensePolynomial<E::Fr>>::calculate_hiding_polynomial_degree(
+            supported_hiding_bound,
+        );
+    (0..=hiding_poly_degree)
+        .map(|i| {
+            pp.powers_of_gamma_g
+                .get(&i)
+                .cloned()
+                .ok_or(Error::HidingBoundToolarge {
+                    hiding_poly_degree,
+                    num_powers: pp.powers_of_gamma_g.len(),
+                })
+        })
+        .collect()
+}
+
+/// Checks that `supported_degree` and every enforced degree bound passed to
+/// `trim` are at most `max_degree`.
+pub(crate) fn check_trim_degrees(
+    supported_degree: usize,
+    max_degree: usize,
+    enforced_degree_bounds: Option<&[usize]>,
+) -> Result<(), Error> {
+    if supported_degree > max_degree {
+        return Err(Error::TrimmingDegreeTooLarge {
+            supported: supported_degree,
+            max: max_degree,
+        });
+    }
+    if let Some(&bound) = enforced_degree_bounds.and_then(|bounds| bounds.iter().max()) {
+        if bound > max_degree {
+            return Err(Error::TrimmingDegreeTooLarge {
+                supported: bound,
+                max: max_degree,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Estimates the bytes allocated by `KZG10::setup`: `powers_of_beta`, and the
+/// projective and affine powers of `G` and `\gamma G`, plus the projective,
+/// affine and prepared powers of `H`.
+fn setup_allocation_bytes<E: PairingEngine>(max_degree: usize, produce_g2_powers: bool) -> usize {
+    let mut bytes_per_power =
+        size_of::<E::Fr>() + 2 * size_of::<E::G1Projective>() + 2 * size_of::<E::G1Affine>();
+    if produce_g2_powers {
+        bytes_per_power +=
+            size_of::<E::G2Projective>() + size_of::<E::G2Affine>() + size_of::<E::G2Prepared>();
+    }
+    bytes_per_power.saturating_mul(max_degree.saturating_add(2))
+}
+
+/// Estimates the bytes allocated for the committer key output by `trim`: the
+/// powers of `G` and `\gamma G`, and the shifted powers if degree bounds are
+/// enforced.
+fn trim_allocation_bytes<E: PairingEngine>(
+    supported_degree: usize,
+    supported_hiding_bound: usize,
+    max_degree: usize,
+    enforces_degree_bounds: bool,
+) -> usize {
+    let mut num_powers = supported_degree.saturating_add(supported_hiding_bound.saturating_add(3));
+    if enforces_degree_bounds {
+        num_powers = num_powers.saturating_add(max_degree + 1);
+    }
+    size_of::<E::G1Affine>().saturating_mul(num_powers)
+}
+
+/// Checks the allocation of the committer key output by `trim` against
+/// `pp.allocation_cap`.
+pub(crate) fn check_trim_allocation<E: PairingEngine>(
+    pp: &UniversalParams<E>,
+    supported_degree: usize,
+    supported_hiding_bound: usize,
+    enforces_degree_bounds: bool,
+) -> Result<(), Error> {
+    check_allocation(
+        trim_allocation_bytes::<E>(
+            supported_degree,
+            supported_hiding_bound,
+            pp.max_degree(),
+            enforces_degree_bounds,
+        ),
+        pp.allocation_cap,
+    )
+}
+
+/// The default `Powers::parallel_threshold`: the number of coefficients below
+/// which `KZG10::commit` and `KZG10::open` (and their variants) run on the
+/// current thread even when the `parallel` feature is enabled, as dispatching
+/// such small multi-scalar multiplications to the thread pool costs more than
+/// it saves.
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 1 << 7;
+
+/// Computes `\sum_i scalars[i] bases[i]`, on the current thread if there are
+/// fewer than `parallel_threshold` scalars.
+fn multi_scalar_mul<G: AffineCurve>(
+    bases: &[G],
+    scalars: &[<G::ScalarField as PrimeField>::BigInt],
+    parallel_threshold: usize,
+) -> G::Projective {
+    if cfg!(feature = "parallel") && scalars.len() < parallel_threshold {
+        naive_multi_scalar_mul(bases, scalars)
+    } else {
+        VariableBaseMSM::multi_scalar_mul(bases, scalars)
+    }
+}
+
+/// Computes `\sum_i scalars[i] bases[i]` one term at a time.
+fn naive_multi_scalar_mul<G: AffineCurve>(
+    bases: &[G],
+    scalars: &[<G::ScalarField as PrimeField>::BigInt],
+) -> G::Projective {
+    bases
+        .iter()
+        .zip(scalars)
+        .fold(G::Projective::zero(), |sum, (base, scalar)| {
+            sum + &base.mul(*scalar)
+        })
+}
+
+/// Writes the coefficients of the quotient of `p` by `x - point` into `quotient`,
+/// using synthetic division. The remainder `p(point)` is discarded.
+fn divide_by_linear_into<F: PrimeField>(p: &[F], point: F, quotient: &mut Vec<F>) {
+    quotient.clear();
+    if p.len() < 2 {
+        return;
+    }
+    quotient.resize(p.len() - 1, F::zero());
+    let mut acc = F::zero();
+    for i in (1..p.len()).rev() {
+        acc = p[i] + &(acc * &point);
+        quotient[i - 1] = acc;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_camel_case_types)]
+    use crate::kzg10::*;
+    use crate::*;
+
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+    use ark_bls12_381::Fr;
+    use ark_ec::PairingEngine;
+    use ark_ff::test_rng;
+    use ark_poly::univariate::DensePolynomial as DensePoly;
+
+    type UniPoly_381 = DensePoly<<Bls12_381 as PairingEngine>::Fr>;
+    type UniPoly_377 = DensePoly<<Bls12_377 as PairingEngine>::Fr>;
+    type KZG_Bls12_381 = KZG10<Bls12_381, UniPoly_381>;
+
+    impl<E: PairingEngine, P: UVPolynomial<E::Fr>> KZG10<E, P> {
+        /// Specializes the public parameters for a given maximum degree `d` for polynomials
+        /// `d` should be less that `pp.max_degree()`.
+        pub(crate) fn trim(
+            pp: &UniversalParams<E>,
+            mut supported_degree: usize,
+        ) -> Result<(Powers<E>, VerifierKey<E>), Error> {
+            if supported_degree == 1 {
+                supported_degree += 1;
+            }
+            let powers_of_g = pp.powers_of_g[..=supported_degree].to_vec();
+            let powers_of_gamma_g = (0..=supported_degree)
+                .map(|i| pp.powers_of_gamma_g[&i])
+                .collect();
+
+            let powers = Powers {
+                powers_of_g: ark_std::borrow::Cow::Owned(powers_of_g),
+                powers_of_gamma_g: ark_std::borrow::Cow::Owned(powers_of_gamma_g),
+                parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+            };
+            let vk = VerifierKey {
+                g: pp.powers_of_g[0],
+                gamma_g: pp.powers_of_gamma_g[&0],
+                h: pp.h,
+                beta_h: pp.beta_h,
+                prepared_h: pp.prepared_h.clone(),
+                prepared_beta_h: pp.prepared_beta_h.clone(),
+            };
+            Ok((powers, vk))
+        }
+    }
+
+    #[test]
+    fn add_commitments_test() {
+        let rng = &mut test_rng();
+        let p = DensePoly::from_coefficients_slice(&[
+            Fr::rand(rng),
+            Fr::rand(rng),
+            Fr::rand(rng),
+            Fr::rand(rng),
+            Fr::rand(rng),
+        ]);
+        let f = Fr::rand(rng);
+        let mut f_p = DensePoly::zero();
+        f_p += (f, &p);
+
+        let degree = 4;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let hiding_bound = None;
+        let (comm, _) = KZG10::commit(&powers, &p, hiding_bound, Some(rng)).unwrap();
+        let (f_comm, _) = KZG10::commit(&powers, &f_p, hiding_bound, Some(rng)).unwrap();
+        let mut f_comm_2 = Commitment::empty();
+        f_comm_2 += (f, &comm);
+
+        assert_eq!(f_comm, f_comm_2);
+    }
+
+    #[test]
+    fn bytes_round_trip_test() {
+        use ark_std::convert::TryFrom;
+
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, Some(1), Some(rng)).unwrap();
+        let proof = KZG_Bls12_381::open(&powers, &p, Fr::rand(rng), &rand).unwrap();
+
+        assert_eq!(
+            Commitment::try_from(comm.to_vec().as_slice()).unwrap(),
+            comm
+        );
+        assert_eq!(Proof::try_from(proof.to_vec().as_slice()).unwrap(), proof);
+        assert!(Commitment::<Bls12_381>::try_from(&comm.to_vec()[1..]).is_err());
+    }
+
+    #[test]
+    fn invalid_group_element_test() {
+        use ark_bls12_381::{Fq, G1Affine};
+        use ark_ff::FpParameters;
+
+        let rng = &mut test_rng();
+        let degree = 4;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+        let point = Fr::rand(rng);
+        let value = p.evaluate(&point);
+        let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+        assert!(KZG_Bls12_381::check(&vk, &comm, point, value, &proof).unwrap());
+
+        // Clearing the prime-order component of a point outside the subgroup
+        // leaves a non-trivial cofactor-torsion point.
+        let modulus = <<Fr as PrimeField>::Params as FpParameters>::MODULUS;
+        let torsion = (1u64..)
+            .filter_map(|x| G1Affine::get_point_from_x(Fq::from(x), false))
+            .map(|g| g.mul(modulus))
+            .find(|t| !t.is_zero())
+            .unwrap();
+        let bad_comm = Commitment((torsion + comm.0.into_projective()).into_affine());
+        let bad_proof = Proof {
+            w: (torsion + proof.w.into_projective()).into_affine(),
+            random_v: None,
+        };
+
+        assert!(matches!(
+            KZG_Bls12_381::check(&vk, &bad_comm, point, value, &proof),
+            Err(Error::InvalidGroupElement)
+        ));
+        assert!(matches!(
+            KZG_Bls12_381::check(&vk, &comm, point, value, &bad_proof),
+            Err(Error::InvalidGroupElement)
+        ));
+        assert!(
+            KZG_Bls12_381::check_with_validation(&vk, &bad_comm, point, value, &proof, false)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn commit_shifted_by_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(5, rng);
+        for &k in &[0, 1, 3, 5] {
+            let mut shifted_coeffs = vec![Fr::zero(); k];
+            shifted_coeffs.extend_from_slice(&p.coeffs);
+            let shifted = UniPoly_381::from_coefficients_vec(shifted_coeffs);
+
+            let (expected, _) = KZG_Bls12_381::commit(&powers, &shifted, None, None).unwrap();
+            let comm = KZG_Bls12_381::commit_shifted_by(&powers, &p, k).unwrap();
+            assert_eq!(comm, expected, "shift by x^{} does not match", k);
+        }
+        assert!(matches!(
+            KZG_Bls12_381::commit_shifted_by(&powers, &p, 6),
+            Err(Error::TooManyCoefficients { .. })
+        ));
+    }
+
+    #[test]
+    fn commit_prefixes_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let coeffs = (0..=degree).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let prefix_comms = KZG_Bls12_381::commit_prefixes(&powers, &coeffs).unwrap();
+        assert_eq!(prefix_comms.len(), coeffs.len());
+        assert_eq!(
+            prefix_comms[0].0,
+            powers.powers_of_g[0].mul(coeffs[0]).into_affine()
+        );
+        for (k, prefix_comm) in prefix_comms.iter().enumerate().skip(1) {
+            let prefix = UniPoly_381::from_coefficients_slice(&coeffs[..=k]);
+            let (comm, _) = KZG_Bls12_381::commit(&powers, &prefix, None, None).unwrap();
+            assert_eq!(*prefix_comm, comm, "prefix {} does not match", k);
+        }
+    }
+
+    #[test]
+    fn shared_powers_test() {
+        use std::sync::Arc;
+
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let shared = SharedPowers::from(powers.clone());
+
+        let num_threads = 4;
+        let polys = (0..num_threads)
+            .map(|_| UniPoly_381::rand(degree, rng))
+            .collect::<Vec<_>>();
+        let points = (0..num_threads).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+        // Cloning only bumps the reference counts; every clone points to the
+        // same allocation.
+        let clones = (0..num_threads).map(|_| shared.clone()).collect::<Vec<_>>();
+        assert_eq!(Arc::strong_count(&shared.powers_of_g), num_threads + 1);
+        assert_eq!(
+            Arc::strong_count(&shared.powers_of_gamma_g),
+            num_threads + 1
+        );
+        assert!(clones
+            .iter()
+            .all(|c| Arc::ptr_eq(&c.powers_of_g, &shared.powers_of_g)));
+
+        let handles = clones
+            .into_iter()
+            .zip(polys.iter().cloned())
+            .zip(points.iter().cloned())
+            .map(|((shared, p), point)| {
+                std::thread::spawn(move || {
+                    let (comm, rand) = KZG_Bls12_381::commit_shared(&shared, &p, None, None)?;
+                    let proof = KZG_Bls12_381::open_shared(&shared, &p, point, &rand)?;
+                    Ok::<_, Error>((comm, proof))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for ((handle, p), point) in handles.into_iter().zip(&polys).zip(&points) {
+            let (comm, proof) = handle.join().unwrap().unwrap();
+            let (expected, _) = KZG_Bls12_381::commit(&powers, p, None, None).unwrap();
+            assert_eq!(comm, expected);
+            let value = p.evaluate(point);
+            assert!(KZG_Bls12_381::check(&vk, &comm, *point, value, &proof).unwrap());
+        }
+        assert_eq!(Arc::strong_count(&shared.powers_of_g), 1);
+    }
+
+    #[test]
+    fn check_hiding_detailed_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, Some(1), Some(rng)).unwrap();
+        let point = Fr::rand(rng);
+        let value = p.evaluate(&point);
+        let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+
+        let details =
+            KZG_Bls12_381::check_hiding_detailed(&vk, &comm, point, value, &proof, &rand).unwrap();
+        assert_eq!(details.failure, None);
+        assert!(!details.hiding_term.is_zero());
+
+        let mut bad_proof = proof.clone();
+        bad_proof.random_v = bad_proof.random_v.map(|v| v + &Fr::one());
+        let details =
+            KZG_Bls12_381::check_hiding_detailed(&vk, &comm, point, value, &bad_proof, &rand)
+                .unwrap();
+        assert_eq!(details.failure, Some(HidingCheckFailure::HidingTerm));
+
+        let bad_value = value + &Fr::one();
+        let details =
+            KZG_Bls12_381::check_hiding_detailed(&vk, &comm, point, bad_value, &proof, &rand)
+                .unwrap();
+        assert_eq!(details.failure, Some(HidingCheckFailure::BindingTerm));
+    }
+
+    #[test]
+    fn dense_powers_of_gamma_g_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let mut pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let powers_of_gamma_g = pp
+            .dense_powers_of_gamma_g()
+            .unwrap()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(powers_of_gamma_g.len(), pp.powers_of_gamma_g.len());
+        let flat_powers = Powers::<Bls12_381> {
+            powers_of_g: powers.powers_of_g.clone(),
+            powers_of_gamma_g: ark_std::borrow::Cow::Borrowed(&powers_of_gamma_g[..=degree]),
+            parallel_threshold: powers.parallel_threshold,
+        };
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, _) = KZG_Bls12_381::commit(&powers, &p, Some(1), Some(&mut test_rng())).unwrap();
+        let (flat_comm, _) =
+            KZG_Bls12_381::commit(&flat_powers, &p, Some(1), Some(&mut test_rng())).unwrap();
+        assert_eq!(comm, flat_comm);
+
+        pp.powers_of_gamma_g.remove(&1);
+        assert!(pp.dense_powers_of_gamma_g().is_none());
+    }
+
+    #[test]
+    fn bound_proof_test() {
+        use blake2::Blake2s;
+
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, Some(1), Some(rng)).unwrap();
+        let point = Fr::rand(rng);
+        let value = p.evaluate(&point);
+        let proof =
+            KZG_Bls12_381::open_bound::<Blake2s>(&powers, &p, &comm, point, b"A", &rand).unwrap();
+
+        assert!(
+            KZG_Bls12_381::check_bound::<Blake2s>(&vk, &comm, point, value, b"A", &proof).unwrap()
+        );
+        assert!(
+            !KZG_Bls12_381::check_bound::<Blake2s>(&vk, &comm, point, value, b"B", &proof).unwrap()
+        );
+    }
+
+    #[test]
+    fn scratch_test() {
+        let rng = &mut test_rng();
+        let degree = 20;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let mut scratch = Scratch::new();
+        for &(d, hiding_bound) in &[(20, Some(1)), (5, None), (12, Some(2)), (7, None)] {
+            let p = UniPoly_381::rand(d, rng);
+            let point = Fr::rand(rng);
+
+            let (comm, rand) =
+                KZG_Bls12_381::commit(&powers, &p, hiding_bound, Some(&mut test_rng())).unwrap();
+            let (scratch_comm, scratch_rand) = KZG_Bls12_381::commit_with_scratch(
+                &powers,
+                &p,
+                hiding_bound,
+                Some(&mut test_rng()),
+                &mut scratch,
+            )
+            .unwrap();
+            assert_eq!(comm, scratch_comm);
+            assert_eq!(rand, scratch_rand);
+
+            let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+            let scratch_proof =
+                KZG_Bls12_381::open_with_scratch(&powers, &p, point, &rand, &mut scratch).unwrap();
+            assert_eq!(proof, scratch_proof);
+        }
+    }
+
+    #[test]
+    fn check_projective_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let mut comms = Vec::new();
+        let mut points = Vec::new();
+        let mut values = Vec::new();
+        let mut proofs = Vec::new();
+        for hiding_bound in vec![None, Some(1)] {
+            let p = UniPoly_381::rand(degree, rng);
+            let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, hiding_bound, Some(rng)).unwrap();
+            let point = Fr::rand(rng);
+            let value = p.evaluate(&point);
+            let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+
+            let projective = comm.0.into_projective();
+            for v in vec![value, value + &Fr::one()] {
+                assert_eq!(
+                    KZG_Bls12_381::check_projective(&vk, projective, point, v, &proof).unwrap(),
+                    KZG_Bls12_381::check(&vk, &comm, point, v, &proof).unwrap()
+                );
+            }
+
+            comms.push(comm);
+            points.push(point);
+            values.push(value);
+            proofs.push(proof);
+        }
+
+        let projective_comms = comms
+            .iter()
+            .map(|c| c.0.into_projective())
+            .collect::<Vec<_>>();
+        assert!(KZG_Bls12_381::batch_check(&vk, &comms, &points, &values, &proofs, rng).unwrap());
+        assert!(KZG_Bls12_381::batch_check_projective(
+            &vk,
+            &projective_comms,
+            &points,
+            &values,
+            &proofs,
+            rng
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn commit_from_coset_evaluations_test() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+
+        let rng = &mut test_rng();
+        let domain = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        let degree = domain.size() - 1;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let offset = Fr::from(7u64);
+        let evals = domain
+            .elements()
+            .map(|w| p.evaluate(&(offset * &w)))
+            .collect::<Vec<_>>();
+
+        let (expected, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+        let comm =
+            KZG_Bls12_381::commit_from_coset_evaluations(&powers, &domain, offset, &evals).unwrap();
+        assert_eq!(comm, expected);
+        assert!(matches!(
+            KZG_Bls12_381::commit_from_coset_evaluations(&powers, &domain, offset, &evals[1..]),
+            Err(Error::IncorrectInputLength(_))
+        ));
+        assert!(matches!(
+            KZG_Bls12_381::commit_from_coset_evaluations(&powers, &domain, Fr::zero(), &evals),
+            Err(Error::ZeroCosetOffset)
+        ));
+    }
+
+    #[test]
+    fn self_check_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, Some(1), Some(rng)).unwrap();
+        let point = Fr::rand(rng);
+        let value = p.evaluate(&point);
+        let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+        assert!(KZG_Bls12_381::self_check(&vk, &comm, point, value, &proof));
+
+        let (mut witness, hiding_witness) =
+            KZG_Bls12_381::compute_witness_polynomial(&p, point, &rand).unwrap();
+        witness.coeffs[0] += &Fr::one();
+        let bad_proof = KZG_Bls12_381::open_with_witness_polynomial(
+            &powers,
+            point,
+            &rand,
+            &witness,
+            hiding_witness.as_ref(),
+        )
+        .unwrap();
+        assert!(!KZG_Bls12_381::self_check(
+            &vk, &comm, point, value, &bad_proof
+        ));
+    }
+
+    #[test]
+    fn hiding_polynomial_degree_test() {
+        use ark_std::borrow::Cow;
+
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let p = UniPoly_381::rand(degree, rng);
+
+        for num_queries in 1..=4 {
+            let (comm, rand) =
+                KZG_Bls12_381::commit(&powers, &p, Some(num_queries), Some(rng)).unwrap();
+            assert_eq!(rand.blinding_polynomial.degree(), num_queries + 1);
+            for _ in 0..num_queries {
+                let point = Fr::rand(rng);
+                let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+                let value = p.evaluate(&point);
+                assert!(KZG_Bls12_381::check(&vk, &comm, point, value, &proof).unwrap());
+            }
+        }
+
+        // Four powers of `\gamma G` support hiding for up to two queries.
+        let short_powers = Powers::<Bls12_381> {
+            powers_of_g: powers.powers_of_g.clone(),
+            powers_of_gamma_g: Cow::Owned(powers.powers_of_gamma_g[..4].to_vec()),
+            parallel_threshold: powers.parallel_threshold,
+        };
+        assert!(KZG_Bls12_381::commit(&short_powers, &p, Some(2), Some(rng)).is_ok());
+        assert!(matches!(
+            KZG_Bls12_381::commit(&short_powers, &p, Some(3), Some(rng)),
+            Err(Error::HidingBoundToolarge {
+                hiding_poly_degree: 4,
+                num_powers: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn consistent_commitments_test() {
+        let rng = &mut test_rng();
+        let pp = KZG_Bls12_381::setup(20, false, rng).unwrap();
+        let (powers_a, vk_a) = KZG_Bls12_381::trim(&pp, 10).unwrap();
+        let (powers_b, vk_b) = KZG_Bls12_381::trim(&pp, 20).unwrap();
+
+        let p = UniPoly_381::rand(10, rng);
+        let q = UniPoly_381::rand(10, rng);
+        let (comm_a, rand_a) = KZG_Bls12_381::commit(&powers_a, &p, Some(1), Some(rng)).unwrap();
+        let (comm_b, rand_b) = KZG_Bls12_381::commit(&powers_b, &p, Some(1), Some(rng)).unwrap();
+        let (comm_q, rand_q) = KZG_Bls12_381::commit(&powers_b, &q, Some(1), Some(rng)).unwrap();
+
+        let challenge = Fr::rand(rng);
+        let (proof_a, proof_b, value) = KZG_Bls12_381::prove_consistent_commitments(
+            &powers_a, &powers_b, &p, &rand_a, &rand_b, challenge,
+        )
+        .unwrap();
+        assert!(KZG_Bls12_381::check_consistent_commitments(
+            &vk_a, &vk_b, &comm_a, &comm_b, challenge, value, &proof_a, &proof_b
+        )
+        .unwrap());
+
+        // A prover claiming that `comm_q` commits to `p` must open `q` at the
+        // challenge, which yields a different value.
+        let proof_q = KZG_Bls12_381::open(&powers_b, &q, challenge, &rand_q).unwrap();
+        assert!(!KZG_Bls12_381::check_consistent_commitments(
+            &vk_a, &vk_b, &comm_a, &comm_q, challenge, value, &proof_a, &proof_q
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn constant_commit_test() {
+        let rng = &mut test_rng();
+        let degree = 4;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let constants = vec![Fr::zero(), Fr::one(), -Fr::one(), Fr::rand(rng)];
+        for c in constants {
+            let p = UniPoly_381::from_coefficients_slice(&[c]);
+            let expected = KZG_Bls12_381::commit_shifted_by(&powers, &p, 0).unwrap();
+            let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+            assert_eq!(comm, expected);
+            assert!(rand.blinding_polynomial.is_zero());
+
+            let (hiding_comm, rand) =
+                KZG_Bls12_381::commit(&powers, &p, Some(1), Some(rng)).unwrap();
+            let blinding = rand
+                .blinding_polynomial
+                .coeffs
+                .iter()
+                .zip(powers.powers_of_gamma_g.iter())
+                .fold(
+                    <Bls12_381 as PairingEngine>::G1Projective::zero(),
+                    |acc, (r, g)| acc + &g.mul(*r),
+                );
+            assert_eq!(
+                hiding_comm.0,
+                (expected.0.into_projective() + &blinding).into_affine()
+            );
+        }
+    }
+
+    #[test]
+    fn commit_cache_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let mut cache = CommitCache::new(2);
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, _) = KZG_Bls12_381::commit_cached(&powers, &p, None, None, &mut cache).unwrap();
+        assert_eq!(cache.hits(), 0);
+        let (cached, _) =
+            KZG_Bls12_381::commit_cached(&powers, &p, None, None, &mut cache).unwrap();
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cached, comm);
+        assert_eq!(
+            comm,
+            KZG_Bls12_381::commit(&powers, &p, None, None).unwrap().0
+        );
+
+        // Hiding commitments bypass the cache.
+        let (hiding, _) =
+            KZG_Bls12_381::commit_cached(&powers, &p, Some(1), Some(rng), &mut cache).unwrap();
+        assert_ne!(hiding, comm);
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.len(), 1);
+
+        // The cache is bounded, and evicts `p` first.
+        for _ in 0..2 {
+            let q = UniPoly_381::rand(degree, rng);
+            KZG_Bls12_381::commit_cached(&powers, &q, None, None, &mut cache).unwrap();
+        }
+        assert_eq!(cache.len(), 2);
+        KZG_Bls12_381::commit_cached(&powers, &p, None, None, &mut cache).unwrap();
+        assert_eq!(cache.hits(), 1);
+    }
+
+    #[test]
+    fn chunked_test() {
+        let rng = &mut test_rng();
+        let degree = 8;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        // Degrees above that supported by the SRS, with the last chunk of
+        // degree 0 in one case.
+        for &(p_degree, chunk_degree) in &[(30, 7), (24, 7), (20, 8)] {
+            let p = UniPoly_381::rand(p_degree, rng);
+            assert!(KZG_Bls12_381::commit(&powers, &p, None, None).is_err());
+
+            let comms = KZG_Bls12_381::commit_chunked(&powers, &p, chunk_degree).unwrap();
+            assert_eq!(comms.len(), p_degree / (chunk_degree + 1) + 1);
+
+            let point = Fr::rand(rng);
+            let value = p.evaluate(&point);
+            let proof = KZG_Bls12_381::open_chunked(&powers, &p, chunk_degree, point).unwrap();
+            assert!(KZG_Bls12_381::check_chunked(
+                &vk,
+                &comms,
+                chunk_degree,
+                point,
+                value,
+                &proof,
+                rng
+            )
+            .unwrap());
+            assert!(!KZG_Bls12_381::check_chunked(
+                &vk,
+                &comms,
+                chunk_degree,
+                point,
+                value + &Fr::one(),
+                &proof,
+                rng
+            )
+            .unwrap());
+
+            let mut bad_proof = proof.clone();
+            bad_proof.chunk_values[0] += &Fr::one();
+            bad_proof.chunk_values[1] -=
+                &point.pow(&[(chunk_degree + 1) as u64]).inverse().unwrap();
+            assert!(!KZG_Bls12_381::check_chunked(
+                &vk,
+                &comms,
+                chunk_degree,
+                point,
+                value,
+                &bad_proof,
+                rng
+            )
+            .unwrap());
+        }
+        assert!(KZG_Bls12_381::commit_chunked(&powers, &UniPoly_381::rand(20, rng), 9).is_err());
+    }
+
+    #[test]
+    fn check_fully_prepared_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let prepared_vk = PreparedVerifierKey::prepare(&vk);
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+        let prepared_comm = PreparedCommitment::prepare(&comm);
+        for _ in 0..4 {
+            let point = Fr::rand(rng);
+            let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+            for &value in &[p.evaluate(&point), Fr::rand(rng)] {
+                let expected = KZG_Bls12_381::check(&vk, &comm, point, value, &proof).unwrap();
+                let result = KZG_Bls12_381::check_fully_prepared(
+                    &prepared_vk,
+                    &prepared_comm,
+                    point,
+                    value,
+                    &proof,
+                )
+                .unwrap();
+                assert_eq!(result, expected);
+            }
+        }
+
+        let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, Some(1), Some(rng)).unwrap();
+        let point = Fr::rand(rng);
+        let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+        assert!(matches!(
+            KZG_Bls12_381::check_fully_prepared(
+                &prepared_vk,
+                &PreparedCommitment::prepare(&comm),
+                point,
+                p.evaluate(&point),
+                &proof,
+            ),
+            Err(Error::HidingProofUnsupported)
+        ));
+
+        let proof = KZG_Bls12_381::open(&powers, &p, point, &Randomness::empty()).unwrap();
+        assert!(matches!(
+            KZG_Bls12_381::check_fully_prepared(
+                &prepared_vk,
+                &PreparedCommitment(Vec::new()),
+                point,
+                p.evaluate(&point),
+                &proof,
+            ),
+            Err(Error::InvalidGroupElement)
+        ));
+    }
+
+    #[test]
+    fn commit_from_reprs_test() {
+        use ark_ff::{FpParameters, PrimeField};
+
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        for &d in &[0, 1, 5, degree] {
+            let p = UniPoly_381::rand(d, rng);
+            let reprs = p.coeffs.iter().map(|c| c.into_repr()).collect::<Vec<_>>();
+            let (expected, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+            let comm = KZG_Bls12_381::commit_from_reprs(&powers, &reprs).unwrap();
+            assert_eq!(comm, expected);
+        }
+
+        let mut reprs = vec![Fr::one().into_repr(); 3];
+        reprs[1] = <Fr as PrimeField>::Params::MODULUS;
+        assert!(matches!(
+            KZG_Bls12_381::commit_from_reprs(&powers, &reprs),
+            Err(Error::InvalidFieldElement)
+        ));
+        let reprs = vec![Fr::one().into_repr(); degree + 2];
+        assert!(matches!(
+            KZG_Bls12_381::commit_from_reprs(&powers, &reprs),
+            Err(Error::TooManyCoefficients { .. })
+        ));
+    }
+
+    #[test]
+    fn check_with_shift_test() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let omega = GeneralEvaluationDomain::<Fr>::new(16).unwrap().element(1);
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+        let z = Fr::rand(rng);
+        let (value_at_z, value_at_zw) = (p.evaluate(&z), p.evaluate(&(z * &omega)));
+        let proof_z = KZG_Bls12_381::open(&powers, &p, z, &rand).unwrap();
+        let proof_zw = KZG_Bls12_381::open(&powers, &p, z * &omega, &rand).unwrap();
+
+        let values = KZG_Bls12_381::check_with_shift(
+            &vk,
+            &comm,
+            z,
+            omega,
+            value_at_z,
+            value_at_zw,
+            &proof_z,
+            &proof_zw,
+        )
+        .unwrap();
+        assert_eq!(values, Some((value_at_z, value_at_zw)));
+
+        // Swapping the openings makes both of them fail.
+        assert!(KZG_Bls12_381::check_with_shift(
+            &vk,
+            &comm,
+            z,
+            omega,
+            value_at_zw,
+            value_at_z,
+            &proof_zw,
+            &proof_z,
+        )
+        .unwrap()
+        .is_none());
+    }
+
+    #[test]
+    fn empty_powers_test() {
+        let rng = &mut test_rng();
+        let degree = 4;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        assert!(!powers.is_empty());
+
+        let empty = Powers::<Bls12_381> {
+            powers_of_g: ark_std::borrow::Cow::Owned(Vec::new()),
+            powers_of_gamma_g: ark_std::borrow::Cow::Owned(Vec::new()),
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+        };
+        assert!(empty.is_empty());
+
+        for p in &[
+            UniPoly_381::from_coefficients_slice(&[Fr::one()]),
+            UniPoly_381::rand(degree, rng),
+        ] {
+            assert!(matches!(
+                KZG_Bls12_381::commit(&empty, p, None, None),
+                Err(Error::EmptyPowers)
+            ));
+            assert!(matches!(
+                KZG_Bls12_381::open(&empty, p, Fr::rand(rng), &Randomness::empty()),
+                Err(Error::EmptyPowers)
+            ));
+        }
+    }
+
+    #[test]
+    fn nested_test() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm_p, rand_p) = KZG_Bls12_381::commit(&powers, &p, Some(1), Some(rng)).unwrap();
+        let z = Fr::rand(rng);
+
+        // A committed vector of values whose third entry is `p(z)`.
+        let domain = GeneralEvaluationDomain::<Fr>::new(8).unwrap();
+        let mut values = (0..8).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        values[3] = p.evaluate(&z);
+        let values_poly = UniPoly_381::from_coefficients_vec(domain.ifft(&values));
+        let (comm_values, rand_values) =
+            KZG_Bls12_381::commit(&powers, &values_poly, Some(1), Some(rng)).unwrap();
+
+        let index_point = domain.element(3);
+        let proof = KZG_Bls12_381::open_nested(
+            &powers,
+            &p,
+            &rand_p,
+            z,
+            &values_poly,
+            &rand_values,
+            index_point,
+        )
+        .unwrap();
+        assert!(
+            KZG_Bls12_381::check_nested(&vk, &comm_p, z, &comm_values, index_point, &proof)
+                .unwrap()
+        );
+
+        // The value at another index differs from `p(z)`.
+        let other_point = domain.element(2);
+        let proof = KZG_Bls12_381::open_nested(
+            &powers,
+            &p,
+            &rand_p,
+            z,
+            &values_poly,
+            &rand_values,
+            other_point,
+        )
+        .unwrap();
+        assert!(
+            !KZG_Bls12_381::check_nested(&vk, &comm_p, z, &comm_values, other_point, &proof)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn commit_blinding_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+        let (hiding_comm, rand) = KZG_Bls12_381::commit(&powers, &p, Some(2), Some(rng)).unwrap();
+        let blinding_comm = rand.commit_blinding(&powers).unwrap();
+        assert_eq!(
+            (comm.0.into_projective() + &blinding_comm.0.into_projective()).into_affine(),
+            hiding_comm.0
+        );
+        assert_eq!(
+            Randomness::<Fr, UniPoly_381>::empty()
+                .commit_blinding(&powers)
+                .unwrap(),
+            Commitment::empty()
+        );
+    }
+
+    #[test]
+    fn pok_test() {
+        use blake2::Blake2s;
+        let rng = &mut test_rng();
+        let pp = KZG_Bls12_381::setup(15, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, 15).unwrap();
+
+        let p = UniPoly_381::rand(12, rng);
+        let (comm, pok) = KZG_Bls12_381::commit_with_pok::<Blake2s, _>(&powers, &p, rng).unwrap();
+        let (expected_comm, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+        assert_eq!(comm, expected_comm);
+        assert!(KZG_Bls12_381::verify_pok::<Blake2s>(&powers, &comm, &pok).unwrap());
+
+        // A proof for different coefficients does not verify against `comm`.
+        let q = UniPoly_381::rand(12, rng);
+        let (_, forged) = KZG_Bls12_381::commit_with_pok::<Blake2s, _>(&powers, &q, rng).unwrap();
+        assert!(!KZG_Bls12_381::verify_pok::<Blake2s>(&powers, &comm, &forged).unwrap());
+
+        // Neither does a proof with a tampered response.
+        let mut tampered = pok.clone();
+        tampered.responses[3] += Fr::one();
+        assert!(!KZG_Bls12_381::verify_pok::<Blake2s>(&powers, &comm, &tampered).unwrap());
+    }
+
+    #[test]
+    fn rotations_test() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+        use blake2::Blake2s;
+        let rng = &mut test_rng();
+        let degree = 20;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+        let omega = GeneralEvaluationDomain::<Fr>::new(8).unwrap().element(1);
+        let z = Fr::rand(rng);
+        let challenge = Fr::rand(rng);
+
+        let (values, proof) =
+            KZG_Bls12_381::open_rotations::<Blake2s>(&powers, &p, z, omega, 4, challenge).unwrap();
+        let mut point = z;
+        for value in &values {
+            assert_eq!(*value, p.evaluate(&point));
+            point *= &omega;
+        }
+        assert!(KZG_Bls12_381::check_rotations::<Blake2s>(
+            &vk, &comm, z, omega, &values, challenge, &proof
+        )
+        .unwrap());
+
+        // A wrong evaluation, or a proof for another challenge, is rejected.
+        let mut bad_values = values.clone();
+        bad_values[2] += Fr::one();
+        assert!(!KZG_Bls12_381::check_rotations::<Blake2s>(
+            &vk,
+            &comm,
+            z,
+            omega,
+            &bad_values,
+            challenge,
+            &proof
+        )
+        .unwrap());
+        assert!(!KZG_Bls12_381::check_rotations::<Blake2s>(
+            &vk,
+            &comm,
+            z,
+            omega,
+            &values,
+            challenge + Fr::one(),
+            &proof
+        )
+        .unwrap());
+
+        // `-1` has order 2, so three rotations repeat a point.
+        let result =
+            KZG_Bls12_381::open_rotations::<Blake2s>(&powers, &p, z, -Fr::one(), 3, challenge);
+        assert!(matches!(result, Err(Error::RepeatedPoint)));
+    }
+
+    #[test]
+    fn commitment_canonical_eq_test() {
+        use ark_bls12_381::{Fq, G1Affine};
+        let rng = &mut test_rng();
+        let pp = KZG_Bls12_381::setup(10, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, 10).unwrap();
+
+        // The same point, computed directly and as a sum of commitments.
+        let p = UniPoly_381::rand(10, rng);
+        let q = UniPoly_381::rand(10, rng);
+        let (comm_p, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+        let (comm_q, _) = KZG_Bls12_381::commit(&powers, &q, None, None).unwrap();
+        let (comm_sum, _) = KZG_Bls12_381::commit(&powers, &(&p + &q), None, None).unwrap();
+        let mut acc = comm_p;
+        acc += (Fr::one(), &comm_q);
+        assert_eq!(acc, comm_sum);
+
+        // The point at infinity, however its coordinates are encoded.
+        let (comm_zero, _) =
+            KZG_Bls12_381::commit(&powers, &UniPoly_381::zero(), None, None).unwrap();
+        let odd_infinity = Commitment::<Bls12_381>(G1Affine::new(Fq::one(), Fq::one(), true));
+        assert_eq!(comm_zero, Commitment::empty());
+        assert_eq!(odd_infinity, Commitment::empty());
+        assert_eq!(odd_infinity, comm_zero);
+        let hash = |c: &Commitment<Bls12_381>| {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            c.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&odd_infinity), hash(&comm_zero));
+        let odd_proof = Proof::<Bls12_381> {
+            w: odd_infinity.0,
+            random_v: None,
+        };
+        assert_eq!(odd_proof, Proof::default());
+
+        // A point and its negation remain distinct.
+        assert_ne!(comm_p, Commitment(-comm_p.0));
+    }
+
+    #[test]
+    fn parallel_threshold_test() {
+        let rng = &mut test_rng();
+        let degree = 100;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let point = Fr::rand(rng);
+
+        for d in (2..=degree).step_by(7) {
+            let scalars = convert_to_bigints(
+                &UniPoly_381::rand(d, rng).coeffs,
+                DEFAULT_PARALLEL_THRESHOLD,
+            );
+            assert_eq!(
+                naive_multi_scalar_mul(&powers.powers_of_g, &scalars),
+                VariableBaseMSM::multi_scalar_mul(&powers.powers_of_g, &scalars)
+            );
+        }
+
+        let results = [0, usize::MAX]
+            .iter()
+            .map(|&threshold| {
+                let mut powers = powers.clone();
+                powers.parallel_threshold = threshold;
+                let mut scratch = Scratch::new();
+                (2..=degree)
+                    .step_by(7)
+                    .map(|d| {
+                        let rng = &mut test_rng();
+                        let p = UniPoly_381::rand(d, rng);
+                        let (comm, rand) = KZG_Bls12_381::commit_with_scratch(
+                            &powers,
+                            &p,
+                            Some(1),
+                            Some(rng),
+                            &mut scratch,
+                        )
+                        .unwrap();
+                        let proof = KZG_Bls12_381::open_with_scratch(
+                            &powers,
+                            &p,
+                            point,
+                            &rand,
+                            &mut scratch,
+                        )
+                        .unwrap();
+                        assert!(KZG_Bls12_381::check(
+                            &vk,
+                            &comm,
+                            point,
+                            p.evaluate(&point),
+                            &proof
+                        )
+                        .unwrap());
+
+                        let (plain_comm, plain_rand) =
+                            KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+                        let plain_proof =
+                            KZG_Bls12_381::open(&powers, &p, point, &plain_rand).unwrap();
+                        assert!(KZG_Bls12_381::check(
+                            &vk,
+                            &plain_comm,
+                            point,
+                            p.evaluate(&point),
+                            &plain_proof
+                        )
+                        .unwrap());
+                        (comm, proof, plain_comm, plain_proof)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(results[0], results[1]);
+    }
+
+    #[test]
+    fn commitment_tag_test() {
+        use ark_std::convert::TryFrom;
+        let rng = &mut test_rng();
+        let pp = KZG_Bls12_381::setup(10, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, 10).unwrap();
+
+        let p = UniPoly_381::rand(10, rng);
+        let q = UniPoly_381::rand(10, rng);
+        let (comm_p, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+        let (comm_q, _) = KZG_Bls12_381::commit(&powers, &q, None, None).unwrap();
+        let (comm_sum, _) = KZG_Bls12_381::commit(&powers, &(&p + &q), None, None).unwrap();
+        let mut acc = comm_p;
+        acc += (Fr::one(), &comm_q);
+        assert_eq!(acc.tag(), comm_sum.tag());
+        assert_ne!(comm_p.tag(), comm_q.tag());
+
+        let round_tripped = Commitment::<Bls12_381>::try_from(comm_p.to_vec().as_slice()).unwrap();
+        assert_eq!(round_tripped.tag(), comm_p.tag());
+    }
+
+    #[test]
+    fn check_linked_test() {
+        let rng = &mut test_rng();
+        let degree = 12;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let (z, z_prime) = (Fr::rand(rng), Fr::rand(rng));
+        let p = UniPoly_381::rand(degree, rng);
+        let v = p.evaluate(&z);
+        let value_comm = pp.powers_of_g[0].mul(v).into_affine();
+
+        // `q` opens at `z_prime` to `v`, whereas `r` does not.
+        let mut q = UniPoly_381::rand(degree, rng);
+        let offset = v - q.evaluate(&z_prime);
+        q.coeffs[0] += offset;
+        let r = UniPoly_381::rand(degree, rng);
+
+        let (comm_p, rand_p) = KZG_Bls12_381::commit(&powers, &p, Some(1), Some(rng)).unwrap();
+        let proof_p = KZG_Bls12_381::open(&powers, &p, z, &rand_p).unwrap();
+        for (other, expected) in [(q, true), (r, false)].iter() {
+            let (comm, rand) = KZG_Bls12_381::commit(&powers, other, None, None).unwrap();
+            let proof = KZG_Bls12_381::open(&powers, other, z_prime, &rand).unwrap();
+            assert_eq!(
+                KZG_Bls12_381::check_linked(
+                    &vk,
+                    &comm_p,
+                    z,
+                    &proof_p,
+                    &comm,
+                    z_prime,
+                    &proof,
+                    &value_comm
+                )
+                .unwrap(),
+                *expected
+            );
+        }
+    }
+
+    #[test]
+    fn allocation_cap_test() {
+        let rng = &mut test_rng();
+        let cap = 1 << 26;
+        let result = KZG_Bls12_381::setup_with_allocation_cap(1 << 24, true, cap, rng);
+        assert!(matches!(
+            result,
+            Err(Error::AllocationTooLarge { requested_bytes }) if requested_bytes > cap
+        ));
+
+        let pp = KZG_Bls12_381::setup_with_allocation_cap(8, true, cap, rng).unwrap();
+        assert_eq!(pp.allocation_cap, cap);
+        assert!(check_trim_allocation(&pp, 8, 1, true).is_ok());
+        let low_cap_pp = UniversalParams {
+            allocation_cap: 64,
+            ..pp
+        };
+        assert!(matches!(
+            check_trim_allocation(&low_cap_pp, 8, 1, true),
+            Err(Error::AllocationTooLarge { requested_bytes }) if requested_bytes > 64
+        ));
+
+        // The cap is unbounded by default.
+        let pp = KZG_Bls12_381::setup(8, true, rng).unwrap();
+        assert_eq!(pp.allocation_cap, usize::MAX);
+    }
+
+    #[test]
+    fn scalar_multiple_test() {
+        use blake2::Blake2s;
+        let rng = &mut test_rng();
+        let degree = 12;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let c = Fr::rand(rng);
+        let p1 = UniPoly_381::rand(degree, rng);
+        let p2 = &p1 * c;
+        let other = UniPoly_381::rand(degree, rng);
+
+        // Public `c`: compare the commitments.
+        let (comm1, _) = KZG_Bls12_381::commit(&powers, &p1, None, None).unwrap();
+        let (comm2, _) = KZG_Bls12_381::commit(&powers, &p2, None, None).unwrap();
+        let (comm_other, _) = KZG_Bls12_381::commit(&powers, &other, None, None).unwrap();
+        assert!(KZG_Bls12_381::check_public_scalar_multiple(
+            &comm1, &comm2, c
+        ));
+        assert!(!KZG_Bls12_381::check_public_scalar_multiple(
+            &comm1,
+            &comm_other,
+            c
+        ));
+
+        // Secret `c`, with hiding commitments.
+        let (comm1, rand1) = KZG_Bls12_381::commit(&powers, &p1, Some(1), Some(rng)).unwrap();
+        let challenge = Fr::rand(rng);
+        for (p, expected) in [(&p2, true), (&other, false)].iter() {
+            let (comm, rand) = KZG_Bls12_381::commit(&powers, p, Some(1), Some(rng)).unwrap();
+            let proof = KZG_Bls12_381::prove_scalar_multiple::<Blake2s>(
+                &powers, &p1, &rand1, &rand, c, challenge, rng,
+            )
+            .unwrap();
+            assert_eq!(
+                KZG_Bls12_381::check_scalar_multiple::<Blake2s>(
+                    &vk, &comm1, &comm, challenge, &proof
+                )
+                .unwrap(),
+                *expected
+            );
+        }
+    }
+
+    #[test]
+    fn compressed_bytes_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let p = UniPoly_381::rand(degree, rng);
+        let point = Fr::rand(rng);
+
+        const COMM_SIZE: usize = 48;
+        const PROOF_SIZE: usize = 48 + 1 + 32;
+        assert_eq!(Commitment::<Bls12_381>::compressed_size(), COMM_SIZE);
+        assert_eq!(Proof::<Bls12_381>::compressed_size(), PROOF_SIZE);
+
+        for hiding_bound in [None, Some(1)].iter() {
+            let (comm, rand) =
+                KZG_Bls12_381::commit(&powers, &p, *hiding_bound, Some(rng)).unwrap();
+            let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+            assert_eq!(proof.random_v.is_some(), hiding_bound.is_some());
+
+            let comm_bytes = comm.to_compressed_bytes::<COMM_SIZE>().unwrap();
+            assert_eq!(
+                Commitment::from_compressed_bytes(&comm_bytes).unwrap(),
+                comm
+            );
+            let proof_bytes = proof.to_compressed_bytes::<PROOF_SIZE>().unwrap();
+            assert_eq!(proof_bytes[COMM_SIZE], hiding_bound.is_some() as u8);
+            assert_eq!(Proof::from_compressed_bytes(&proof_bytes).unwrap(), proof);
+
+            let mut bad_presence = proof_bytes;
+            bad_presence[COMM_SIZE] = 2;
+            assert!(matches!(
+                Proof::<Bls12_381>::from_compressed_bytes(&bad_presence),
+                Err(Error::MalformedEncoding(_))
+            ));
+            let mut bad_point = proof_bytes;
+            bad_point[0] ^= 1;
+            bad_point[COMM_SIZE - 1] ^= 1;
+            assert!(Proof::<Bls12_381>::from_compressed_bytes(&bad_point).is_err());
+        }
+
+        let mut bad_padding = Proof::<Bls12_381>::default()
+            .to_compressed_bytes::<PROOF_SIZE>()
+            .unwrap();
+        bad_padding[PROOF_SIZE - 1] = 1;
+        assert!(matches!(
+            Proof::<Bls12_381>::from_compressed_bytes(&bad_padding),
+            Err(Error::MalformedEncoding(_))
+        ));
+        let bad_field = [0xffu8; 32];
+        let mut bad_random_v = bad_padding;
+        bad_random_v[COMM_SIZE] = 1;
+        bad_random_v[COMM_SIZE + 1..].copy_from_slice(&bad_field);
+        assert!(matches!(
+            Proof::<Bls12_381>::from_compressed_bytes(&bad_random_v),
+            Err(Error::InvalidFieldElement)
+        ));
+
+        let comm = Commitment::<Bls12_381>::empty();
+        assert!(comm.to_compressed_bytes::<32>().is_err());
+        assert!(Commitment::<Bls12_381>::from_compressed_bytes(&[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn batch_check_multi_key_test() {
+        let rng = &mut test_rng();
+        let pp = KZG_Bls12_381::setup(20, false, rng).unwrap();
+        let (small_powers, small_vk) = KZG_Bls12_381::trim(&pp, 5).unwrap();
+        let (large_powers, large_vk) = KZG_Bls12_381::trim(&pp, 20).unwrap();
+
+        let mut entries = Vec::new();
+        for (powers, vk, degree, hiding_bound) in [
+            (&small_powers, &small_vk, 5, None),
+            (&large_powers, &large_vk, 20, Some(1)),
+            (&small_powers, &small_vk, 3, Some(1)),
+            (&large_powers, &large_vk, 15, None),
+        ]
+        .iter()
+        {
+            let p = UniPoly_381::rand(*degree, rng);
+            let (comm, rand) = KZG_Bls12_381::commit(powers, &p, *hiding_bound, Some(rng)).unwrap();
+            let point = Fr::rand(rng);
+            let proof = KZG_Bls12_381::open(powers, &p, point, &rand).unwrap();
+            entries.push((*vk, comm, point, p.evaluate(&point), proof));
+        }
+        assert!(KZG_Bls12_381::batch_check_multi_key(&entries, rng).unwrap());
+
+        for i in 0..entries.len() {
+            let mut tampered = entries.clone();
+            tampered[i].3 += Fr::one();
+            assert!(!KZG_Bls12_381::batch_check_multi_key(&tampered, rng).unwrap());
+        }
+
+        let other_pp = KZG_Bls12_381::setup(5, false, rng).unwrap();
+        let (_, other_vk) = KZG_Bls12_381::trim(&other_pp, 5).unwrap();
+        let mut mismatched = entries.clone();
+        mismatched[0].0 = &other_vk;
+        assert!(matches!(
+            KZG_Bls12_381::batch_check_multi_key(&mismatched, rng),
+            Err(Error::MismatchedVerifierKeys)
+        ));
+    }
+
+    #[test]
+    fn commitment_sub_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        for _ in 0..5 {
+            let p = UniPoly_381::rand(degree, rng);
+            let q = UniPoly_381::rand(degree, rng);
+            let (a, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+            let (b, _) = KZG_Bls12_381::commit(&powers, &q, None, None).unwrap();
+            let (diff, _) = KZG_Bls12_381::commit(&powers, &(&p - &q), None, None).unwrap();
+
+            let mut sum = a;
+            sum += (Fr::one(), &b);
+            assert_eq!(sum - b, a);
+            assert_eq!(a - b, diff);
+            let mut c = a;
+            c -= &a;
+            assert!(c.0.is_zero());
+        }
+    }
+
+    #[test]
+    fn commitment_mul_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let p = UniPoly_381::rand(degree, rng);
+        let (c, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+
+        let mut sum = Commitment::empty();
+        for f in 0..6u64 {
+            assert_eq!(c * Fr::from(f), sum);
+            let mut scaled = c;
+            scaled *= Fr::from(f);
+            assert_eq!(scaled, sum);
+            sum += (Fr::one(), &c);
+        }
+
+        let f = Fr::rand(rng);
+        let (scaled_comm, _) = KZG_Bls12_381::commit(&powers, &(&p * f), None, None).unwrap();
+        assert_eq!(c * f, scaled_comm);
+    }
+
+    #[test]
+    fn verifier_key_serialization_test() {
+        use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let mut compressed = Vec::new();
+        vk.serialize(&mut compressed).unwrap();
+        assert_eq!(compressed.len(), vk.serialized_size());
+        let mut uncompressed = Vec::new();
+        vk.serialize_uncompressed(&mut uncompressed).unwrap();
+        assert_eq!(uncompressed.len(), vk.uncompressed_size());
+        assert!(compressed.len() < uncompressed.len());
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, Some(1), Some(rng)).unwrap();
+        let point = Fr::rand(rng);
+        let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+        for vk2 in vec![
+            VerifierKey::<Bls12_381>::deserialize(&compressed[..]).unwrap(),
+            VerifierKey::<Bls12_381>::deserialize_uncompressed(&uncompressed[..]).unwrap(),
+        ] {
+            assert_eq!(
+                (vk2.g, vk2.gamma_g, vk2.h, vk2.beta_h),
+                (vk.g, vk.gamma_g, vk.h, vk.beta_h)
+            );
+            assert!(KZG_Bls12_381::check(&vk2, &comm, point, p.evaluate(&point), &proof).unwrap());
+        }
+        assert!(VerifierKey::<Bls12_381>::deserialize(&compressed[1..]).is_err());
+    }
+
+    #[test]
+    fn universal_params_serialization_test() {
+        use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+        let rng = &mut test_rng();
+        let degree = 64;
+        let pp = KZG_Bls12_381::setup(degree, true, rng).unwrap();
+
+        let mut compressed = Vec::new();
+        pp.serialize(&mut compressed).unwrap();
+        assert_eq!(compressed.len(), pp.serialized_size());
+        let mut uncompressed = Vec::new();
+        pp.serialize_uncompressed(&mut uncompressed).unwrap();
+        assert_eq!(uncompressed.len(), pp.uncompressed_size());
+
+        let p = UniPoly_381::rand(degree, rng);
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let (comm, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+        for pp2 in vec![
+            UniversalParams::<Bls12_381>::deserialize(&compressed[..]).unwrap(),
+            UniversalParams::<Bls12_381>::deserialize_uncompressed(&uncompressed[..]).unwrap(),
+        ] {
+            assert_eq!(pp2.max_degree(), degree);
+            assert_eq!(pp2.powers_of_g, pp.powers_of_g);
+            assert_eq!(pp2.powers_of_gamma_g, pp.powers_of_gamma_g);
+            assert_eq!(pp2.neg_powers_of_h, pp.neg_powers_of_h);
+            assert_eq!(pp2.neg_powers_of_h.len(), degree + 1);
+            assert_eq!(
+                pp2.prepared_neg_powers_of_h.len(),
+                pp.prepared_neg_powers_of_h.len()
+            );
+            let (powers2, _) = KZG_Bls12_381::trim(&pp2, degree).unwrap();
+            let (comm2, _) = KZG_Bls12_381::commit(&powers2, &p, None, None).unwrap();
+            assert_eq!(comm2, comm);
+        }
+
+        let mut empty = pp.clone();
+        empty.powers_of_g.clear();
+        let mut bytes = Vec::new();
+        empty.serialize(&mut bytes).unwrap();
+        assert!(UniversalParams::<Bls12_381>::deserialize(&bytes[..]).is_err());
+    }
+
+    #[test]
+    fn multiple_points_test() {
+        let rng = &mut test_rng();
+        let degree = 16;
+        let pp = KZG_Bls12_381::setup(degree, true, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        for &num_points in &[1, 2, 5] {
+            let p =
+                LabeledPolynomial::new("p".to_string(), UniPoly_381::rand(degree, rng), None, None);
+            let (comm, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+            let points = (0..num_points).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+            let (proof, values) =
+                KZG_Bls12_381::open_at_multiple_points(&powers, &p, &points).unwrap();
+            assert_eq!(values.len(), num_points);
+            assert!(KZG_Bls12_381::check_at_multiple_points(
+                &vk,
+                &pp.neg_powers_of_h,
+                &comm,
+                &points,
+                &values,
+                &proof
+            )
+            .unwrap());
+
+            for i in 0..num_points {
+                let mut tampered = values.clone();
+                tampered[i] += Fr::one();
+                assert!(!KZG_Bls12_381::check_at_multiple_points(
+                    &vk,
+                    &pp.neg_powers_of_h,
+                    &comm,
+                    &points,
+                    &tampered,
+                    &proof
+                )
+                .unwrap());
+            }
+        }
+
+        let p = LabeledPolynomial::new("p".to_string(), UniPoly_381::rand(4, rng), None, None);
+        let z = Fr::rand(rng);
+        assert!(matches!(
+            KZG_Bls12_381::open_at_multiple_points(&powers, &p, &[z, Fr::rand(rng), z]),
+            Err(Error::RepeatedPoint)
+        ));
+        assert!(KZG_Bls12_381::open_at_multiple_points(&powers, &p, &[]).is_err());
+    }
+
+    #[test]
+    fn neg_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        for hiding_bound in [None, Some(1)].iter() {
+            let p = UniPoly_381::rand(degree, rng);
+            let (comm, rand) =
+                KZG_Bls12_381::commit(&powers, &p, *hiding_bound, Some(rng)).unwrap();
+            let point = Fr::rand(rng);
+            let value = p.evaluate(&point);
+            let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+
+            let mut sum = comm;
+            sum += (Fr::one(), &-comm);
+            assert_eq!(sum, Commitment::empty());
+            assert_eq!(-(-comm), comm);
+
+            let neg_proof = -proof;
+            assert!((proof.w.into_projective() + &neg_proof.w.into_projective()).is_zero());
+            assert_eq!(
+                proof.random_v.zip(neg_proof.random_v).map(|(a, b)| a + &b),
+                hiding_bound.map(|_| Fr::zero())
+            );
+            assert_eq!(-neg_proof, proof);
+            assert!(KZG_Bls12_381::check(&vk, &-comm, point, -value, &neg_proof).unwrap());
+        }
+    }
+
+    #[test]
+    fn batch_check_tampered_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let mut comms = Vec::new();
+        let mut points = Vec::new();
+        let mut values = Vec::new();
+        let mut proofs = Vec::new();
+        for i in 0..8 {
+            let p = UniPoly_381::rand(degree, rng);
+            let hiding_bound = if i % 2 == 0 { None } else { Some(1) };
+            let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, hiding_bound, Some(rng)).unwrap();
+            let point = Fr::rand(rng);
+            comms.push(comm);
+            values.push(p.evaluate(&point));
+            proofs.push(KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap());
+            points.push(point);
+        }
+        assert!(KZG_Bls12_381::batch_check(&vk, &comms, &points, &values, &proofs, rng).unwrap());
+
+        for i in 0..comms.len() {
+            let mut tampered_values = values.clone();
+            tampered_values[i] += Fr::one();
+            assert!(!KZG_Bls12_381::batch_check(
+                &vk,
+                &comms,
+                &points,
+                &tampered_values,
+                &proofs,
+                rng
+            )
+            .unwrap());
+
+            let mut tampered_points = points.clone();
+            tampered_points[i] += Fr::one();
+            assert!(!KZG_Bls12_381::batch_check(
+                &vk,
+                &comms,
+                &tampered_points,
+                &values,
+                &proofs,
+                rng
+            )
+            .unwrap());
+
+            let mut tampered_proofs = proofs.clone();
+            tampered_proofs.swap(i, (i + 1) % comms.len());
+            assert!(!KZG_Bls12_381::batch_check(
+                &vk,
+                &comms,
+                &points,
+                &values,
+                &tampered_proofs,
+                rng
+            )
+            .unwrap());
+        }
+
+        assert!(matches!(
+            KZG_Bls12_381::batch_check(&vk, &comms, &points[1..], &values, &proofs, rng),
+            Err(Error::IncorrectInputLength(_))
+        ));
+    }
+
+    #[test]
+    fn combine_with_coeffs_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        for &n in &[0, 1, 5, 20] {
+            let comms = (0..n)
+                .map(|_| {
+                    let p = UniPoly_381::rand(degree, rng);
+                    KZG_Bls12_381::commit(&powers, &p, None, None).unwrap().0
+                })
+                .collect::<Vec<_>>();
+            let coeffs = (0..n).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
 
-        let pairing_time = start_timer!(|| "Performing product of pairings");
-        let result = E::product_of_pairings(&[
-            (total_w.into(), vk.prepared_beta_h.clone()),
-            (total_c.into(), vk.prepared_h.clone()),
-        ])
-        .is_one();
-        end_timer!(pairing_time);
-        end_timer!(check_time, || format!("Result: {}", result));
-        Ok(result)
+            let mut expected = Commitment::empty();
+            for (comm, coeff) in comms.iter().zip(&coeffs) {
+                expected += (*coeff, comm);
+            }
+            assert_eq!(
+                Commitment::combine_with_coeffs(&comms, &coeffs).unwrap(),
+                expected
+            );
+        }
+
+        let comm = Commitment::<Bls12_381>::empty();
+        assert!(matches!(
+            Commitment::combine_with_coeffs(&[comm, comm], &[Fr::one()]),
+            Err(Error::IncorrectInputLength(_))
+        ));
     }
 
-    // Functions for checking errors
-    pub(crate) fn check_degree_is_within_bounds(
-        num_coefficients: usize,
-        num_powers: usize,
-    ) -> Result<(), Error> {
-        if num_coefficients < 1 {
-            Err(Error::DegreeIsZero)
-        } else {
-            Self::check_degree_is_too_large(num_coefficients, num_powers)
+    #[test]
+    fn prepare_batch_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let comms = (0..8)
+            .map(|_| {
+                let p = UniPoly_381::rand(degree, rng);
+                KZG_Bls12_381::commit(&powers, &p, None, None).unwrap().0
+            })
+            .collect::<Vec<_>>();
+
+        let prepared = PreparedCommitment::prepare_batch(&comms);
+        assert_eq!(prepared.len(), comms.len());
+        for (comm, prepared_comm) in comms.iter().zip(&prepared) {
+            assert_eq!(*prepared_comm, PreparedCommitment::prepare(comm));
         }
+        assert!(PreparedCommitment::<Bls12_381>::prepare_batch(&[]).is_empty());
     }
 
-    pub(crate) fn check_degree_is_too_large(
-        num_coefficients: usize,
-        num_powers: usize,
-    ) -> Result<(), Error> {
-        if num_coefficients > num_powers {
-            Err(Error::TooManyCoefficients {
-                num_coefficients,
-                num_powers,
-            })
-        } else {
-            Ok(())
+    #[test]
+    fn commit_matches_naive_sum_test() {
+        let rng = &mut test_rng();
+        let degree = 50;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        for &d in &[1, 7, degree] {
+            let p = UniPoly_381::rand(d, rng);
+            let naive = p.coeffs.iter().zip(powers.powers_of_g.iter()).fold(
+                <Bls12_381 as PairingEngine>::G1Projective::zero(),
+                |acc, (c, g)| acc + &g.mul(*c),
+            );
+            let (comm, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+            assert_eq!(comm.0, naive.into_affine());
         }
     }
 
-    pub(crate) fn check_hiding_bound(
-        hiding_poly_degree: usize,
-        num_powers: usize,
-    ) -> Result<(), Error> {
-        if hiding_poly_degree == 0 {
-            Err(Error::HidingBoundIsZero)
-        } else if hiding_poly_degree >= num_powers {
-            // The above check uses `>=` because committing to a hiding poly with
-            // degree `hiding_poly_degree` requires `hiding_poly_degree + 1`
-            // powers.
-            Err(Error::HidingBoundToolarge {
-                hiding_poly_degree,
-                num_powers,
-            })
-        } else {
-            Ok(())
+    #[test]
+    fn hex_codec_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        for hiding_bound in [None, Some(1)].iter() {
+            let p = UniPoly_381::rand(degree, rng);
+            let point = Fr::rand(rng);
+            let (comm, rand) =
+                KZG_Bls12_381::commit(&powers, &p, *hiding_bound, Some(rng)).unwrap();
+            let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+
+            let comm_hex = comm.to_hex();
+            assert_eq!(comm_hex.len(), 2 * 48);
+            assert_eq!(Commitment::from_hex(&comm_hex).unwrap(), comm);
+            assert_eq!(
+                Commitment::from_hex(&comm_hex.to_uppercase()).unwrap(),
+                comm
+            );
+            let proof_hex = proof.to_hex();
+            assert_eq!(Proof::from_hex(&proof_hex).unwrap(), proof);
+        }
+
+        let comm_hex = Commitment::<Bls12_381>::empty().to_hex();
+        for bad in &[
+            comm_hex[1..].to_string(),
+            format!("{}00", comm_hex),
+            format!("zz{}", &comm_hex[2..]),
+            "ff".repeat(48),
+        ] {
+            assert!(matches!(
+                Commitment::<Bls12_381>::from_hex(bad),
+                Err(Error::MalformedHex(_))
+            ));
         }
     }
 
-    pub(crate) fn check_degrees_and_bounds<'a>(
-        supported_degree: usize,
-        max_degree: usize,
-        enforced_degree_bounds: Option<&[usize]>,
-        p: &'a LabeledPolynomial<E::Fr, P>,
-    ) -> Result<(), Error> {
-        if let Some(bound) = p.degree_bound() {
-            let enforced_degree_bounds =
-                enforced_degree_bounds.ok_or(Error::UnsupportedDegreeBound(bound))?;
+    #[test]
+    fn aggregate_proof_size_test() {
+        use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
-            if enforced_degree_bounds.binary_search(&bound).is_err() {
-                Err(Error::UnsupportedDegreeBound(bound))
-            } else if bound < p.degree() || bound > max_degree {
-                return Err(Error::IncorrectDegreeBound {
-                    poly_degree: p.degree(),
-                    degree_bound: p.degree_bound().unwrap(),
-                    supported_degree,
-                    label: p.label().to_string(),
-                });
-            } else {
-                Ok(())
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let mut proofs = Vec::new();
+        for i in 0..4 {
+            let p = UniPoly_381::rand(degree, rng);
+            let hiding_bound = if i % 2 == 0 { None } else { Some(1) };
+            let (_, rand) = KZG_Bls12_381::commit(&powers, &p, hiding_bound, Some(rng)).unwrap();
+            proofs.push(KZG_Bls12_381::open(&powers, &p, Fr::rand(rng), &rand).unwrap());
+        }
+
+        for n in 0..=proofs.len() {
+            let aggregate = AggregateProof::from(proofs[..n].to_vec());
+            let mut bytes = Vec::new();
+            aggregate.serialize(&mut bytes).unwrap();
+            assert_eq!(aggregate.size_in_bytes(), bytes.len());
+            assert_eq!(
+                AggregateProof::<Bls12_381>::deserialize(&bytes[..]).unwrap(),
+                aggregate
+            );
+
+            let bytes = ark_ff::to_bytes![aggregate].unwrap();
+            let proof_bytes = ark_ff::to_bytes![proofs[0]].unwrap();
+            assert_eq!(bytes.len(), 8 + n * proof_bytes.len());
+            assert_eq!(bytes[..8], (n as u64).to_le_bytes());
+            for (i, proof) in proofs[..n].iter().enumerate() {
+                let start = 8 + i * proof_bytes.len();
+                assert_eq!(
+                    bytes[start..start + proof_bytes.len()],
+                    ark_ff::to_bytes![proof].unwrap()[..]
+                );
             }
-        } else {
-            Ok(())
         }
     }
-}
 
-fn skip_leading_zeros_and_convert_to_bigints<F: PrimeField, P: UVPolynomial<F>>(
-    p: &P,
-) -> (usize, Vec<F::BigInt>) {
-    let mut num_leading_zeros = 0;
-    while p.coeffs()[num_leading_zeros].is_zero() && num_leading_zeros < p.coeffs().len() {
-        num_leading_zeros += 1;
-    }
-    let coeffs = convert_to_bigints(&p.coeffs()[num_leading_zeros..]);
-    (num_leading_zeros, coeffs)
-}
+    #[test]
+    fn projective_commitment_test() {
+        use ark_ec::ProjectiveCurve;
 
-fn convert_to_bigints<F: PrimeField>(p: &[F]) -> Vec<F::BigInt> {
-    let to_bigint_time = start_timer!(|| "Converting polynomial coeffs to bigints");
-    let coeffs = ark_std::cfg_iter!(p)
-        .map(|s| s.into_repr())
-        .collect::<Vec<_>>();
-    end_timer!(to_bigint_time);
-    coeffs
-}
+        let rng = &mut test_rng();
+        let comms = (0..1000)
+            .map(|_| {
+                Commitment(<Bls12_381 as PairingEngine>::G1Projective::rand(rng).into_affine())
+            })
+            .collect::<Vec<_>>();
 
-#[cfg(test)]
-mod tests {
-    #![allow(non_camel_case_types)]
-    use crate::kzg10::*;
-    use crate::*;
+        let mut affine_sum = Commitment::<Bls12_381>::empty();
+        for comm in &comms {
+            affine_sum += (Fr::one(), comm);
+        }
 
-    use ark_bls12_377::Bls12_377;
-    use ark_bls12_381::Bls12_381;
-    use ark_bls12_381::Fr;
-    use ark_ec::PairingEngine;
-    use ark_ff::test_rng;
-    use ark_poly::univariate::DensePolynomial as DensePoly;
+        let mut projective_sum = ProjectiveCommitment::default();
+        for comm in &comms[..500] {
+            projective_sum += comm;
+        }
+        let rest = comms[500..]
+            .iter()
+            .map(|comm| ProjectiveCommitment::from(*comm))
+            .fold(ProjectiveCommitment::default(), |acc, comm| acc + comm);
+        projective_sum += &rest;
+        assert_eq!(Commitment::from(projective_sum), affine_sum);
+    }
 
-    type UniPoly_381 = DensePoly<<Bls12_381 as PairingEngine>::Fr>;
-    type UniPoly_377 = DensePoly<<Bls12_377 as PairingEngine>::Fr>;
-    type KZG_Bls12_381 = KZG10<Bls12_381, UniPoly_381>;
+    #[test]
+    fn check_prepared_test() {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let prepared_vk = PreparedVerifierKey::prepare(&vk);
 
-    impl<E: PairingEngine, P: UVPolynomial<E::Fr>> KZG10<E, P> {
-        /// Specializes the public parameters for a given maximum degree `d` for polynomials
-        /// `d` should be less that `pp.max_degree()`.
-        pub(crate) fn trim(
-            pp: &UniversalParams<E>,
-            mut supported_degree: usize,
-        ) -> Result<(Powers<E>, VerifierKey<E>), Error> {
-            if supported_degree == 1 {
-                supported_degree += 1;
+        for _ in 0..4 {
+            let p = UniPoly_381::rand(degree, rng);
+            let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+            let point = Fr::rand(rng);
+            let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+            for &value in &[p.evaluate(&point), Fr::rand(rng)] {
+                let expected = KZG_Bls12_381::check(&vk, &comm, point, value, &proof).unwrap();
+                let result =
+                    KZG_Bls12_381::check_prepared(&prepared_vk, &comm, point, value, &proof)
+                        .unwrap();
+                assert_eq!(result, expected);
             }
-            let powers_of_g = pp.powers_of_g[..=supported_degree].to_vec();
-            let powers_of_gamma_g = (0..=supported_degree)
-                .map(|i| pp.powers_of_gamma_g[&i])
-                .collect();
+        }
 
-            let powers = Powers {
-                powers_of_g: ark_std::borrow::Cow::Owned(powers_of_g),
-                powers_of_gamma_g: ark_std::borrow::Cow::Owned(powers_of_gamma_g),
-            };
-            let vk = VerifierKey {
-                g: pp.powers_of_g[0],
-                gamma_g: pp.powers_of_gamma_g[&0],
-                h: pp.h,
-                beta_h: pp.beta_h,
-                prepared_h: pp.prepared_h.clone(),
-                prepared_beta_h: pp.prepared_beta_h.clone(),
-            };
-            Ok((powers, vk))
+        let p = UniPoly_381::rand(degree, rng);
+        let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, Some(1), Some(rng)).unwrap();
+        let point = Fr::rand(rng);
+        let proof = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+        assert!(matches!(
+            KZG_Bls12_381::check_prepared(&prepared_vk, &comm, point, p.evaluate(&point), &proof),
+            Err(Error::HidingProofUnsupported)
+        ));
+    }
+
+    #[test]
+    fn commit_lagrange_test() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+
+        let rng = &mut test_rng();
+        let degree = 20;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        for &size in &[1, 2, 8, 16] {
+            let domain = GeneralEvaluationDomain::<Fr>::new(size).unwrap();
+            let lagrange_basis = powers.to_lagrange_basis(&domain).unwrap();
+            assert_eq!(lagrange_basis.len(), size);
+
+            let p = UniPoly_381::rand(size - 1, rng);
+            let evals = domain.fft(&p.coeffs);
+            let (expected, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+            let comm = KZG_Bls12_381::commit_lagrange(&lagrange_basis, &evals).unwrap();
+            assert_eq!(comm, expected);
+            assert!(matches!(
+                KZG_Bls12_381::commit_lagrange(&lagrange_basis, &evals[1..]),
+                Err(Error::IncorrectInputLength(_))
+            ));
         }
+
+        let domain = GeneralEvaluationDomain::<Fr>::new(32).unwrap();
+        assert!(matches!(
+            powers.to_lagrange_basis(&domain),
+            Err(Error::TooManyCoefficients { .. })
+        ));
     }
 
     #[test]
-    fn add_commitments_test() {
+    fn open_at_domain_point_test() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+
         let rng = &mut test_rng();
-        let p = DensePoly::from_coefficients_slice(&[
-            Fr::rand(rng),
-            Fr::rand(rng),
-            Fr::rand(rng),
-            Fr::rand(rng),
-            Fr::rand(rng),
-        ]);
-        let f = Fr::rand(rng);
-        let mut f_p = DensePoly::zero();
-        f_p += (f, &p);
+        let domain = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        let degree = 20;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
 
-        let degree = 4;
+        for &(p_degree, hiding_bound) in &[(1, None), (15, None), (20, None), (20, Some(1))] {
+            let p = UniPoly_381::rand(p_degree, rng);
+            let (comm, rand) = KZG_Bls12_381::commit(&powers, &p, hiding_bound, Some(rng)).unwrap();
+            for &index in &[0, 1, 7, 15] {
+                let point = domain.element(index);
+                let proof = KZG_Bls12_381::open_at_domain_point(&powers, &p, &domain, index, &rand)
+                    .unwrap();
+                let expected = KZG_Bls12_381::open(&powers, &p, point, &rand).unwrap();
+                assert_eq!(proof, expected);
+                assert!(
+                    KZG_Bls12_381::check(&vk, &comm, point, p.evaluate(&point), &proof).unwrap()
+                );
+            }
+        }
+
+        let p = UniPoly_381::rand(degree, rng);
+        assert!(matches!(
+            KZG_Bls12_381::open_at_domain_point(&powers, &p, &domain, 16, &Randomness::empty()),
+            Err(Error::IncorrectInputLength(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn randomness_zeroize_test() {
+        use zeroize::Zeroize;
+
+        let rng = &mut test_rng();
+        let degree = 10;
         let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
         let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let p = UniPoly_381::rand(degree, rng);
+        let (_, mut rand) = KZG_Bls12_381::commit(&powers, &p, Some(2), Some(rng)).unwrap();
+        assert!(rand.is_hiding());
 
-        let hiding_bound = None;
-        let (comm, _) = KZG10::commit(&powers, &p, hiding_bound, Some(rng)).unwrap();
-        let (f_comm, _) = KZG10::commit(&powers, &f_p, hiding_bound, Some(rng)).unwrap();
-        let mut f_comm_2 = Commitment::empty();
-        f_comm_2 += (f, &comm);
+        rand.zeroize();
+        assert!(rand.blinding_polynomial.coeffs.is_empty());
+        assert!(!rand.is_hiding());
+        assert_eq!(rand, Randomness::empty());
+    }
 
-        assert_eq!(f_comm, f_comm_2);
+    #[test]
+    #[cfg(feature = "subtle")]
+    fn commitment_ct_eq_test() {
+        use subtle::ConstantTimeEq;
+
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, _) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let mut comms = vec![Commitment::empty()];
+        for _ in 0..4 {
+            let p = UniPoly_381::rand(degree, rng);
+            comms.push(KZG_Bls12_381::commit(&powers, &p, None, None).unwrap().0);
+        }
+        comms.push(-comms[1]);
+        for a in &comms {
+            for b in &comms {
+                assert_eq!(bool::from(a.ct_eq(b)), a == b);
+            }
+            assert!(bool::from(a.ct_eq(&a.clone())));
+        }
+    }
+
+    #[test]
+    fn vanishing_argument_test() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+
+        let rng = &mut test_rng();
+        let domain = GeneralEvaluationDomain::<Fr>::new(8).unwrap();
+        let degree = domain.size() + 5;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let z_h: UniPoly_381 = domain.vanishing_polynomial().into();
+        let vanishing = &UniPoly_381::rand(5, rng) * &z_h;
+        let non_vanishing = UniPoly_381::rand(degree, rng);
+
+        for (p, expected) in vec![(vanishing, true), (non_vanishing, false)] {
+            let (comm_p, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+            let (comm_q, q) =
+                KZG_Bls12_381::prove_vanishes_on_domain(&powers, &p, &domain).unwrap();
+            let point = Fr::rand(rng);
+            let proof =
+                KZG_Bls12_381::open_vanishes_on_domain(&powers, &p, &q, &domain, point).unwrap();
+            let result = KZG_Bls12_381::check_vanishes_on_domain(
+                &vk, &comm_p, &comm_q, &domain, point, &proof,
+            )
+            .unwrap();
+            assert_eq!(result, expected);
+        }
     }
 
     fn end_to_end_test_template<E, P>() -> Result<(), Error>
@@ -643,6 +4337,47 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn agrees_on_subdomain_test() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+
+        let rng = &mut test_rng();
+        let degree = 16;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+        let subdomain = GeneralEvaluationDomain::<Fr>::new(4).unwrap();
+        let z_h: UniPoly_381 = subdomain.vanishing_polynomial().into();
+
+        let public_poly = UniPoly_381::rand(3, rng);
+        let agreeing = &public_poly + &(&UniPoly_381::rand(8, rng) * &z_h);
+        // Differs from `public_poly` only at the second subdomain element.
+        let mut evals = vec![Fr::zero(); 4];
+        evals[1] = Fr::one();
+        let bump = UniPoly_381::from_coefficients_vec(subdomain.ifft(&evals));
+        let disagreeing = &agreeing + &bump;
+
+        for (p, expected) in vec![(agreeing, true), (disagreeing, false)] {
+            let (comm_p, _) = KZG_Bls12_381::commit(&powers, &p, None, None).unwrap();
+            let (comm_q, q) =
+                KZG_Bls12_381::prove_agrees_on_subdomain(&powers, &p, &public_poly, &subdomain)
+                    .unwrap();
+            let point = Fr::rand(rng);
+            let proof = KZG_Bls12_381::open_agrees_on_subdomain(&powers, &p, &q, &subdomain, point)
+                .unwrap();
+            let result = KZG_Bls12_381::check_agrees_on_subdomain(
+                &vk,
+                &comm_p,
+                &public_poly,
+                &comm_q,
+                &subdomain,
+                point,
+                &proof,
+            )
+            .unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
     #[test]
     fn end_to_end_test() {
         end_to_end_test_template::<Bls12_377, UniPoly_377>().expect("test failed for bls12-377");
@@ -656,6 +4391,51 @@ mod tests {
         linear_polynomial_test_template::<Bls12_381, UniPoly_381>()
             .expect("test failed for bls12-381");
     }
+
+    #[test]
+    fn fold_proofs_test() {
+        use ark_ec::ProjectiveCurve;
+
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = KZG_Bls12_381::setup(degree, false, rng).unwrap();
+        let (powers, vk) = KZG_Bls12_381::trim(&pp, degree).unwrap();
+
+        let point = Fr::rand(rng);
+        let mut comms = Vec::new();
+        let mut values = Vec::new();
+        let mut proofs = Vec::new();
+        for hiding_bound in vec![Some(1), None] {
+            let p = UniPoly_381::rand(degree, rng);
+            let (comm, rand) = KZG10::commit(&powers, &p, hiding_bound, Some(rng)).unwrap();
+            comms.push(comm);
+            values.push(p.evaluate(&point));
+            proofs.push(KZG10::open(&powers, &p, point, &rand).unwrap());
+        }
+        let weights = vec![Fr::rand(rng), Fr::rand(rng)];
+
+        let folded = Proof::fold(&proofs, &weights).unwrap();
+        let expected_w = proofs[0].w.mul(weights[0]) + &proofs[1].w.mul(weights[1]);
+        assert_eq!(folded.w, expected_w.into_affine());
+        assert_eq!(
+            folded.random_v,
+            Some(proofs[0].random_v.unwrap() * &weights[0])
+        );
+
+        // Openings at the same point fold into an opening of the combined
+        // commitment.
+        let comm = Commitment::combine_with_coeffs(&comms, &weights).unwrap();
+        let value = values[0] * &weights[0] + &(values[1] * &weights[1]);
+        assert!(KZG_Bls12_381::check(&vk, &comm, point, value, &folded).unwrap());
+
+        let non_hiding = Proof::fold(&proofs[1..], &weights[1..]).unwrap();
+        assert!(non_hiding.random_v.is_none());
+        assert!(matches!(
+            Proof::fold(&proofs, &weights[1..]),
+            Err(Error::IncorrectInputLength(_))
+        ));
+    }
+
     #[test]
     fn batch_check_test() {
         batch_check_test_template::<Bls12_377, UniPoly_377>().expect("test failed for bls12-377");
@@ -0,0 +1,95 @@
+use crate::kzg10;
+use crate::{PCCommitterKey, PCPreparedVerifierKey, PCVerifierKey, Vec};
+use ark_ec::PairingEngine;
+
+/// `UniversalParams` are the universal parameters for the KZG10 scheme.
+pub type UniversalParams<E> = kzg10::UniversalParams<E>;
+
+/// `Randomness` is the randomness for the KZG10 scheme.
+pub type Randomness<F, P> = kzg10::Randomness<F, P>;
+
+/// `Commitment` is the commitment for the KZG10 scheme.
+pub type Commitment<E> = kzg10::Commitment<E>;
+
+/// `PreparedCommitment` is the prepared commitment for the KZG10 scheme.
+pub type PreparedCommitment<E> = kzg10::PreparedCommitment<E>;
+
+/// `Proof` is an evaluation proof that is output by `KZG10PC::open`.
+pub type Proof<E> = kzg10::Proof<E>;
+
+/// `ComitterKey` is used to commit to, and create evaluation proofs for, a given
+/// polynomial.
+#[derive(Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Hash(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = "")
+)]
+pub struct CommitterKey<E: PairingEngine> {
+    /// The key used to commit to polynomials.
+    pub powers_of_g: Vec<E::G1Affine>,
+
+    /// The key used to commit to hiding polynomials.
+    pub powers_of_gamma_g: Vec<E::G1Affine>,
+
+    /// The maximum degree supported by the `UniversalParams` from which `self` was derived
+    pub max_degree: usize,
+}
+
+impl<E: PairingEngine> CommitterKey<E> {
+    /// Obtain powers for the underlying KZG10 construction
+    pub fn powers(&self) -> kzg10::Powers<E> {
+        kzg10::Powers {
+            powers_of_g: self.powers_of_g.as_slice().into(),
+            powers_of_gamma_g: self.powers_of_gamma_g.as_slice().into(),
+            parallel_threshold: kzg10::DEFAULT_PARALLEL_THRESHOLD,
+        }
+    }
+}
+
+impl<E: PairingEngine> PCCommitterKey for CommitterKey<E> {
+    fn max_degree(&self) -> usize {
+        self.max_degree
+    }
+
+    fn supported_degree(&self) -> usize {
+        self.powers_of_g.len() - 1
+    }
+}
+
+/// `VerifierKey` is used to check evaluation proofs for a given commitment.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""), Clone(bound = ""), Debug(bound = ""))]
+pub struct VerifierKey<E: PairingEngine> {
+    /// The verification key for the underlying KZG10 scheme.
+    pub vk: kzg10::VerifierKey<E>,
+
+    /// The maximum degree supported by the trimmed parameters that `self` is
+    /// a part of.
+    pub supported_degree: usize,
+
+    /// The maximum degree supported by the `UniversalParams` `self` was derived
+    /// from.
+    pub max_degree: usize,
+}
+
+impl<E: PairingEngine> PCVerifierKey for VerifierKey<E> {
+    fn max_degree(&self) -> usize {
+        self.max_degree
+    }
+
+    fn supported_degree(&self) -> usize {
+        self.supported_degree
+    }
+}
+
+/// Nothing to do to prepare this verifier key (for now).
+pub type PreparedVerifierKey<E> = VerifierKey<E>;
+
+impl<E: PairingEngine> PCPreparedVerifierKey<VerifierKey<E>> for PreparedVerifierKey<E> {
+    /// prepare `PreparedVerifierKey` from `VerifierKey`
+    fn prepare(vk: &VerifierKey<E>) -> Self {
+        vk.clone()
+    }
+}
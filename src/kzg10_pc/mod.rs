@@ -0,0 +1,423 @@
+use crate::{kzg10, PCCommitterKey};
+use crate::{Error, UVPolynomial, Vec};
+use crate::{LabeledCommitment, LabeledPolynomial};
+use crate::{PCCommitment, PCRandomness, PCUniversalParams, PolynomialCommitment};
+
+use ark_ec::PairingEngine;
+use ark_ff::Zero;
+use ark_std::{marker::PhantomData, ops::Div};
+use rand_core::RngCore;
+
+mod data_structures;
+pub use data_structures::*;
+
+/// A thin wrapper that exposes the plain [[KZG10]][kzg] construction through the
+/// `PolynomialCommitment` interface, so that it can be used interchangeably with
+/// `MarlinKZG10` and `SonicKZG10`.
+///
+/// Commitments may be hiding, but degree bounds are not supported: trimming
+/// or committing with a degree bound fails with `Error::UnsupportedDegreeBound`.
+/// Polynomials opened at the same point are combined with the opening
+/// challenges into a single KZG10 opening.
+///
+/// [kzg]: http://cacr.uwaterloo.ca/techreports/2010/cacr2010-10.pdf
+pub struct KZG10PC<E: PairingEngine, P: UVPolynomial<E::Fr>> {
+    _engine: PhantomData<E>,
+    _poly: PhantomData<P>,
+}
+
+impl<E, P> PolynomialCommitment<E::Fr, P> for KZG10PC<E, P>
+where
+    E: PairingEngine,
+    P: UVPolynomial<E::Fr, Point = E::Fr>,
+    for<'a, 'b> &'a P: Div<&'b P, Output = P>,
+{
+    type UniversalParams = UniversalParams<E>;
+    type CommitterKey = CommitterKey<E>;
+    type VerifierKey = VerifierKey<E>;
+    type PreparedVerifierKey = PreparedVerifierKey<E>;
+    type Commitment = Commitment<E>;
+    type PreparedCommitment = PreparedCommitment<E>;
+    type Randomness = Randomness<E::Fr, P>;
+    type Proof = Proof<E>;
+    type BatchProof = Vec<Self::Proof>;
+    type Error = Error;
+
+    fn setup<R: RngCore>(
+        max_degree: usize,
+        _: Option<usize>,
+        rng: &mut R,
+    ) -> Result<Self::UniversalParams, Self::Error> {
+        kzg10::KZG10::<E, P>::setup(max_degree, false, rng).map_err(Into::into)
+    }
+
+    fn trim(
+        pp: &Self::UniversalParams,
+        supported_degree: usize,
+        supported_hiding_bound: usize,
+        enforced_degree_bounds: Option<&[usize]>,
+    ) -> Result<(Self::CommitterKey, Self::VerifierKey), Self::Error> {
+        let trim_time = start_timer!(|| "Trimming public parameters");
+        let max_degree = pp.max_degree();
+        kzg10::check_trim_degrees(supported_degree, max_degree, None)?;
+        if let Some(bound) = enforced_degree_bounds.and_then(|bounds| bounds.first()) {
+            return Err(Error::UnsupportedDegreeBound(*bound));
+        }
+        kzg10::check_trim_allocation(pp, supported_degree, supported_hiding_bound, false)?;
+
+        let powers_of_g = pp.powers_of_g[..=supported_degree].to_vec();
+        let powers_of_gamma_g = kzg10::trim_powers_of_gamma_g(pp, supported_hiding_bound)?;
+
+        let ck = CommitterKey {
+            powers_of_g,
+            powers_of_gamma_g,
+            max_degree,
+        };
+
+        let vk = VerifierKey {
+            vk: kzg10::VerifierKey {
+                g: pp.powers_of_g[0],
+                gamma_g: pp.powers_of_gamma_g[&0],
+                h: pp.h,
+                beta_h: pp.beta_h,
+                prepared_h: pp.prepared_h.clone(),
+                prepared_beta_h: pp.prepared_beta_h.clone(),
+            },
+            supported_degree,
+            max_degree,
+        };
+
+        end_timer!(trim_time);
+        Ok((ck, vk))
+    }
+
+    /// Outputs a commitment to `polynomial`.
+    fn commit<'a>(
+        ck: &Self::CommitterKey,
+        polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<E::Fr, P>>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<
+        (
+            Vec<LabeledCommitment<Self::Commitment>>,
+            Vec<Self::Randomness>,
+        ),
+        Self::Error,
+    >
+    where
+        P: 'a,
+    {
+        let rng = &mut crate::optional_rng::OptionalRng(rng);
+        let commit_time = start_timer!(|| "Committing to polynomials");
+        let powers = ck.powers();
+        let mut labeled_comms: Vec<LabeledCommitment<Self::Commitment>> = Vec::new();
+        let mut randomness: Vec<Self::Randomness> = Vec::new();
+
+        for labeled_polynomial in polynomials {
+            kzg10::KZG10::<E, P>::check_degrees_and_bounds(
+                ck.supported_degree(),
+                ck.max_degree,
+                None,
+                &labeled_polynomial,
+            )?;
+
+            let (comm, rand) = kzg10::KZG10::commit(
+                &powers,
+                labeled_polynomial.polynomial(),
+                labeled_polynomial.hiding_bound(),
+                Some(rng),
+            )?;
+
+            labeled_comms.push(LabeledCommitment::new(
+                labeled_polynomial.label().clone(),
+                comm,
+                None,
+            ));
+            randomness.push(rand);
+        }
+
+        end_timer!(commit_time);
+        Ok((labeled_comms, randomness))
+    }
+
+    fn open_individual_opening_challenges<'a>(
+        ck: &Self::CommitterKey,
+        labeled_polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<E::Fr, P>>,
+        _commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
+        point: &'a P::Point,
+        opening_challenges: &dyn Fn(u64) -> E::Fr,
+        rands: impl IntoIterator<Item = &'a Self::Randomness>,
+        _rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        Self::Randomness: 'a,
+        Self::Commitment: 'a,
+        P: 'a,
+    {
+        let mut combined_polynomial = P::zero();
+        let mut combined_rand = kzg10::Randomness::empty();
+
+        for (i, (polynomial, rand)) in labeled_polynomials.into_iter().zip(rands).enumerate() {
+            kzg10::KZG10::<E, P>::check_degrees_and_bounds(
+                ck.supported_degree(),
+                ck.max_degree,
+                None,
+                &polynomial,
+            )?;
+
+            let curr_challenge = opening_challenges(i as u64);
+            combined_polynomial += (curr_challenge, polynomial.polynomial());
+            combined_rand += (curr_challenge, rand);
+        }
+
+        let proof_time = start_timer!(|| "Creating proof for polynomials");
+        let proof = kzg10::KZG10::open(&ck.powers(), &combined_polynomial, *point, &combined_rand)?;
+        end_timer!(proof_time);
+
+        Ok(proof)
+    }
+
+    fn check_individual_opening_challenges<'a>(
+        vk: &Self::VerifierKey,
+        commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
+        point: &'a P::Point,
+        values: impl IntoIterator<Item = E::Fr>,
+        proof: &Self::Proof,
+        opening_challenges: &dyn Fn(u64) -> E::Fr,
+        _rng: Option<&mut dyn RngCore>,
+    ) -> Result<bool, Self::Error>
+    where
+        Self::Commitment: 'a,
+    {
+        let check_time = start_timer!(|| "Checking evaluations");
+        let mut combined_comm = Commitment::<E>::empty();
+        let mut combined_value = E::Fr::zero();
+
+        for (i, (labeled_comm, value)) in commitments.into_iter().zip(values).enumerate() {
+            if let Some(bound) = labeled_comm.degree_bound() {
+                return Err(Error::UnsupportedDegreeBound(bound));
+            }
+
+            let curr_challenge = opening_challenges(i as u64);
+            combined_comm += (curr_challenge, labeled_comm.commitment());
+            combined_value += &(curr_challenge * &value);
+        }
+
+        let result =
+            kzg10::KZG10::<E, P>::check(&vk.vk, &combined_comm, *point, combined_value, proof);
+        end_timer!(check_time);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_camel_case_types)]
+    use super::KZG10PC;
+    use crate::marlin_pc::MarlinKZG10;
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+    use ark_ec::PairingEngine;
+    use ark_ff::UniformRand;
+    use ark_poly::{univariate::DensePolynomial as DensePoly, UVPolynomial};
+
+    type UniPoly_381 = DensePoly<<Bls12_381 as PairingEngine>::Fr>;
+    type UniPoly_377 = DensePoly<<Bls12_377 as PairingEngine>::Fr>;
+
+    type PC<E, P> = KZG10PC<E, P>;
+    type PC_Bls12_377 = PC<Bls12_377, UniPoly_377>;
+    type PC_Bls12_381 = PC<Bls12_381, UniPoly_381>;
+    type Marlin_Bls12_381 = MarlinKZG10<Bls12_381, UniPoly_381>;
+
+    fn rand_poly<E: PairingEngine>(
+        degree: usize,
+        _: Option<usize>,
+        rng: &mut rand::prelude::StdRng,
+    ) -> DensePoly<E::Fr> {
+        DensePoly::<E::Fr>::rand(degree, rng)
+    }
+
+    fn rand_point<E: PairingEngine>(_: Option<usize>, rng: &mut rand::prelude::StdRng) -> E::Fr {
+        E::Fr::rand(rng)
+    }
+
+    #[test]
+    fn single_poly_test() {
+        use crate::tests::*;
+        single_poly_test::<_, _, PC_Bls12_377>(
+            None,
+            rand_poly::<Bls12_377>,
+            rand_point::<Bls12_377>,
+        )
+        .expect("test failed for bls12-377");
+        single_poly_test::<_, _, PC_Bls12_381>(
+            None,
+            rand_poly::<Bls12_381>,
+            rand_point::<Bls12_381>,
+        )
+        .expect("test failed for bls12-381");
+    }
+
+    #[test]
+    fn single_equation_test() {
+        use crate::tests::*;
+        single_equation_test::<_, _, PC_Bls12_377>(
+            None,
+            rand_poly::<Bls12_377>,
+            rand_point::<Bls12_377>,
+        )
+        .expect("test failed for bls12-377");
+        single_equation_test::<_, _, PC_Bls12_381>(
+            None,
+            rand_poly::<Bls12_381>,
+            rand_point::<Bls12_381>,
+        )
+        .expect("test failed for bls12-381");
+    }
+
+    #[test]
+    fn two_equation_test() {
+        use crate::tests::*;
+        two_equation_test::<_, _, PC_Bls12_377>(
+            None,
+            rand_poly::<Bls12_377>,
+            rand_point::<Bls12_377>,
+        )
+        .expect("test failed for bls12-377");
+        two_equation_test::<_, _, PC_Bls12_381>(
+            None,
+            rand_poly::<Bls12_381>,
+            rand_point::<Bls12_381>,
+        )
+        .expect("test failed for bls12-381");
+    }
+
+    #[test]
+    fn check_combinations_test() {
+        use crate::{
+            Evaluations, LabeledPolynomial, LinearCombination, PolynomialCommitment, QuerySet,
+        };
+        use ark_ff::{test_rng, One};
+
+        type Fr = <Bls12_381 as PairingEngine>::Fr;
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = PC_Bls12_381::setup(degree, None, rng).unwrap();
+        let (ck, vk) = PC_Bls12_381::trim(&pp, degree, 0, None).unwrap();
+
+        let polynomials = vec![
+            LabeledPolynomial::new(
+                "a".into(),
+                rand_poly::<Bls12_381>(degree, None, rng),
+                None,
+                None,
+            ),
+            LabeledPolynomial::new(
+                "b".into(),
+                rand_poly::<Bls12_381>(degree, None, rng),
+                None,
+                None,
+            ),
+        ];
+        let (comms, rands) = PC_Bls12_381::commit(&ck, &polynomials, None).unwrap();
+
+        // The point label matches no polynomial label, so the evaluations of
+        // `a` and `b` are only found if they are keyed by polynomial label.
+        let lc = LinearCombination::new("lc", vec![(Fr::from(2u64), "a"), (Fr::one(), "b")]);
+        let point = rand_point::<Bls12_381>(None, rng);
+        let mut query_set = QuerySet::new();
+        query_set.insert(("lc".to_string(), ("z".to_string(), point)));
+        let mut evaluations = Evaluations::new();
+        let value =
+            Fr::from(2u64) * polynomials[0].evaluate(&point) + polynomials[1].evaluate(&point);
+        evaluations.insert(("lc".to_string(), point), value);
+
+        let challenge = Fr::rand(rng);
+        let proof = PC_Bls12_381::open_combinations(
+            &ck,
+            &[lc.clone()],
+            &polynomials,
+            &comms,
+            &query_set,
+            challenge,
+            &rands,
+            None,
+        )
+        .unwrap();
+        assert!(PC_Bls12_381::check_combinations(
+            &vk,
+            &[lc],
+            &comms,
+            &query_set,
+            &evaluations,
+            &proof,
+            challenge,
+            rng,
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn degree_bound_unsupported_test() {
+        use crate::tests::*;
+        use crate::Error;
+        let result = single_poly_degree_bound_test::<_, _, PC_Bls12_381>(
+            rand_poly::<Bls12_381>,
+            rand_point::<Bls12_381>,
+        );
+        assert!(matches!(result, Err(Error::UnsupportedDegreeBound(_))));
+    }
+
+    #[test]
+    fn interchangeable_with_marlin_test() {
+        use crate::{LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        // The same prover/verifier routine, generic over the scheme.
+        fn prove_and_verify<PC>() -> bool
+        where
+            PC: PolynomialCommitment<<Bls12_381 as PairingEngine>::Fr, UniPoly_381>,
+        {
+            let rng = &mut test_rng();
+            let degree = 15;
+            let pp = PC::setup(degree, None, rng).unwrap();
+            let (ck, vk) = PC::trim(&pp, degree, 1, None).unwrap();
+
+            let polynomials = vec![
+                LabeledPolynomial::new(
+                    "a".into(),
+                    rand_poly::<Bls12_381>(degree, None, rng),
+                    None,
+                    Some(1),
+                ),
+                LabeledPolynomial::new(
+                    "b".into(),
+                    rand_poly::<Bls12_381>(degree - 3, None, rng),
+                    None,
+                    None,
+                ),
+            ];
+            let (comms, rands) = PC::commit(&ck, &polynomials, Some(rng)).unwrap();
+
+            let point = rand_point::<Bls12_381>(None, rng);
+            let values = polynomials
+                .iter()
+                .map(|p| p.evaluate(&point))
+                .collect::<Vec<_>>();
+            let challenge = <Bls12_381 as PairingEngine>::Fr::rand(rng);
+            let proof = PC::open(
+                &ck,
+                &polynomials,
+                &comms,
+                &point,
+                challenge,
+                &rands,
+                Some(rng),
+            )
+            .unwrap();
+            PC::check(&vk, &comms, &point, values, &proof, challenge, Some(rng)).unwrap()
+        }
+
+        assert!(prove_and_verify::<PC_Bls12_381>());
+        assert!(prove_and_verify::<Marlin_Bls12_381>());
+    }
+}
@@ -56,6 +56,12 @@ macro_rules! println {
 /// [kzg]: http://cacr.uwaterloo.ca/techreports/2010/cacr2010-10.pdf
 pub mod kzg10;
 
+/// The plain [[KZG10]][kzg] construction exposed through the
+/// `PolynomialCommitment` interface, without degree bounds.
+///
+/// [kzg]: http://cacr.uwaterloo.ca/techreports/2010/cacr2010-10.pdf
+pub mod kzg10_pc;
+
 /// Polynomial commitment scheme from [[KZG10]][kzg] that enforces
 /// strict degree bounds and (optionally) enables hiding commitments by
 /// following the approach outlined in [[CHMMVW20, "Marlin"]][marlin].
@@ -83,11 +89,31 @@ pub mod sonic_pc;
 /// [pcdas]: https://eprint.iacr.org/2020/499
 pub mod ipa_pc;
 
+/// Deterministic [[KZG10]][kzg] test vectors for cross-implementation
+/// compatibility testing.
+///
+/// [kzg]: http://cacr.uwaterloo.ca/techreports/2010/cacr2010-10.pdf
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+
+/// Hex encodings shared by `HexCodec` and the `serde` support.
+mod hex;
+
+/// `serde` support for commitments, proofs and verifier keys.
+#[cfg(feature = "serde")]
+mod serde_support;
+
 /// `QuerySet` is the set of queries that are to be made to a set of labeled polynomials/equations
 /// `p` that have previously been committed to. Each element of a `QuerySet` is a pair of
 /// `(label, (point_label, point))`, where `label` is the label of a polynomial in `p`,
 /// `point_label` is the label for the point (e.g., "beta"), and  and `point` is the location
 /// that `p[label]` is to be queried at.
+///
+/// Because a `QuerySet` is a `BTreeSet`, it is iterated in sorted order no matter
+/// the order in which queries were inserted. The batch opening and checking methods
+/// of `PolynomialCommitment` rely on this: they group the queries by point label and,
+/// for each point, fold the polynomials in the order of their labels, so that a prover
+/// and a verifier holding the same set of queries always fold them in the same sequence.
 pub type QuerySet<T> = BTreeSet<(String, (String, T))>;
 
 /// `Evaluations` is the result of querying a set of labeled polynomials or equations
@@ -144,6 +170,8 @@ pub trait PolynomialCommitment<F: Field, P: Polynomial<F>>: Sized {
 
     /// Specializes the public parameters for polynomials up to the given `supported_degree`
     /// and for enforcing degree bounds in the range `1..=supported_degree`.
+    /// Hiding commitments made with the resulting keys remain hiding when opened at up to
+    /// `supported_hiding_bound` points.
     fn trim(
         pp: &Self::UniversalParams,
         supported_degree: usize,
@@ -174,6 +202,38 @@ pub trait PolynomialCommitment<F: Field, P: Polynomial<F>>: Sized {
     where
         P: 'a;
 
+    /// Commits to `polynomials` as `commit` does, but pairs each commitment
+    /// with the degree bound that it enforces, or `None` if it enforces none.
+    /// The bound is only reported if the commitment has a degree bound part
+    /// (see `PCCommitment::has_degree_bound`), so a bound in the label that the
+    /// scheme does not enforce is never reported as enforced.
+    fn commit_with_bounds<'a>(
+        ck: &Self::CommitterKey,
+        polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<F, P>>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<
+        (
+            Vec<(LabeledCommitment<Self::Commitment>, Option<usize>)>,
+            Vec<Self::Randomness>,
+        ),
+        Self::Error,
+    >
+    where
+        P: 'a,
+    {
+        let (commitments, randomness) = Self::commit(ck, polynomials, rng)?;
+        let commitments = commitments
+            .into_iter()
+            .map(|c| {
+                let degree_bound = c
+                    .degree_bound()
+                    .filter(|_| c.commitment().has_degree_bound());
+                (c, degree_bound)
+            })
+            .collect();
+        Ok((commitments, randomness))
+    }
+
     /// On input a list of labeled polynomials and a query point, `open` outputs a proof of evaluation
     /// of the polynomials at the query point.
     fn open<'a>(
@@ -204,6 +264,8 @@ pub trait PolynomialCommitment<F: Field, P: Polynomial<F>>: Sized {
 
     /// On input a list of labeled polynomials and a query set, `open` outputs a proof of evaluation
     /// of the polynomials at the points in the query set.
+    ///
+    /// The queries are processed in the deterministic order described in `QuerySet`.
     fn batch_open<'a>(
         ck: &Self::CommitterKey,
         labeled_polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<F, P>>,
@@ -230,6 +292,77 @@ pub trait PolynomialCommitment<F: Field, P: Polynomial<F>>: Sized {
         )
     }
 
+    /// On input a list of labeled polynomials and a list of `(label, point)`
+    /// queries, outputs one proof per distinct point, for all the polynomials
+    /// queried at that point. The proofs are ordered by point.
+    ///
+    /// Unlike `batch_open`, the queries need no point labels: polynomials are
+    /// grouped by the points themselves, and each group is opened with `open`.
+    ///
+    /// Within a group, the polynomials are passed to `open` in ascending order
+    /// of label, with duplicate queries removed. To verify the proof for a
+    /// point, pass the commitments and values of the polynomials queried there
+    /// to `check` in that same order; since the challenge powers are assigned
+    /// by position, any other order fails to verify.
+    fn batch_open_individual<'a>(
+        ck: &Self::CommitterKey,
+        labeled_polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<F, P>>,
+        commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
+        queries: impl IntoIterator<Item = (PolynomialLabel, P::Point)>,
+        opening_challenge: F,
+        rands: impl IntoIterator<Item = &'a Self::Randomness>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<Vec<(P::Point, Self::Proof)>, Self::Error>
+    where
+        Self::Randomness: 'a,
+        Self::Commitment: 'a,
+        P: 'a,
+    {
+        let rng = &mut crate::optional_rng::OptionalRng(rng);
+        let poly_rand_comm: BTreeMap<_, _> = labeled_polynomials
+            .into_iter()
+            .zip(rands)
+            .zip(commitments)
+            .map(|((poly, r), comm)| (poly.label(), (poly, r, comm)))
+            .collect();
+
+        let mut point_to_labels_map = BTreeMap::new();
+        for (label, point) in queries {
+            point_to_labels_map
+                .entry(point)
+                .or_insert_with(BTreeSet::new)
+                .insert(label);
+        }
+
+        let mut proofs = Vec::new();
+        for (point, labels) in point_to_labels_map {
+            let mut query_polys = Vec::new();
+            let mut query_rands = Vec::new();
+            let mut query_comms = Vec::new();
+            for label in labels {
+                let (polynomial, rand, comm) =
+                    poly_rand_comm.get(&label).ok_or(Error::MissingPolynomial {
+                        label: label.to_string(),
+                    })?;
+                query_polys.push(*polynomial);
+                query_rands.push(*rand);
+                query_comms.push(*comm);
+            }
+
+            let proof = Self::open(
+                ck,
+                query_polys,
+                query_comms,
+                &point,
+                opening_challenge,
+                query_rands,
+                Some(rng),
+            )?;
+            proofs.push((point, proof));
+        }
+        Ok(proofs)
+    }
+
     /// Verifies that `values` are the evaluations at `point` of the polynomials
     /// committed inside `commitments`.
     fn check<'a>(
@@ -258,6 +391,8 @@ pub trait PolynomialCommitment<F: Field, P: Polynomial<F>>: Sized {
 
     /// Checks that `values` are the true evaluations at `query_set` of the polynomials
     /// committed in `labeled_commitments`.
+    ///
+    /// The queries are processed in the deterministic order described in `QuerySet`.
     fn batch_check<'a, R: RngCore>(
         vk: &Self::VerifierKey,
         commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
@@ -383,13 +518,7 @@ pub trait PolynomialCommitment<F: Field, P: Polynomial<F>>: Sized {
         Self::Commitment: 'a,
     {
         let commitments: BTreeMap<_, _> = commitments.into_iter().map(|c| (c.label(), c)).collect();
-        let mut query_to_labels_map = BTreeMap::new();
-        for (label, (point_label, point)) in query_set.iter() {
-            let labels = query_to_labels_map
-                .entry(point_label)
-                .or_insert((point, BTreeSet::new()));
-            labels.1.insert(label);
-        }
+        let query_to_labels_map = query_set.group_by_point();
 
         // Implicit assumption: proofs are order in same manner as queries in
         // `query_to_labels_map`.
@@ -489,8 +618,7 @@ pub trait PolynomialCommitment<F: Field, P: Polynomial<F>>: Sized {
         let poly_evals = Evaluations::from_iter(
             poly_query_set
                 .iter()
-                .map(|(_, point)| point)
-                .cloned()
+                .map(|(label, (_, point))| (label.clone(), point.clone()))
                 .zip(evals.clone().unwrap()),
         );
 
@@ -567,14 +695,7 @@ pub trait PolynomialCommitment<F: Field, P: Polynomial<F>>: Sized {
             query_set.len(),
         ));
 
-        let mut query_to_labels_map = BTreeMap::new();
-
-        for (label, (point_label, point)) in query_set.iter() {
-            let labels = query_to_labels_map
-                .entry(point_label)
-                .or_insert((point, BTreeSet::new()));
-            labels.1.insert(label);
-        }
+        let query_to_labels_map = query_set.group_by_point();
 
         let mut proofs = Vec::new();
         for (_point_label, (point, labels)) in query_to_labels_map.into_iter() {
@@ -764,6 +885,85 @@ pub mod tests {
         Ok(())
     }
 
+    pub fn query_set_order_test<F, P, PC>(
+        rand_poly: fn(usize, Option<usize>, &mut rand::prelude::StdRng) -> P,
+        rand_point: fn(Option<usize>, &mut rand::prelude::StdRng) -> P::Point,
+    ) -> Result<(), PC::Error>
+    where
+        F: Field,
+        P: Polynomial<F>,
+        PC: PolynomialCommitment<F, P>,
+        PC::BatchProof: PartialEq + Debug,
+    {
+        let rng = &mut test_rng();
+        let degree = 10;
+        let pp = PC::setup(degree, None, rng)?;
+        let (ck, vk) = PC::trim(&pp, degree, 0, None)?;
+
+        let polynomials = (0..4)
+            .map(|i| {
+                LabeledPolynomial::new(
+                    format!("Test{}", i),
+                    rand_poly(degree, None, rng),
+                    None,
+                    None,
+                )
+            })
+            .collect::<Vec<_>>();
+        let (comms, rands) = PC::commit(&ck, &polynomials, None)?;
+
+        let points = (0..2).map(|_| rand_point(None, rng)).collect::<Vec<_>>();
+        let mut queries = Vec::new();
+        for (i, point) in points.iter().enumerate() {
+            for polynomial in &polynomials {
+                let query = (
+                    polynomial.label().clone(),
+                    (format!("{}", i), point.clone()),
+                );
+                let value = polynomial.evaluate(point);
+                queries.push((query, value));
+            }
+        }
+
+        let opening_challenge = F::rand(rng);
+        let mut results = Vec::new();
+        for reverse in vec![false, true] {
+            let mut queries = queries.clone();
+            if reverse {
+                queries.reverse();
+            }
+            let mut query_set = QuerySet::new();
+            let mut values = Evaluations::new();
+            for ((label, (point_label, point)), value) in queries {
+                query_set.insert((label.clone(), (point_label, point.clone())));
+                values.insert((label, point), value);
+            }
+
+            let proof = PC::batch_open(
+                &ck,
+                &polynomials,
+                &comms,
+                &query_set,
+                opening_challenge,
+                &rands,
+                Some(&mut test_rng()),
+            )?;
+            let result = PC::batch_check(
+                &vk,
+                &comms,
+                &query_set,
+                &values,
+                &proof,
+                opening_challenge,
+                &mut test_rng(),
+            )?;
+            assert!(result, "proof was incorrect, Query set: {:#?}", query_set);
+            results.push(proof);
+        }
+        assert_eq!(results[0], results[1]);
+        Ok(())
+    }
+
     fn test_template<F, P, PC>(info: TestInfo<F, P>) -> Result<(), PC::Error>
     where
         F: Field,
@@ -1,10 +1,19 @@
 use crate::{
-    PCCommitment, PCCommitterKey, PCPreparedCommitment, PCPreparedVerifierKey, PCRandomness,
-    PCVerifierKey, UVPolynomial, Vec,
+    Error, HexCodec, LabeledCommitment, PCCommitment, PCCommitterKey, PCPreparedCommitment,
+    PCPreparedVerifierKey, PCRandomness, PCVerifierKey, String, ToString, UVPolynomial, Vec,
 };
 use ark_ec::{PairingEngine, ProjectiveCurve};
-use ark_ff::{PrimeField, ToBytes};
-use ark_std::ops::{Add, AddAssign};
+use ark_ff::{PrimeField, ToBytes, Zero};
+#[cfg(feature = "zeroize")]
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::EvaluationDomain;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::{
+    convert::TryFrom,
+    format,
+    io::{Read, Write},
+    ops::{Add, AddAssign},
+};
 use rand_core::RngCore;
 
 use crate::kzg10;
@@ -12,8 +21,8 @@ use crate::kzg10;
 pub type UniversalParams<E> = kzg10::UniversalParams<E>;
 
 /// `CommitterKey` is used to commit to and create evaluation proofs for a given
-/// polynomial.
-#[derive(Derivative)]
+/// polynomial. Serializing it propagates the errors of the writer.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
 #[derivative(
     Default(bound = ""),
     Hash(bound = ""),
@@ -41,14 +50,51 @@ pub struct CommitterKey<E: PairingEngine> {
 }
 
 impl<E: PairingEngine> CommitterKey<E> {
+    /// Was `self` trimmed with a nonzero hiding bound? `trim` outputs
+    /// `supported_hiding_bound + 2` powers of `\gamma G`.
+    pub fn supports_hiding_commitments(&self) -> bool {
+        self.powers_of_gamma_g.len() > 2
+    }
+
     /// Obtain powers for the underlying KZG10 construction
     pub fn powers<'a>(&'a self) -> kzg10::Powers<'a, E> {
         kzg10::Powers {
             powers_of_g: self.powers.as_slice().into(),
             powers_of_gamma_g: self.powers_of_gamma_g.as_slice().into(),
+            parallel_threshold: kzg10::DEFAULT_PARALLEL_THRESHOLD,
+        }
+    }
+
+    /// Obtain powers for the underlying KZG10 construction that only support
+    /// committing to polynomials of degree at most `degree`, without
+    /// re-trimming. The powers of `\gamma G` are kept in full, as the number
+    /// needed depends on the hiding bound rather than on the degree.
+    ///
+    /// Panics if `degree > self.supported_degree()`.
+    pub fn powers_up_to<'a>(&'a self, degree: usize) -> kzg10::Powers<'a, E> {
+        assert!(
+            degree < self.powers.len(),
+            "degree {} is not supported by a key with {} powers",
+            degree,
+            self.powers.len()
+        );
+        kzg10::Powers {
+            powers_of_g: self.powers[..=degree].into(),
+            powers_of_gamma_g: self.powers_of_gamma_g.as_slice().into(),
+            parallel_threshold: kzg10::DEFAULT_PARALLEL_THRESHOLD,
         }
     }
 
+    /// Transforms the powers of `G` to the Lagrange basis over `domain`, for
+    /// committing to polynomials given by their evaluations with
+    /// `KZG10::commit_lagrange`. See `kzg10::Powers::to_lagrange_basis`.
+    pub fn to_lagrange_basis<D: EvaluationDomain<E::Fr>>(
+        &self,
+        domain: &D,
+    ) -> Result<Vec<E::G1Affine>, Error> {
+        self.powers().to_lagrange_basis(domain)
+    }
+
     /// Obtain powers for committing to shifted polynomials.
     pub fn shifted_powers<'a>(
         &'a self,
@@ -74,6 +120,7 @@ impl<E: PairingEngine> CommitterKey<E> {
             let ck = kzg10::Powers {
                 powers_of_g: (&shifted_powers[powers_range]).into(),
                 powers_of_gamma_g: self.powers_of_gamma_g.as_slice().into(),
+                parallel_threshold: kzg10::DEFAULT_PARALLEL_THRESHOLD,
             };
             ck
         })
@@ -101,6 +148,15 @@ pub struct VerifierKey<E: PairingEngine> {
     /// The vector is sorted in ascending order of `degree_bound`.
     /// This is `None` if `self` does not support enforcing any degree bounds.
     pub degree_bounds_and_shift_powers: Option<Vec<(usize, E::G1Affine)>>,
+    /// Pairs a degree bound with `\beta^{degree_bound - max_degree} h`, prepared
+    /// for use in pairings. These are only needed by
+    /// `MarlinKZG10::batch_check_degree_bounds`, and are present only if the
+    /// `UniversalParams` `self` was derived from contain negative powers of `h`
+    /// and `self` does not support hiding commitments.
+    pub degree_bounds_and_prepared_neg_powers_of_h: Option<Vec<(usize, E::G2Prepared)>>,
+    /// Whether `self` was trimmed with a nonzero hiding bound, in which case
+    /// the commitments it checks may be hiding.
+    pub supports_hiding_commitments: bool,
     /// The maximum degree supported by the `UniversalParams` `self` was derived
     /// from.
     pub max_degree: usize,
@@ -110,6 +166,50 @@ pub struct VerifierKey<E: PairingEngine> {
 }
 
 impl<E: PairingEngine> VerifierKey<E> {
+    /// Reconstructs the verifier key that `trim` outputs alongside `ck`, given
+    /// the generator `h` of G2 and `beta_h`.
+    ///
+    /// The shift power for a degree bound `d` is `\beta^{max_degree - d} G`. Since
+    /// `ck.shifted_powers` starts at `\beta^{max_degree - max_bound} G`, where
+    /// `max_bound` is the largest bound enforced by `ck`, this is the element of
+    /// `ck.shifted_powers` at index `max_bound - d`.
+    ///
+    /// The negative powers of `h` cannot be recovered from `ck`, so
+    /// `degree_bounds_and_prepared_neg_powers_of_h` is always `None`.
+    pub fn from_committer_key(ck: &CommitterKey<E>, h: E::G2Affine, beta_h: E::G2Affine) -> Self {
+        let vk = kzg10::VerifierKey {
+            g: ck.powers[0],
+            gamma_g: ck.powers_of_gamma_g[0],
+            h,
+            beta_h,
+            prepared_h: h.into(),
+            prepared_beta_h: beta_h.into(),
+        };
+
+        let degree_bounds_and_shift_powers = match (&ck.enforced_degree_bounds, &ck.shifted_powers)
+        {
+            (Some(bounds), Some(shifted_powers)) if !bounds.is_empty() => {
+                let max_bound = *bounds.last().unwrap();
+                Some(
+                    bounds
+                        .iter()
+                        .map(|d| (*d, shifted_powers[max_bound - *d]))
+                        .collect(),
+                )
+            }
+            _ => None,
+        };
+
+        Self {
+            vk,
+            degree_bounds_and_shift_powers,
+            degree_bounds_and_prepared_neg_powers_of_h: None,
+            supports_hiding_commitments: ck.supports_hiding_commitments(),
+            max_degree: ck.max_degree,
+            supported_degree: ck.powers.len() - 1,
+        }
+    }
+
     /// Find the appropriate shift for the degree bound.
     pub fn get_shift_power(&self, bound: usize) -> Option<E::G1Affine> {
         self.degree_bounds_and_shift_powers.as_ref().and_then(|v| {
@@ -118,6 +218,135 @@ impl<E: PairingEngine> VerifierKey<E> {
                 .map(|i| v[i].1)
         })
     }
+
+    /// Find the prepared negative power of `h` for the degree bound.
+    pub fn get_prepared_neg_power_of_h(&self, bound: usize) -> Option<E::G2Prepared> {
+        self.degree_bounds_and_prepared_neg_powers_of_h
+            .as_ref()
+            .and_then(|v| {
+                v.binary_search_by(|(d, _)| d.cmp(&bound))
+                    .ok()
+                    .map(|i| v[i].1.clone())
+            })
+    }
+
+    /// Serializes `self` compactly: group elements are compressed, and the
+    /// degrees and degree bounds are written as varints, with each bound after
+    /// the first written as its difference from the previous one. This makes
+    /// each bound of a dense set of bounds take a single byte.
+    ///
+    /// The prepared negative powers of `h` cannot be serialized, and are not
+    /// written.
+    pub fn serialize_compact<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.vk.g.serialize(&mut writer)?;
+        self.vk.gamma_g.serialize(&mut writer)?;
+        self.vk.h.serialize(&mut writer)?;
+        self.vk.beta_h.serialize(&mut writer)?;
+        write_varint(self.max_degree as u64, &mut writer)?;
+        write_varint(self.supported_degree as u64, &mut writer)?;
+        self.supports_hiding_commitments.serialize(&mut writer)?;
+
+        self.degree_bounds_and_shift_powers
+            .is_some()
+            .serialize(&mut writer)?;
+        if let Some(degree_bounds_and_shift_powers) = &self.degree_bounds_and_shift_powers {
+            write_varint(degree_bounds_and_shift_powers.len() as u64, &mut writer)?;
+            let mut previous = 0;
+            for (bound, _) in degree_bounds_and_shift_powers {
+                write_varint((bound - previous) as u64, &mut writer)?;
+                previous = *bound;
+            }
+            for (_, shift_power) in degree_bounds_and_shift_powers {
+                shift_power.serialize(&mut writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deserializes a verifier key written by `serialize_compact`. The
+    /// resulting key has no prepared negative powers of `h`, so it cannot be
+    /// used with `MarlinKZG10::batch_check_degree_bounds`.
+    pub fn deserialize_compact<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let g = E::G1Affine::deserialize(&mut reader)?;
+        let gamma_g = E::G1Affine::deserialize(&mut reader)?;
+        let h = E::G2Affine::deserialize(&mut reader)?;
+        let beta_h = E::G2Affine::deserialize(&mut reader)?;
+        let max_degree = read_varint_usize(&mut reader)?;
+        let supported_degree = read_varint_usize(&mut reader)?;
+        let supports_hiding_commitments = bool::deserialize(&mut reader)?;
+
+        let degree_bounds_and_shift_powers = if bool::deserialize(&mut reader)? {
+            let len = read_varint_usize(&mut reader)?;
+            let mut bounds = Vec::new();
+            let mut previous = 0usize;
+            for i in 0..len {
+                let delta = read_varint_usize(&mut reader)?;
+                // Bounds are strictly ascending.
+                if i > 0 && delta == 0 {
+                    return Err(SerializationError::InvalidData);
+                }
+                previous = previous
+                    .checked_add(delta)
+                    .ok_or(SerializationError::InvalidData)?;
+                bounds.push(previous);
+            }
+            let mut degree_bounds_and_shift_powers = Vec::new();
+            for bound in bounds {
+                degree_bounds_and_shift_powers
+                    .push((bound, E::G1Affine::deserialize(&mut reader)?));
+            }
+            Some(degree_bounds_and_shift_powers)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            vk: kzg10::VerifierKey {
+                g,
+                gamma_g,
+                h,
+                beta_h,
+                prepared_h: h.into(),
+                prepared_beta_h: beta_h.into(),
+            },
+            degree_bounds_and_shift_powers,
+            degree_bounds_and_prepared_neg_powers_of_h: None,
+            supports_hiding_commitments,
+            max_degree,
+            supported_degree,
+        })
+    }
+}
+
+/// Writes `value` as an LEB128 varint.
+fn write_varint<W: Write>(mut value: u64, mut writer: W) -> Result<(), SerializationError> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            writer.write_all(&[byte])?;
+            return Ok(());
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+/// Reads an LEB128 varint written by `write_varint` that fits in a `usize`.
+fn read_varint_usize<R: Read>(mut reader: R) -> Result<usize, SerializationError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8];
+        reader.read_exact(&mut byte)?;
+        let bits = u64::from(byte[0] & 0x7f);
+        if shift == 63 && bits > 1 {
+            return Err(SerializationError::InvalidData);
+        }
+        value |= bits << shift;
+        if byte[0] & 0x80 == 0 {
+            return usize::try_from(value).map_err(|_| SerializationError::InvalidData);
+        }
+    }
+    Err(SerializationError::InvalidData)
 }
 
 impl<E: PairingEngine> PCVerifierKey for VerifierKey<E> {
@@ -132,7 +361,7 @@ impl<E: PairingEngine> PCVerifierKey for VerifierKey<E> {
 
 impl<E: PairingEngine> ToBytes for VerifierKey<E> {
     #[inline]
-    fn write<W: ark_std::io::Write>(&self, mut writer: W) -> ark_std::io::Result<()> {
+    fn write<W: Write>(&self, mut writer: W) -> ark_std::io::Result<()> {
         self.vk.write(&mut writer)?;
         if let Some(degree_bounds_and_shift_powers) = &self.degree_bounds_and_shift_powers {
             writer.write_all(&degree_bounds_and_shift_powers.len().to_le_bytes())?;
@@ -204,6 +433,19 @@ impl<E: PairingEngine> PCPreparedVerifierKey<VerifierKey<E>> for PreparedVerifie
     }
 }
 
+impl<E: PairingEngine> PreparedVerifierKey<E> {
+    /// Find the doublings of the shift power for the degree bound.
+    pub fn get_prepared_shift_power(&self, bound: usize) -> Option<&[E::G1Affine]> {
+        self.prepared_degree_bounds_and_shift_powers
+            .as_ref()
+            .and_then(|v| {
+                v.binary_search_by(|(d, _)| d.cmp(&bound))
+                    .ok()
+                    .map(|i| v[i].1.as_slice())
+            })
+    }
+}
+
 /// Commitment to a polynomial that optionally enforces a degree bound.
 #[derive(Derivative)]
 #[derivative(
@@ -215,6 +457,11 @@ impl<E: PairingEngine> PCPreparedVerifierKey<VerifierKey<E>> for PreparedVerifie
     PartialEq(bound = ""),
     Eq(bound = "")
 )]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct Commitment<E: PairingEngine> {
     /// A KZG10 commitment to the polynomial.
     pub comm: kzg10::Commitment<E>,
@@ -227,7 +474,7 @@ pub struct Commitment<E: PairingEngine> {
 
 impl<E: PairingEngine> ToBytes for Commitment<E> {
     #[inline]
-    fn write<W: ark_std::io::Write>(&self, mut writer: W) -> ark_std::io::Result<()> {
+    fn write<W: Write>(&self, mut writer: W) -> ark_std::io::Result<()> {
         self.comm.write(&mut writer)?;
         let shifted_exists = self.shifted_comm.is_some();
         shifted_exists.write(&mut writer)?;
@@ -238,6 +485,14 @@ impl<E: PairingEngine> ToBytes for Commitment<E> {
     }
 }
 
+impl<E: PairingEngine> Commitment<E> {
+    /// Is `self` a commitment to the zero polynomial? This holds when `comm`
+    /// is zero and `shifted_comm` is either `None` or zero.
+    pub fn is_zero(&self) -> bool {
+        self.comm.0.is_zero() && self.shifted_comm.map_or(true, |c| c.0.is_zero())
+    }
+}
+
 impl<E: PairingEngine> PCCommitment for Commitment<E> {
     #[inline]
     fn empty() -> Self {
@@ -247,6 +502,10 @@ impl<E: PairingEngine> PCCommitment for Commitment<E> {
         }
     }
 
+    fn is_zero(&self) -> bool {
+        Commitment::is_zero(self)
+    }
+
     fn has_degree_bound(&self) -> bool {
         self.shifted_comm.is_some()
     }
@@ -256,6 +515,56 @@ impl<E: PairingEngine> PCCommitment for Commitment<E> {
     }
 }
 
+/// The encoding is that of `comm`, followed by that of `shifted_comm` if it is
+/// present.
+impl<E: PairingEngine> HexCodec for Commitment<E> {
+    fn to_hex(&self) -> String {
+        let mut hex = self.comm.to_hex();
+        if let Some(shifted_comm) = &self.shifted_comm {
+            hex.push_str(&shifted_comm.to_hex());
+        }
+        hex
+    }
+
+    fn from_hex(hex: &str) -> Result<Self, Error> {
+        let comm_len = 2 * kzg10::Commitment::<E>::empty().serialized_size();
+        if hex.len() != comm_len && hex.len() != 2 * comm_len {
+            return Err(Error::MalformedHex(format!(
+                "hex string has length {}, expected {} or {}",
+                hex.len(),
+                comm_len,
+                2 * comm_len
+            )));
+        }
+        if !hex.is_char_boundary(comm_len) {
+            return Err(Error::MalformedHex("hex string is not ASCII".to_string()));
+        }
+        let (comm, shifted_comm) = hex.split_at(comm_len);
+        Ok(Self {
+            comm: kzg10::Commitment::from_hex(comm)?,
+            shifted_comm: if shifted_comm.is_empty() {
+                None
+            } else {
+                Some(kzg10::Commitment::from_hex(shifted_comm)?)
+            },
+        })
+    }
+}
+
+impl<E: PairingEngine> LabeledCommitment<Commitment<E>> {
+    /// Returns the degree bound of `self`, after checking that the commitment
+    /// has a shifted commitment exactly when a degree bound is set. Fails with
+    /// `Error::DegreeBoundMismatch` otherwise.
+    pub fn checked_degree_bound(&self) -> Result<Option<usize>, Error> {
+        if self.degree_bound().is_some() != self.commitment().shifted_comm.is_some() {
+            return Err(Error::DegreeBoundMismatch {
+                label: self.label().clone(),
+            });
+        }
+        Ok(self.degree_bound())
+    }
+}
+
 /// Prepared commitment to a polynomial that optionally enforces a degree bound.
 #[derive(Derivative)]
 #[derivative(
@@ -302,6 +611,27 @@ pub struct Randomness<F: PrimeField, P: UVPolynomial<F>> {
     pub shifted_rand: Option<kzg10::Randomness<F, P>>,
 }
 
+/// Wipes `rand` and `shifted_rand`; see the implementation for
+/// `kzg10::Randomness`. Wrap `self` in `zeroize::Zeroizing` to wipe it on
+/// drop.
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> zeroize::Zeroize for Randomness<F, DensePolynomial<F>> {
+    fn zeroize(&mut self) {
+        self.rand.zeroize();
+        if let Some(shifted_rand) = &mut self.shifted_rand {
+            shifted_rand.zeroize();
+        }
+    }
+}
+
+impl<F: PrimeField, P: UVPolynomial<F>> Randomness<F, P> {
+    /// Is `self` the empty randomness? This holds when `rand` is zero and
+    /// `shifted_rand` is either `None` or zero.
+    pub fn is_zero(&self) -> bool {
+        !self.rand.is_hiding() && self.shifted_rand.as_ref().map_or(true, |r| !r.is_hiding())
+    }
+}
+
 impl<'a, F: PrimeField, P: UVPolynomial<F>> Add<&'a Self> for Randomness<F, P> {
     type Output = Self;
 
@@ -376,3 +706,26 @@ impl<F: PrimeField, P: UVPolynomial<F>> PCRandomness for Randomness<F, P> {
         }
     }
 }
+
+/// A proof, output by `MarlinKZG10::prove_degree_at_least`, that a committed
+/// polynomial `p` has degree at least `d`.
+#[derive(Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Hash(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct DegreeLowerBoundProof<E: PairingEngine> {
+    /// The commitment, with degree bound `d - 1`, to the remainder `r` of `p`
+    /// modulo `X^d`.
+    pub remainder_comm: Commitment<E>,
+    /// The commitment to the quotient `h` of `p` by `X^d`.
+    pub quotient_comm: Commitment<E>,
+    /// The evaluations of `p`, `r` and `h` at the challenge point.
+    pub values: [E::Fr; 3],
+    /// The proof of the evaluations of `p`, `r` and `h` at the challenge point.
+    pub proof: kzg10::Proof<E>,
+}
@@ -1,12 +1,13 @@
 use crate::{kzg10, PCCommitterKey};
 use crate::{BTreeMap, BTreeSet, String, ToString, Vec};
 use crate::{BatchLCProof, Error, Evaluations, QuerySet};
-use crate::{LabeledCommitment, LabeledPolynomial, LinearCombination};
+use crate::{LabeledCommitment, LabeledPolynomial, LinearCombination, PolynomialLabel};
 use crate::{PCRandomness, PCUniversalParams, PolynomialCommitment, UVPolynomial};
 
 use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
-use ark_ff::{One, Zero};
+use ark_ff::{Field, One, Zero};
 use ark_std::{convert::TryInto, marker::PhantomData, ops::Div, vec};
+use digest::Digest;
 use rand_core::RngCore;
 
 mod data_structures;
@@ -51,8 +52,9 @@ pub(crate) fn shift_polynomial<E: PairingEngine, P: UVPolynomial<E::Fr>>(
 }
 
 impl<E: PairingEngine, P: UVPolynomial<E::Fr>> MarlinKZG10<E, P> {
-    /// MSM for `commitments` and `coeffs`
-    fn combine_commitments<'a>(
+    /// MSM for `commitments` and `coeffs`. The result can be normalized with
+    /// `normalize_commitments`.
+    pub fn combine_commitments<'a>(
         coeffs_and_comms: impl IntoIterator<Item = (E::Fr, &'a Commitment<E>)>,
     ) -> (E::G1Projective, Option<E::G1Projective>) {
         let mut combined_comm = E::G1Projective::zero();
@@ -72,7 +74,9 @@ impl<E: PairingEngine, P: UVPolynomial<E::Fr>> MarlinKZG10<E, P> {
         (combined_comm, combined_shifted_comm)
     }
 
-    fn normalize_commitments<'a>(
+    /// Converts combined commitments, as output by `combine_commitments`, to
+    /// affine form in a single batch.
+    pub fn normalize_commitments<'a>(
         commitments: Vec<(E::G1Projective, Option<E::G1Projective>)>,
     ) -> Vec<Commitment<E>> {
         let mut comms = Vec::with_capacity(commitments.len());
@@ -155,6 +159,219 @@ impl<E: PairingEngine, P: UVPolynomial<E::Fr>> MarlinKZG10<E, P> {
         end_timer!(acc_time);
         Ok((combined_comm, combined_value))
     }
+
+    /// Checks that each of the `labeled_commitments` that has a degree bound
+    /// commits to a polynomial that respects that bound, without opening them.
+    ///
+    /// For a commitment to `p` with degree bound `d`, the shifted commitment is
+    /// to `x^{max_degree - d} p`, so `e(shifted_comm, \beta^{d - max_degree} h)`
+    /// must equal `e(comm, h)`. These checks are combined using powers of
+    /// `challenge`, and the shifted commitments that share a degree bound are
+    /// summed, so the whole batch costs a single product of pairings with one
+    /// term per distinct degree bound.
+    ///
+    /// This requires `vk` to contain the negative powers of `h` (i.e., `pp` was
+    /// generated by `KZG10::setup` with `produce_g2_powers` set), and only
+    /// accepts non-hiding commitments, as the hiding randomness of `comm` and
+    /// `shifted_comm` is independent. If `vk` was trimmed with a nonzero hiding
+    /// bound, this fails with `Error::HidingProofUnsupported`. A commitment
+    /// with a shifted commitment but no degree bound is rejected with
+    /// `Error::DegreeBoundMismatch`.
+    pub fn batch_check_degree_bounds<'a>(
+        vk: &VerifierKey<E>,
+        labeled_commitments: impl IntoIterator<Item = &'a LabeledCommitment<Commitment<E>>>,
+        challenge: E::Fr,
+    ) -> Result<bool, Error> {
+        if vk.supports_hiding_commitments {
+            return Err(Error::HidingProofUnsupported);
+        }
+        let check_time = start_timer!(|| "Batch checking degree bounds");
+        let mut combined_comm = E::G1Projective::zero();
+        let mut combined_shifted_comms = BTreeMap::new();
+        let mut challenge_i = E::Fr::one();
+        for labeled_commitment in labeled_commitments {
+            let commitment = labeled_commitment.commitment();
+            let degree_bound = match labeled_commitment.degree_bound() {
+                Some(degree_bound) => degree_bound,
+                None if commitment.shifted_comm.is_some() => {
+                    return Err(Error::DegreeBoundMismatch {
+                        label: labeled_commitment.label().clone(),
+                    })
+                }
+                None => continue,
+            };
+            let shifted_comm = match &commitment.shifted_comm {
+                Some(shifted_comm) => shifted_comm,
+                None => return Ok(false),
+            };
+            if vk.get_prepared_neg_power_of_h(degree_bound).is_none() {
+                return Err(Error::UnsupportedDegreeBound(degree_bound));
+            }
+
+            combined_comm += &commitment.comm.0.mul(challenge_i);
+            *combined_shifted_comms
+                .entry(degree_bound)
+                .or_insert_with(E::G1Projective::zero) += &shifted_comm.0.mul(challenge_i);
+            challenge_i *= &challenge;
+        }
+
+        let mut g1_projective_elems = vec![-combined_comm];
+        let mut g2_prepared_elems = vec![vk.vk.prepared_h.clone()];
+        for (degree_bound, shifted_comm) in combined_shifted_comms {
+            g1_projective_elems.push(shifted_comm);
+            g2_prepared_elems.push(vk.get_prepared_neg_power_of_h(degree_bound).unwrap());
+        }
+
+        let g1_prepared_elems_iter =
+            E::G1Projective::batch_normalization_into_affine(g1_projective_elems.as_slice())
+                .into_iter()
+                .map(|a| a.into());
+
+        let g1_g2_prepared: Vec<(E::G1Prepared, E::G2Prepared)> =
+            g1_prepared_elems_iter.zip(g2_prepared_elems).collect();
+        let is_one: bool = E::product_of_pairings(g1_g2_prepared.iter()).is_one();
+        end_timer!(check_time);
+        Ok(is_one)
+    }
+}
+
+impl<E, P> MarlinKZG10<E, P>
+where
+    E: PairingEngine,
+    P: UVPolynomial<E::Fr, Point = E::Fr>,
+    for<'a, 'b> &'a P: Div<&'b P, Output = P>,
+{
+    /// Verifies that `values` are the evaluations at `point` of the polynomials
+    /// committed inside `commitments`, as `check` does, using a verifier key
+    /// that has already been prepared.
+    ///
+    /// Preparing a verifier key computes `E::Fr::size_in_bits()` doublings of
+    /// `g` and of every shift power, so when many proofs are checked against
+    /// the same key, `PreparedVerifierKey::prepare` should be called once,
+    /// outside the loop. Hiding proofs are rejected with
+    /// `Error::HidingProofUnsupported`, as the prepared key omits `gamma_g`.
+    pub fn check_prepared<'a>(
+        prepared_vk: &PreparedVerifierKey<E>,
+        commitments: impl IntoIterator<Item = &'a LabeledCommitment<Commitment<E>>>,
+        point: E::Fr,
+        values: impl IntoIterator<Item = E::Fr>,
+        proof: &kzg10::Proof<E>,
+        opening_challenge: E::Fr,
+    ) -> Result<bool, Error> {
+        let check_time = start_timer!(|| "Checking evaluations with a prepared key");
+        let mut combined_comm = E::G1Projective::zero();
+        let mut combined_value = E::Fr::zero();
+        let mut challenge_i = E::Fr::one();
+        for (labeled_commitment, value) in commitments.into_iter().zip(values) {
+            let degree_bound = labeled_commitment.degree_bound();
+            let commitment = labeled_commitment.commitment();
+            if degree_bound.is_some() != commitment.shifted_comm.is_some() {
+                return Ok(false);
+            }
+
+            combined_comm += &commitment.comm.0.mul(challenge_i);
+            combined_value += &(value * &challenge_i);
+            challenge_i *= &opening_challenge;
+
+            if let Some(degree_bound) = degree_bound {
+                let prepared_shift_power = prepared_vk
+                    .get_prepared_shift_power(degree_bound)
+                    .ok_or(Error::UnsupportedDegreeBound(degree_bound))?;
+                let mut adjusted_comm = commitment.shifted_comm.unwrap().0.into_projective()
+                    - &kzg10::mul_by_doublings(prepared_shift_power, value);
+                adjusted_comm *= challenge_i;
+                combined_comm += &adjusted_comm;
+                challenge_i *= &opening_challenge;
+            }
+        }
+        let combined_comm = kzg10::Commitment(combined_comm.into_affine());
+        let result = kzg10::KZG10::<E, P>::check_prepared(
+            &prepared_vk.prepared_vk,
+            &combined_comm,
+            point,
+            combined_value,
+            proof,
+        );
+        end_timer!(check_time);
+        result
+    }
+
+    /// Outputs a proof that `polynomial` respects its degree bound, for use with
+    /// `verify_degree_bound`. This is the part of the proof output by `open`
+    /// that concerns the shifted polynomial, on its own.
+    pub fn open_degree_bound(
+        ck: &CommitterKey<E>,
+        polynomial: &LabeledPolynomial<E::Fr, P>,
+        point: E::Fr,
+        rand: &Randomness<E::Fr, P>,
+    ) -> Result<kzg10::Proof<E>, Error> {
+        let (degree_bound, shifted_rand) = match (polynomial.degree_bound(), &rand.shifted_rand) {
+            (Some(degree_bound), Some(shifted_rand)) => (degree_bound, shifted_rand),
+            _ => {
+                return Err(Error::DegreeBoundMismatch {
+                    label: polynomial.label().clone(),
+                })
+            }
+        };
+        let enforced_degree_bounds: Option<&[usize]> = ck
+            .enforced_degree_bounds
+            .as_ref()
+            .map(|bounds| bounds.as_slice());
+        kzg10::KZG10::<E, P>::check_degrees_and_bounds(
+            ck.supported_degree(),
+            ck.max_degree,
+            enforced_degree_bounds,
+            polynomial,
+        )?;
+
+        let (witness, shifted_rand_witness) = kzg10::KZG10::<E, P>::compute_witness_polynomial(
+            polynomial.polynomial(),
+            point,
+            shifted_rand,
+        )?;
+        let shifted_witness = shift_polynomial(ck, &witness, degree_bound);
+        kzg10::KZG10::open_with_witness_polynomial(
+            &ck.shifted_powers(None).unwrap(),
+            point,
+            shifted_rand,
+            &shifted_witness,
+            shifted_rand_witness.as_ref(),
+        )
+    }
+
+    /// Checks only the degree bound of `commitment`: that its shifted
+    /// commitment, adjusted by `value` times the shift power of
+    /// `degree_bound`, opens to zero at `point` under `proof`, as output by
+    /// `open_degree_bound`. Unlike `check`, this does not check that `value`
+    /// is the evaluation of the unshifted commitment.
+    ///
+    /// Fails with `Error::UnsupportedDegreeBound` if `vk` has no shift power
+    /// for `degree_bound`, and rejects commitments without a shifted
+    /// commitment.
+    pub fn verify_degree_bound(
+        vk: &VerifierKey<E>,
+        commitment: &Commitment<E>,
+        degree_bound: usize,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &kzg10::Proof<E>,
+    ) -> Result<bool, Error> {
+        let shift_power = vk
+            .get_shift_power(degree_bound)
+            .ok_or(Error::UnsupportedDegreeBound(degree_bound))?;
+        let shifted_comm = match &commitment.shifted_comm {
+            Some(shifted_comm) => shifted_comm,
+            None => return Ok(false),
+        };
+        let adjusted_comm = shifted_comm.0.into_projective() - &shift_power.mul(value);
+        kzg10::KZG10::<E, P>::check(
+            &vk.vk,
+            &kzg10::Commitment(adjusted_comm.into_affine()),
+            point,
+            E::Fr::zero(),
+            proof,
+        )
+    }
 }
 
 impl<E, P> PolynomialCommitment<E::Fr, P> for MarlinKZG10<E, P>
@@ -191,9 +408,13 @@ where
         enforced_degree_bounds: Option<&[usize]>,
     ) -> Result<(Self::CommitterKey, Self::VerifierKey), Self::Error> {
         let max_degree = pp.max_degree();
-        if supported_degree > max_degree {
-            return Err(Error::TrimmingDegreeTooLarge);
-        }
+        kzg10::check_trim_degrees(supported_degree, max_degree, enforced_degree_bounds)?;
+        kzg10::check_trim_allocation(
+            pp,
+            supported_degree,
+            supported_hiding_bound,
+            enforced_degree_bounds.map_or(false, |bounds| !bounds.is_empty()),
+        )?;
 
         // Construct the KZG10 committer key for committing to unshifted polynomials.
         let ck_time = start_timer!(|| format!(
@@ -203,9 +424,7 @@ where
         let powers = pp.powers_of_g[..=supported_degree].to_vec();
         // We want to support making up to `supported_hiding_bound` queries to committed
         // polynomials.
-        let powers_of_gamma_g = (0..=supported_hiding_bound + 1)
-            .map(|i| pp.powers_of_gamma_g[&i])
-            .collect::<Vec<_>>();
+        let powers_of_gamma_g = kzg10::trim_powers_of_gamma_g(pp, supported_hiding_bound)?;
 
         end_timer!(ck_time);
 
@@ -258,6 +477,22 @@ where
                 (None, None)
             };
 
+        // Negative powers of `h` are only available if `pp` was generated with them,
+        // and are only usable with non-hiding commitments.
+        let supports_hiding_commitments = supported_hiding_bound > 0;
+        let degree_bounds_and_prepared_neg_powers_of_h = enforced_degree_bounds
+            .as_ref()
+            .filter(|v| !supports_hiding_commitments && !v.is_empty())
+            .and_then(|v| {
+                v.iter()
+                    .map(|d| {
+                        pp.prepared_neg_powers_of_h
+                            .get(&(max_degree - *d))
+                            .map(|neg_power| (*d, neg_power.clone()))
+                    })
+                    .collect::<Option<Vec<_>>>()
+            });
+
         let ck = CommitterKey {
             powers,
             shifted_powers,
@@ -269,6 +504,8 @@ where
         let vk = VerifierKey {
             vk,
             degree_bounds_and_shift_powers,
+            degree_bounds_and_prepared_neg_powers_of_h,
+            supports_hiding_commitments,
             supported_degree,
             max_degree,
         };
@@ -276,6 +513,10 @@ where
     }
 
     /// Outputs a commitment to `polynomial`.
+    ///
+    /// Each labeled commitment carries the degree bound of the corresponding
+    /// polynomial, and has a shifted commitment if and only if that bound is
+    /// `Some`.
     fn commit<'a>(
         ck: &Self::CommitterKey,
         polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<E::Fr, P>>,
@@ -460,6 +701,13 @@ where
         Commitment<E>: 'a,
     {
         let check_time = start_timer!(|| "Checking evaluations");
+        let commitments = commitments.into_iter().collect::<Vec<_>>();
+        if commitments
+            .iter()
+            .any(|c| c.degree_bound().is_some() != c.commitment().shifted_comm.is_some())
+        {
+            return Ok(false);
+        }
         let (combined_comm, combined_value) =
             Self::accumulate_commitments_and_values_individual_opening_challenges(
                 vk,
@@ -473,6 +721,13 @@ where
         Ok(result)
     }
 
+    /// Commitments with and without degree bounds, hiding or not, may be mixed
+    /// freely. For a commitment with a degree bound, the shifted commitment,
+    /// adjusted by the claimed value times the shift power, is folded into the
+    /// same random combination as the unshifted commitments, so the bound is
+    /// enforced without any extra pairing: the whole batch costs a single
+    /// product of two pairings. A commitment that has a degree bound but no
+    /// shifted commitment (or vice versa) is rejected.
     fn batch_check_individual_opening_challenges<'a, R: RngCore>(
         vk: &VerifierKey<E>,
         commitments: impl IntoIterator<Item = &'a LabeledCommitment<Commitment<E>>>,
@@ -508,11 +763,11 @@ where
                 let commitment = commitments.get(label).ok_or(Error::MissingPolynomial {
                     label: label.to_string(),
                 })?;
-                let degree_bound = commitment.degree_bound();
-                assert_eq!(
-                    degree_bound.is_some(),
-                    commitment.commitment().shifted_comm.is_some()
-                );
+                if commitment.degree_bound().is_some()
+                    != commitment.commitment().shifted_comm.is_some()
+                {
+                    return Ok(false);
+                }
 
                 let v_i =
                     evaluations
@@ -815,6 +1070,358 @@ where
     }
 }
 
+impl<E, P> MarlinKZG10<E, P>
+where
+    E: PairingEngine,
+    P: UVPolynomial<E::Fr, Point = E::Fr>,
+    for<'a, 'b> &'a P: Div<&'b P, Output = P>,
+{
+    /// Commits to each of `polynomials` without degree bounds, using `hiding_bound`
+    /// for all of them. This is a convenience for prototyping and tests that
+    /// labels the `i`-th polynomial with `i` and then calls `commit`; protocols
+    /// should use `commit` with `LabeledPolynomial`s directly.
+    pub fn commit_unlabeled(
+        ck: &CommitterKey<E>,
+        polynomials: &[P],
+        hiding_bound: Option<usize>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<(Vec<Commitment<E>>, Vec<Randomness<E::Fr, P>>), Error> {
+        let labeled_polynomials = polynomials
+            .iter()
+            .enumerate()
+            .map(|(i, p)| LabeledPolynomial::new(i.to_string(), p.clone(), None, hiding_bound))
+            .collect::<Vec<_>>();
+        let (labeled_comms, rands) = Self::commit(ck, &labeled_polynomials, rng)?;
+        let comms = labeled_comms
+            .into_iter()
+            .map(|c| c.commitment().clone())
+            .collect();
+        Ok((comms, rands))
+    }
+
+    /// Computes the polynomial `L = \sum_i c_i p_i` described by `lc`, and
+    /// outputs its evaluation at `point` together with a proof of it, without
+    /// committing to `L`. `polynomials` and `rands` must contain the
+    /// polynomials referred to by `lc` and their randomness, in the same order.
+    ///
+    /// The verifier obtains the commitment to `L` by combining the commitments
+    /// to the `p_i` with `combine_commitments` and `normalize_commitments`, and
+    /// then calls `check` with `opening_challenge`. As in `open_combinations`,
+    /// the constant terms of `lc` are ignored, and only an `lc` with a single
+    /// term may have a degree bound.
+    pub fn open_lc<'a>(
+        ck: &CommitterKey<E>,
+        lc: &LinearCombination<E::Fr>,
+        polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<E::Fr, P>>,
+        point: E::Fr,
+        opening_challenge: E::Fr,
+        rands: impl IntoIterator<Item = &'a Randomness<E::Fr, P>>,
+    ) -> Result<(E::Fr, kzg10::Proof<E>), Error>
+    where
+        P: 'a,
+    {
+        let label_map = polynomials
+            .into_iter()
+            .zip(rands)
+            .map(|(p, r)| (p.label(), (p, r)))
+            .collect::<BTreeMap<_, _>>();
+
+        let lc_label = lc.label().clone();
+        let mut poly = P::zero();
+        let mut degree_bound = None;
+        let mut hiding_bound = None;
+        let mut randomness = Randomness::<E::Fr, P>::empty();
+
+        let num_polys = lc.len();
+        for (coeff, label) in lc.iter().filter(|(_, l)| !l.is_one()) {
+            let label: &String = label.try_into().expect("cannot be one!");
+            let &(cur_poly, cur_rand) = label_map.get(label).ok_or(Error::MissingPolynomial {
+                label: label.to_string(),
+            })?;
+
+            if num_polys == 1 && cur_poly.degree_bound().is_some() {
+                assert!(
+                    coeff.is_one(),
+                    "Coefficient must be one for degree-bounded equations"
+                );
+                degree_bound = cur_poly.degree_bound();
+            } else if cur_poly.degree_bound().is_some() {
+                return Err(Error::EquationHasDegreeBounds(lc_label));
+            }
+
+            // Some(_) > None, always.
+            hiding_bound = core::cmp::max(hiding_bound, cur_poly.hiding_bound());
+            poly += (*coeff, cur_poly.polynomial());
+            randomness += (*coeff, cur_rand);
+        }
+
+        let value = poly.evaluate(&point);
+        let lc_poly = LabeledPolynomial::new(lc_label, poly, degree_bound, hiding_bound);
+        let proof = Self::open(
+            ck,
+            &[lc_poly],
+            &[],
+            &point,
+            opening_challenge,
+            &[randomness],
+            None,
+        )?;
+        Ok((value, proof))
+    }
+
+    /// Commits to `polynomials` as `commit` does, but returns the commitments
+    /// and randomness keyed by label. Fails if two polynomials share a label.
+    pub fn commit_map<'a>(
+        ck: &CommitterKey<E>,
+        polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<E::Fr, P>>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<
+        (
+            BTreeMap<PolynomialLabel, Commitment<E>>,
+            BTreeMap<PolynomialLabel, Randomness<E::Fr, P>>,
+        ),
+        Error,
+    >
+    where
+        P: 'a,
+    {
+        let polynomials = polynomials.into_iter().collect::<Vec<_>>();
+        let mut labels = BTreeSet::new();
+        for p in &polynomials {
+            if !labels.insert(p.label()) {
+                return Err(Error::DuplicateLabel {
+                    label: p.label().clone(),
+                });
+            }
+        }
+
+        let (labeled_comms, rands) = Self::commit(ck, polynomials, rng)?;
+        let rands = labeled_comms
+            .iter()
+            .map(|c| c.label().clone())
+            .zip(rands)
+            .collect();
+        let comms = labeled_comms
+            .into_iter()
+            .map(|c| (c.label().clone(), c.commitment().clone()))
+            .collect();
+        Ok((comms, rands))
+    }
+
+    /// Commits to `polynomials` as `commit` does, but computes the commitment
+    /// to polynomials with identical coefficients and degree bounds only once,
+    /// and reuses it for each of their labels. Polynomials are compared by
+    /// their coefficients, so no two distinct polynomials are ever merged.
+    ///
+    /// Hiding commitments are randomized, so polynomials with a hiding bound
+    /// are always committed to separately.
+    pub fn commit_dedup<'a>(
+        ck: &CommitterKey<E>,
+        polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<E::Fr, P>>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<
+        (
+            Vec<LabeledCommitment<Commitment<E>>>,
+            Vec<Randomness<E::Fr, P>>,
+        ),
+        Error,
+    >
+    where
+        P: 'a,
+    {
+        let rng = &mut crate::optional_rng::OptionalRng(rng);
+        Self::commit_dedup_with(polynomials, |p| Self::commit(ck, Some(p), Some(&mut *rng)))
+    }
+
+    /// Deduplicates `polynomials` as `commit_dedup` does, calling `commit_one`
+    /// for each polynomial whose commitment cannot be reused.
+    fn commit_dedup_with<'a>(
+        polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<E::Fr, P>>,
+        mut commit_one: impl FnMut(
+            &'a LabeledPolynomial<E::Fr, P>,
+        ) -> Result<
+            (
+                Vec<LabeledCommitment<Commitment<E>>>,
+                Vec<Randomness<E::Fr, P>>,
+            ),
+            Error,
+        >,
+    ) -> Result<
+        (
+            Vec<LabeledCommitment<Commitment<E>>>,
+            Vec<Randomness<E::Fr, P>>,
+        ),
+        Error,
+    >
+    where
+        P: 'a,
+    {
+        let mut commitments: Vec<LabeledCommitment<Commitment<E>>> = Vec::new();
+        let mut randomness = Vec::new();
+        let mut first_index = BTreeMap::<_, usize>::new();
+        for p in polynomials {
+            if p.hiding_bound().is_none() {
+                let key = (p.polynomial().coeffs(), p.degree_bound());
+                if let Some(&i) = first_index.get(&key) {
+                    let comm = commitments[i].commitment().clone();
+                    commitments.push(LabeledCommitment::new(
+                        p.label().clone(),
+                        comm,
+                        p.degree_bound(),
+                    ));
+                    let rand = randomness[i].clone();
+                    randomness.push(rand);
+                    continue;
+                }
+                first_index.insert(key, commitments.len());
+            }
+            let (comms, rands) = commit_one(p)?;
+            commitments.extend(comms);
+            randomness.extend(rands);
+        }
+        Ok((commitments, randomness))
+    }
+
+    /// Outputs a proof that `p`, committed in `p_comm` with randomness
+    /// `p_rand`, has degree at least `d`, that is, a nonzero coefficient of
+    /// `X^i` for some `i >= d`.
+    ///
+    /// `p` is split as `r + X^d h`, where `r` has degree less than `d`; `r` is
+    /// committed with degree bound `d - 1` (which `ck` must support) and `h`
+    /// without one, with the hiding bound of `p`. Then `p`, `r` and `h` are
+    /// opened at a point `x` derived by hashing `p_comm`, both commitments
+    /// and `d` with `D`. The proof reveals `p(x)`, `r(x)` and `h(x)`.
+    ///
+    /// If `p` has degree less than `d`, `h` is zero, and the resulting proof
+    /// is rejected by `check_degree_at_least`.
+    pub fn prove_degree_at_least<D: Digest>(
+        ck: &CommitterKey<E>,
+        p: &LabeledPolynomial<E::Fr, P>,
+        p_comm: &LabeledCommitment<Commitment<E>>,
+        p_rand: &Randomness<E::Fr, P>,
+        d: usize,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<DegreeLowerBoundProof<E>, Error> {
+        if d == 0 {
+            return Err(Error::DegreeIsZero);
+        }
+        let prove_time = start_timer!(|| format!("Proving degree at least {}", d));
+        let coeffs = p.coeffs();
+        let split = ark_std::cmp::min(d, coeffs.len());
+        let remainder = LabeledPolynomial::new(
+            "remainder".to_string(),
+            P::from_coefficients_slice(&coeffs[..split]),
+            Some(d - 1),
+            p.hiding_bound(),
+        );
+        let quotient = LabeledPolynomial::new(
+            "quotient".to_string(),
+            P::from_coefficients_slice(&coeffs[split..]),
+            None,
+            p.hiding_bound(),
+        );
+        let polynomials = [p.clone(), remainder, quotient];
+        let (comms, rands) = Self::commit(ck, &polynomials[1..], rng)?;
+
+        let (point, opening_challenge) = Self::degree_lower_bound_challenges::<D>(
+            p_comm,
+            comms[0].commitment(),
+            comms[1].commitment(),
+            d,
+        );
+        let all_comms = [p_comm.clone(), comms[0].clone(), comms[1].clone()];
+        let all_rands = [p_rand.clone(), rands[0].clone(), rands[1].clone()];
+        let proof = Self::open(
+            ck,
+            &polynomials,
+            &all_comms,
+            &point,
+            opening_challenge,
+            &all_rands,
+            None,
+        )?;
+        end_timer!(prove_time);
+
+        Ok(DegreeLowerBoundProof {
+            remainder_comm: comms[0].commitment().clone(),
+            quotient_comm: comms[1].commitment().clone(),
+            values: [
+                polynomials[0].evaluate(&point),
+                polynomials[1].evaluate(&point),
+                polynomials[2].evaluate(&point),
+            ],
+            proof,
+        })
+    }
+
+    /// Verifies a proof, output by `prove_degree_at_least`, that the polynomial
+    /// committed in `p_comm` has degree at least `d`.
+    ///
+    /// The verifier checks the openings of `p`, `r` and `h` at `x`, that
+    /// `p(x) - r(x) = x^d h(x)` and that `h(x)` is nonzero. The degree bound
+    /// of `r` is enforced as for any other commitment with a degree bound, so
+    /// `r` has degree less than `d`. As `x` is derived from the commitments,
+    /// the identity at `x` implies `p = r + X^d h` except with probability
+    /// `deg(p) / |F|`, and `h(x) != 0` implies `h != 0`, so that `p` has a
+    /// nonzero coefficient of degree at least `d`. The guarantee is thus
+    /// computational (degree bounds rely on the hardness assumptions of
+    /// [[CHMMVW20]][marlin]) and statistical in the choice of `x`.
+    ///
+    /// [marlin]: https://eprint.iacr.org/2019/1047
+    pub fn check_degree_at_least<D: Digest>(
+        vk: &VerifierKey<E>,
+        p_comm: &LabeledCommitment<Commitment<E>>,
+        d: usize,
+        proof: &DegreeLowerBoundProof<E>,
+    ) -> Result<bool, Error> {
+        if d == 0 {
+            return Err(Error::DegreeIsZero);
+        }
+        let check_time = start_timer!(|| format!("Checking degree at least {}", d));
+        let (point, opening_challenge) = Self::degree_lower_bound_challenges::<D>(
+            p_comm,
+            &proof.remainder_comm,
+            &proof.quotient_comm,
+            d,
+        );
+        let [p_value, remainder_value, quotient_value] = proof.values;
+        let is_split = p_value - &remainder_value == point.pow(&[d as u64]) * &quotient_value;
+
+        let comms = [
+            p_comm.clone(),
+            LabeledCommitment::new("remainder".to_string(), proof.remainder_comm, Some(d - 1)),
+            LabeledCommitment::new("quotient".to_string(), proof.quotient_comm, None),
+        ];
+        let result = is_split
+            && !quotient_value.is_zero()
+            && Self::check(
+                vk,
+                &comms,
+                &point,
+                proof.values.iter().cloned(),
+                &proof.proof,
+                opening_challenge,
+                None,
+            )?;
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Hashes the commitments and `d` to the evaluation point and the opening
+    /// challenge used by `prove_degree_at_least`.
+    fn degree_lower_bound_challenges<D: Digest>(
+        p_comm: &LabeledCommitment<Commitment<E>>,
+        remainder_comm: &Commitment<E>,
+        quotient_comm: &Commitment<E>,
+        d: usize,
+    ) -> (E::Fr, E::Fr) {
+        let bytes = ark_ff::to_bytes![p_comm.commitment(), remainder_comm, quotient_comm, d as u64]
+            .unwrap();
+        let mut challenges = (0u8..2).map(|tag| kzg10::hash_to_field::<D, _>(&[tag], &bytes));
+        (challenges.next().unwrap(), challenges.next().unwrap())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(non_camel_case_types)]
@@ -1029,6 +1636,13 @@ mod tests {
         println!("Finished bls12-381");
     }
 
+    #[test]
+    fn query_set_order_test() {
+        use crate::tests::*;
+        query_set_order_test::<_, _, PC_Bls12_381>(rand_poly::<Bls12_381>, rand_point::<Bls12_381>)
+            .expect("test failed for bls12-381");
+    }
+
     #[test]
     #[should_panic]
     fn bad_degree_bound_test() {
@@ -1046,4 +1660,1170 @@ mod tests {
         .expect("test failed for bls12-381");
         println!("Finished bls12-381");
     }
+
+    #[test]
+    fn batch_check_degree_bounds_test() {
+        use crate::kzg10::KZG10;
+        use crate::{LabeledCommitment, LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let max_degree = 32;
+        let pp = KZG10::<Bls12_381, UniPoly_381>::setup(max_degree, true, rng).unwrap();
+        let (ck, vk) = PC_Bls12_381::trim(&pp, max_degree, 0, Some(&[5, 10, 20])).unwrap();
+
+        let polynomials = [5, 10, 20, 10]
+            .iter()
+            .enumerate()
+            .map(|(i, &d)| {
+                let p = rand_poly::<Bls12_381>(d, None, rng);
+                LabeledPolynomial::new(format!("p{}", i), p, Some(d), None)
+            })
+            .collect::<Vec<_>>();
+        let (comms, _) = PC_Bls12_381::commit(&ck, &polynomials, None).unwrap();
+
+        let challenge = <Bls12_381 as PairingEngine>::Fr::rand(rng);
+        assert!(PC_Bls12_381::batch_check_degree_bounds(&vk, &comms, challenge).unwrap());
+
+        // Claim that the degree-20 polynomial respects a degree bound of 10.
+        let mut bad_comms = comms.clone();
+        bad_comms[2] = LabeledCommitment::new(
+            comms[2].label().clone(),
+            comms[2].commitment().clone(),
+            Some(10),
+        );
+        assert!(!PC_Bls12_381::batch_check_degree_bounds(&vk, &bad_comms, challenge).unwrap());
+
+        // Drop the degree bound of a commitment that has a shifted commitment.
+        let mut bad_comms = comms.clone();
+        bad_comms[1] = LabeledCommitment::new(
+            comms[1].label().clone(),
+            comms[1].commitment().clone(),
+            None,
+        );
+        assert!(matches!(
+            PC_Bls12_381::batch_check_degree_bounds(&vk, &bad_comms, challenge),
+            Err(crate::Error::DegreeBoundMismatch { label }) if label == "p1"
+        ));
+
+        // Keys that support hiding commitments are rejected.
+        let (_, hiding_vk) = PC_Bls12_381::trim(&pp, max_degree, 1, Some(&[5, 10, 20])).unwrap();
+        assert!(matches!(
+            PC_Bls12_381::batch_check_degree_bounds(&hiding_vk, &comms, challenge),
+            Err(crate::Error::HidingProofUnsupported)
+        ));
+    }
+
+    #[test]
+    fn commit_unlabeled_test() {
+        use crate::{LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(20, None, rng).unwrap();
+        let (ck, _) = PC_Bls12_381::trim(&pp, 20, 0, None).unwrap();
+
+        let polynomials = (0..3)
+            .map(|_| rand_poly::<Bls12_381>(20, None, rng))
+            .collect::<Vec<_>>();
+        let (comms, _) = PC_Bls12_381::commit_unlabeled(&ck, &polynomials, None, None).unwrap();
+
+        let labeled_polynomials = polynomials
+            .iter()
+            .enumerate()
+            .map(|(i, p)| LabeledPolynomial::new(format!("p{}", i), p.clone(), None, None))
+            .collect::<Vec<_>>();
+        let (labeled_comms, _) = PC_Bls12_381::commit(&ck, &labeled_polynomials, None).unwrap();
+
+        assert_eq!(comms.len(), labeled_comms.len());
+        for (comm, labeled_comm) in comms.iter().zip(&labeled_comms) {
+            assert_eq!(comm, labeled_comm.commitment());
+        }
+    }
+
+    #[test]
+    fn commit_map_test() {
+        use crate::{Error, LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(20, None, rng).unwrap();
+        let (ck, _) = PC_Bls12_381::trim(&pp, 20, 0, None).unwrap();
+
+        let mut polynomials = ["b", "a", "c"]
+            .iter()
+            .map(|l| {
+                let p = rand_poly::<Bls12_381>(20, None, rng);
+                LabeledPolynomial::new(l.to_string(), p, None, None)
+            })
+            .collect::<Vec<_>>();
+        let (comms, _) = PC_Bls12_381::commit(&ck, &polynomials, None).unwrap();
+        let (comm_map, rand_map) = PC_Bls12_381::commit_map(&ck, &polynomials, None).unwrap();
+        assert_eq!(comm_map.len(), comms.len());
+        assert_eq!(rand_map.len(), comms.len());
+        for comm in &comms {
+            assert_eq!(&comm_map[comm.label()], comm.commitment());
+        }
+
+        polynomials.push(polynomials[1].clone());
+        let result = PC_Bls12_381::commit_map(&ck, &polynomials, None);
+        assert!(matches!(result, Err(Error::DuplicateLabel { label }) if label == "a"));
+    }
+
+    #[test]
+    fn open_lc_test() {
+        use crate::{
+            LabeledCommitment, LabeledPolynomial, LinearCombination, PolynomialCommitment,
+        };
+        use ark_ff::{test_rng, One};
+
+        type Fr = <Bls12_381 as PairingEngine>::Fr;
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(20, None, rng).unwrap();
+        let (ck, vk) = PC_Bls12_381::trim(&pp, 20, 1, None).unwrap();
+
+        let polynomials = ["a", "b"]
+            .iter()
+            .map(|l| {
+                let p = rand_poly::<Bls12_381>(20, None, rng);
+                LabeledPolynomial::new(l.to_string(), p, None, Some(1))
+            })
+            .collect::<Vec<_>>();
+        let (comms, rands) = PC_Bls12_381::commit(&ck, &polynomials, Some(rng)).unwrap();
+
+        let two = Fr::one() + &Fr::one();
+        let lc = LinearCombination::new("2a - b", vec![(two, "a"), (-Fr::one(), "b")]);
+        let point = Fr::rand(rng);
+        let opening_challenge = Fr::rand(rng);
+        let (value, proof) =
+            PC_Bls12_381::open_lc(&ck, &lc, &polynomials, point, opening_challenge, &rands)
+                .unwrap();
+        assert_eq!(
+            value,
+            two * &polynomials[0].evaluate(&point) - &polynomials[1].evaluate(&point)
+        );
+
+        let combined = PC_Bls12_381::combine_commitments(vec![
+            (two, comms[0].commitment()),
+            (-Fr::one(), comms[1].commitment()),
+        ]);
+        let lc_comm = PC_Bls12_381::normalize_commitments(vec![combined]).remove(0);
+        let lc_comm = LabeledCommitment::new(lc.label().clone(), lc_comm, None);
+        assert!(PC_Bls12_381::check(
+            &vk,
+            &[lc_comm.clone()],
+            &point,
+            vec![value],
+            &proof,
+            opening_challenge,
+            None
+        )
+        .unwrap());
+        assert!(!PC_Bls12_381::check(
+            &vk,
+            &[lc_comm],
+            &point,
+            vec![value + &Fr::one()],
+            &proof,
+            opening_challenge,
+            None
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn verifier_key_from_committer_key_test() {
+        use crate::{LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let max_degree = 32;
+        let pp = PC_Bls12_381::setup(max_degree, None, rng).unwrap();
+        let (ck, trimmed_vk) = PC_Bls12_381::trim(&pp, 20, 1, Some(&[5, 10, 20])).unwrap();
+        let vk = super::VerifierKey::from_committer_key(&ck, pp.h, pp.beta_h);
+
+        assert_eq!(
+            vk.degree_bounds_and_shift_powers,
+            trimmed_vk.degree_bounds_and_shift_powers
+        );
+        assert_eq!(vk.supported_degree, trimmed_vk.supported_degree);
+        assert_eq!(vk.max_degree, trimmed_vk.max_degree);
+        assert!(vk.supports_hiding_commitments);
+        assert_eq!(
+            vk.supports_hiding_commitments,
+            trimmed_vk.supports_hiding_commitments
+        );
+
+        let polynomials = [(5, Some(5)), (10, Some(20)), (20, None)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(d, bound))| {
+                let p = rand_poly::<Bls12_381>(d, None, rng);
+                LabeledPolynomial::new(format!("p{}", i), p, bound, Some(1))
+            })
+            .collect::<Vec<_>>();
+        let (comms, rands) = PC_Bls12_381::commit(&ck, &polynomials, Some(rng)).unwrap();
+
+        let point = rand_point::<Bls12_381>(None, rng);
+        let opening_challenge = <Bls12_381 as PairingEngine>::Fr::rand(rng);
+        let proof = PC_Bls12_381::open(
+            &ck,
+            &polynomials,
+            &comms,
+            &point,
+            opening_challenge,
+            &rands,
+            Some(rng),
+        )
+        .unwrap();
+        let values = polynomials.iter().map(|p| p.evaluate(&point));
+        assert!(
+            PC_Bls12_381::check(&vk, &comms, &point, values, &proof, opening_challenge, None)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn commit_propagates_degree_bounds_test() {
+        use crate::{LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(20, None, rng).unwrap();
+        let (ck, _) = PC_Bls12_381::trim(&pp, 20, 1, Some(&[5, 10])).unwrap();
+
+        let polynomials = [(5, Some(5)), (8, Some(10)), (20, None)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(d, bound))| {
+                let p = rand_poly::<Bls12_381>(d, None, rng);
+                LabeledPolynomial::new(format!("p{}", i), p, bound, Some(1))
+            })
+            .collect::<Vec<_>>();
+        let (comms, _) = PC_Bls12_381::commit(&ck, &polynomials, Some(rng)).unwrap();
+
+        for (comm, p) in comms.iter().zip(&polynomials) {
+            assert_eq!(comm.label(), p.label());
+            assert_eq!(comm.degree_bound(), p.degree_bound());
+            assert_eq!(
+                comm.commitment().shifted_comm.is_some(),
+                p.degree_bound().is_some()
+            );
+        }
+    }
+
+    #[test]
+    fn commit_dedup_test() {
+        use crate::{LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(20, None, rng).unwrap();
+        let (ck, _) = PC_Bls12_381::trim(&pp, 20, 1, None).unwrap();
+
+        let selector = rand_poly::<Bls12_381>(20, None, rng);
+        let other = rand_poly::<Bls12_381>(20, None, rng);
+        let polynomials = vec![
+            LabeledPolynomial::new("q_1".to_string(), selector.clone(), None, None),
+            LabeledPolynomial::new("q_2".to_string(), selector.clone(), None, None),
+            LabeledPolynomial::new("other".to_string(), other, None, None),
+            LabeledPolynomial::new("hiding_1".to_string(), selector.clone(), None, Some(1)),
+            LabeledPolynomial::new("hiding_2".to_string(), selector, None, Some(1)),
+        ];
+
+        let (comms, rands) = PC_Bls12_381::commit_dedup(&ck, &polynomials, Some(rng)).unwrap();
+
+        assert_eq!(comms.len(), polynomials.len());
+        assert_eq!(rands.len(), polynomials.len());
+        for (comm, p) in comms.iter().zip(&polynomials) {
+            assert_eq!(comm.label(), p.label());
+        }
+        assert_eq!(comms[0].commitment(), comms[1].commitment());
+        assert_ne!(comms[0].commitment(), comms[2].commitment());
+        assert_ne!(comms[3].commitment(), comms[4].commitment());
+
+        let (expected, _) = PC_Bls12_381::commit(&ck, &polynomials[..3], None).unwrap();
+        for (comm, expected) in comms.iter().zip(&expected) {
+            assert_eq!(comm.commitment(), expected.commitment());
+        }
+
+        // One commitment each for the selector, `other`, and the two hiding
+        // polynomials.
+        let mut committed = Vec::new();
+        PC_Bls12_381::commit_dedup_with(&polynomials, |p| {
+            committed.push(p.label().clone());
+            PC_Bls12_381::commit(&ck, Some(p), Some(&mut *rng))
+        })
+        .unwrap();
+        assert_eq!(committed, ["q_1", "other", "hiding_1", "hiding_2"]);
+
+        // Duplicates with a degree bound reuse the shifted randomness of the
+        // first occurrence, so they can be opened.
+        let (ck, vk) = PC_Bls12_381::trim(&pp, 20, 1, Some(&[10])).unwrap();
+        let bounded = rand_poly::<Bls12_381>(10, None, rng);
+        let polynomials = vec![
+            LabeledPolynomial::new("b_1".to_string(), bounded.clone(), Some(10), None),
+            LabeledPolynomial::new("b_2".to_string(), bounded, Some(10), None),
+        ];
+        let (comms, rands) = PC_Bls12_381::commit_dedup(&ck, &polynomials, Some(rng)).unwrap();
+        assert_eq!(comms[0].commitment(), comms[1].commitment());
+        assert!(rands.iter().all(|r| r.shifted_rand.is_some()));
+
+        let point = rand_point::<Bls12_381>(None, rng);
+        let opening_challenge = <Bls12_381 as PairingEngine>::Fr::rand(rng);
+        let proof = PC_Bls12_381::open(
+            &ck,
+            &polynomials,
+            &comms,
+            &point,
+            opening_challenge,
+            &rands,
+            Some(rng),
+        )
+        .unwrap();
+        let values = polynomials.iter().map(|p| p.evaluate(&point));
+        assert!(
+            PC_Bls12_381::check(&vk, &comms, &point, values, &proof, opening_challenge, None)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn verifier_key_compact_serialization_test() {
+        use crate::PolynomialCommitment;
+        use ark_ff::test_rng;
+        use ark_serialize::CanonicalSerialize;
+
+        let rng = &mut test_rng();
+        let max_degree = 128;
+        let pp = PC_Bls12_381::setup(max_degree, None, rng).unwrap();
+        let bounds = (1..=64).collect::<Vec<_>>();
+        let (_, vk) = PC_Bls12_381::trim(&pp, max_degree, 0, Some(&bounds)).unwrap();
+
+        let mut bytes = Vec::new();
+        vk.serialize_compact(&mut bytes).unwrap();
+        let decoded = super::VerifierKey::<Bls12_381>::deserialize_compact(&bytes[..]).unwrap();
+        assert_eq!(decoded.vk.g, vk.vk.g);
+        assert_eq!(decoded.vk.gamma_g, vk.vk.gamma_g);
+        assert_eq!(decoded.vk.h, vk.vk.h);
+        assert_eq!(decoded.vk.beta_h, vk.vk.beta_h);
+        assert_eq!(
+            decoded.degree_bounds_and_shift_powers,
+            vk.degree_bounds_and_shift_powers
+        );
+        assert_eq!(decoded.max_degree, vk.max_degree);
+        assert_eq!(decoded.supported_degree, vk.supported_degree);
+        assert!(!decoded.supports_hiding_commitments);
+
+        // The naive encoding writes every degree and bound as a `u64`.
+        let g1_size = vk.vk.g.serialized_size();
+        let g2_size = vk.vk.h.serialized_size();
+        let naive_size =
+            2 * g1_size + 2 * g2_size + 8 + 8 + 1 + 1 + 8 + bounds.len() * (8 + g1_size);
+        // `max_degree` and `supported_degree` take two bytes, and the length one.
+        assert_eq!(naive_size - bytes.len(), 7 * bounds.len() + 6 + 6 + 7);
+
+        let (_, vk) = PC_Bls12_381::trim(&pp, max_degree, 0, None).unwrap();
+        let mut bytes = Vec::new();
+        vk.serialize_compact(&mut bytes).unwrap();
+        let decoded = super::VerifierKey::<Bls12_381>::deserialize_compact(&bytes[..]).unwrap();
+        assert!(decoded.degree_bounds_and_shift_powers.is_none());
+        assert!(
+            super::VerifierKey::<Bls12_381>::deserialize_compact(&bytes[..bytes.len() - 1])
+                .is_err()
+        );
+
+        let (_, vk) = PC_Bls12_381::trim(&pp, max_degree, 1, None).unwrap();
+        let mut bytes = Vec::new();
+        vk.serialize_compact(&mut bytes).unwrap();
+        let decoded = super::VerifierKey::<Bls12_381>::deserialize_compact(&bytes[..]).unwrap();
+        assert!(decoded.supports_hiding_commitments);
+    }
+
+    #[test]
+    fn batch_check_mixed_degree_bounds_test() {
+        use crate::{
+            Evaluations, LabeledCommitment, LabeledPolynomial, PolynomialCommitment, QuerySet,
+        };
+        use ark_ff::{test_rng, One};
+
+        let rng = &mut test_rng();
+        let max_degree = 32;
+        let pp = PC_Bls12_381::setup(max_degree, None, rng).unwrap();
+        let (ck, vk) = PC_Bls12_381::trim(&pp, max_degree, 2, Some(&[5, 20])).unwrap();
+
+        // (degree, degree bound, hiding bound)
+        let shapes = [
+            (5, Some(5), None),
+            (20, Some(20), Some(2)),
+            (31, None, None),
+            (3, None, Some(1)),
+            (4, Some(20), None),
+        ];
+        let polynomials = shapes
+            .iter()
+            .enumerate()
+            .map(|(i, &(d, bound, hiding))| {
+                let p = rand_poly::<Bls12_381>(d, None, rng);
+                LabeledPolynomial::new(format!("p{}", i), p, bound, hiding)
+            })
+            .collect::<Vec<_>>();
+        let (comms, rands) = PC_Bls12_381::commit(&ck, &polynomials, Some(rng)).unwrap();
+
+        let mut query_set = QuerySet::new();
+        let mut values = Evaluations::new();
+        for j in 0..2 {
+            let point = rand_point::<Bls12_381>(None, rng);
+            for p in &polynomials {
+                query_set.insert((p.label().clone(), (format!("{}", j), point)));
+                values.insert((p.label().clone(), point), p.evaluate(&point));
+            }
+        }
+
+        let challenge = <Bls12_381 as PairingEngine>::Fr::rand(rng);
+        let proof = PC_Bls12_381::batch_open(
+            &ck,
+            &polynomials,
+            &comms,
+            &query_set,
+            challenge,
+            &rands,
+            Some(rng),
+        )
+        .unwrap();
+        let check = |comms: &[LabeledCommitment<_>], values: &Evaluations<_, _>, proof: &Vec<_>| {
+            PC_Bls12_381::batch_check(
+                &vk,
+                comms,
+                &query_set,
+                values,
+                proof,
+                challenge,
+                &mut test_rng(),
+            )
+            .unwrap()
+        };
+        assert!(check(&comms, &values, &proof));
+
+        // Tampering with any single value is rejected.
+        for key in values.keys() {
+            let mut bad_values = values.clone();
+            *bad_values.get_mut(key).unwrap() += <Bls12_381 as PairingEngine>::Fr::one();
+            assert!(!check(&comms, &bad_values, &proof));
+        }
+
+        // So is tampering with any single commitment or shifted commitment.
+        let g = pp.powers_of_g[0];
+        for i in 0..comms.len() {
+            let mut bad_comms = comms.clone();
+            let mut c = comms[i].commitment().clone();
+            c.comm.0 = (c.comm.0 + g).into();
+            bad_comms[i] =
+                LabeledCommitment::new(comms[i].label().clone(), c, comms[i].degree_bound());
+            assert!(!check(&bad_comms, &values, &proof));
+
+            let mut c = comms[i].commitment().clone();
+            match c.shifted_comm.as_mut() {
+                Some(shifted_comm) => shifted_comm.0 = (shifted_comm.0 + g).into(),
+                None => c.shifted_comm = Some(c.comm),
+            }
+            bad_comms[i] =
+                LabeledCommitment::new(comms[i].label().clone(), c, comms[i].degree_bound());
+            assert!(!check(&bad_comms, &values, &proof));
+        }
+
+        // And with the proof for either point.
+        for i in 0..proof.len() {
+            let mut bad_proof = proof.clone();
+            bad_proof[i].w = (bad_proof[i].w + g).into();
+            assert!(!check(&comms, &values, &bad_proof));
+        }
+    }
+
+    #[test]
+    fn degree_at_least_test() {
+        use crate::{LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+        use blake2::Blake2s;
+
+        let rng = &mut test_rng();
+        let d = 10;
+        let pp = PC_Bls12_381::setup(32, None, rng).unwrap();
+        let (ck, vk) = PC_Bls12_381::trim(&pp, 32, 1, Some(&[d - 1])).unwrap();
+
+        for &(degree, hiding_bound, expected) in
+            &[(d, None, true), (20, Some(1), true), (d - 1, None, false)]
+        {
+            let p = LabeledPolynomial::new(
+                "p".to_string(),
+                rand_poly::<Bls12_381>(degree, None, rng),
+                None,
+                hiding_bound,
+            );
+            let (comms, rands) = PC_Bls12_381::commit(&ck, Some(&p), Some(rng)).unwrap();
+            let proof = PC_Bls12_381::prove_degree_at_least::<Blake2s>(
+                &ck,
+                &p,
+                &comms[0],
+                &rands[0],
+                d,
+                Some(rng),
+            )
+            .unwrap();
+            assert_eq!(
+                PC_Bls12_381::check_degree_at_least::<Blake2s>(&vk, &comms[0], d, &proof).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn is_zero_test() {
+        use super::Randomness;
+        use crate::{LabeledPolynomial, PCRandomness, PolynomialCommitment};
+        use ark_ff::{test_rng, Zero};
+        type Rand = Randomness<<Bls12_381 as PairingEngine>::Fr, UniPoly_381>;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        let (ck, _) = PC_Bls12_381::trim(&pp, 16, 1, Some(&[8])).unwrap();
+
+        for &(degree_bound, hiding_bound) in &[(None, None), (Some(8), None), (Some(8), Some(1))] {
+            let zero =
+                LabeledPolynomial::new("zero".to_string(), UniPoly_381::zero(), degree_bound, None);
+            let p = LabeledPolynomial::new(
+                "p".to_string(),
+                rand_poly::<Bls12_381>(8, None, rng),
+                degree_bound,
+                hiding_bound,
+            );
+            let (comms, rands) = PC_Bls12_381::commit(&ck, vec![&zero, &p], Some(rng)).unwrap();
+            assert!(comms[0].commitment().is_zero());
+            assert!(rands[0].is_zero());
+            assert!(!comms[1].commitment().is_zero());
+            assert_eq!(rands[1].is_zero(), hiding_bound.is_none());
+        }
+
+        assert!(!Rand::rand(1, true, None, rng).is_zero());
+        assert!(Rand::empty().is_zero());
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn randomness_zeroize_test() {
+        use crate::{LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+        use zeroize::Zeroize;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        let (ck, _) = PC_Bls12_381::trim(&pp, 16, 2, Some(&[8])).unwrap();
+        let p = LabeledPolynomial::new(
+            "p".to_string(),
+            rand_poly::<Bls12_381>(8, None, rng),
+            Some(8),
+            Some(2),
+        );
+        let (_, mut rands) = PC_Bls12_381::commit(&ck, Some(&p), Some(rng)).unwrap();
+        let rand = &mut rands[0];
+        assert!(rand.rand.is_hiding());
+        assert!(rand.shifted_rand.as_ref().unwrap().is_hiding());
+
+        rand.zeroize();
+        assert!(rand.rand.blinding_polynomial.coeffs.is_empty());
+        let shifted_rand = rand.shifted_rand.as_ref().unwrap();
+        assert!(shifted_rand.blinding_polynomial.coeffs.is_empty());
+        assert!(rand.is_zero());
+    }
+
+    #[test]
+    fn batch_open_individual_test() {
+        use crate::{LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        let (ck, vk) = PC_Bls12_381::trim(&pp, 16, 0, Some(&[8])).unwrap();
+
+        let polys: Vec<_> = (0..3)
+            .map(|i| {
+                LabeledPolynomial::new(
+                    format!("p{}", i),
+                    rand_poly::<Bls12_381>(8, None, rng),
+                    if i == 1 { Some(8) } else { None },
+                    None,
+                )
+            })
+            .collect();
+        let (comms, rands) = PC_Bls12_381::commit(&ck, &polys, Some(rng)).unwrap();
+        let (x, y) = (
+            rand_point::<Bls12_381>(None, rng),
+            rand_point::<Bls12_381>(None, rng),
+        );
+        let queries = vec![
+            ("p0".to_string(), x),
+            ("p1".to_string(), y),
+            ("p2".to_string(), x),
+            ("p1".to_string(), x),
+        ];
+        let challenge = rand_point::<Bls12_381>(None, rng);
+
+        let proofs = PC_Bls12_381::batch_open_individual(
+            &ck,
+            &polys,
+            &comms,
+            queries,
+            challenge,
+            &rands,
+            Some(rng),
+        )
+        .unwrap();
+        assert_eq!(proofs.len(), 2);
+
+        let mut points = vec![x, y];
+        points.sort();
+        for ((point, proof), expected_point) in proofs.iter().zip(&points) {
+            assert_eq!(point, expected_point);
+            let indices: &[usize] = if *point == x { &[0, 1, 2] } else { &[1] };
+            let query_polys: Vec<_> = indices.iter().map(|&i| &polys[i]).collect();
+            let query_comms: Vec<_> = indices.iter().map(|&i| &comms[i]).collect();
+            let query_rands: Vec<_> = indices.iter().map(|&i| &rands[i]).collect();
+            let expected = PC_Bls12_381::open(
+                &ck,
+                query_polys.clone(),
+                query_comms.clone(),
+                point,
+                challenge,
+                query_rands,
+                Some(rng),
+            )
+            .unwrap();
+            assert_eq!(*proof, expected);
+
+            let values: Vec<_> = query_polys.iter().map(|p| p.evaluate(point)).collect();
+            assert!(PC_Bls12_381::check(
+                &vk,
+                query_comms,
+                point,
+                values,
+                proof,
+                challenge,
+                Some(rng)
+            )
+            .unwrap());
+        }
+    }
+
+    #[test]
+    fn checked_degree_bound_test() {
+        use crate::{Error, LabeledCommitment, LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        let (ck, _) = PC_Bls12_381::trim(&pp, 16, 0, Some(&[8])).unwrap();
+        let polys = vec![
+            LabeledPolynomial::new(
+                "p".to_string(),
+                rand_poly::<Bls12_381>(8, None, rng),
+                None,
+                None,
+            ),
+            LabeledPolynomial::new(
+                "q".to_string(),
+                rand_poly::<Bls12_381>(8, None, rng),
+                Some(8),
+                None,
+            ),
+        ];
+        let (comms, _) = PC_Bls12_381::commit(&ck, &polys, Some(rng)).unwrap();
+        assert_eq!(comms[0].checked_degree_bound().unwrap(), None);
+        assert_eq!(comms[1].checked_degree_bound().unwrap(), Some(8));
+
+        // A degree bound, but no shifted commitment.
+        let mut unshifted = *comms[1].commitment();
+        unshifted.shifted_comm = None;
+        let malformed = LabeledCommitment::new("q".to_string(), unshifted, Some(8));
+        assert!(matches!(
+            malformed.checked_degree_bound(),
+            Err(Error::DegreeBoundMismatch { label }) if label == "q"
+        ));
+
+        // A shifted commitment, but no degree bound.
+        let malformed = LabeledCommitment::new("q".to_string(), *comms[1].commitment(), None);
+        assert!(matches!(
+            malformed.checked_degree_bound(),
+            Err(Error::DegreeBoundMismatch { label }) if label == "q"
+        ));
+    }
+
+    #[test]
+    fn powers_up_to_test() {
+        use crate::kzg10::KZG10;
+        use crate::PolynomialCommitment;
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(32, None, rng).unwrap();
+        let (ck, _) = PC_Bls12_381::trim(&pp, 32, 1, None).unwrap();
+        let d = 5;
+        let p = rand_poly::<Bls12_381>(d, None, rng);
+
+        let powers = ck.powers_up_to(d);
+        assert_eq!(powers.powers_of_g.len(), d + 1);
+        let (comm, _) = KZG10::commit(&powers, &p, None, None).unwrap();
+        let (full_comm, _) = KZG10::commit(&ck.powers(), &p, None, None).unwrap();
+        assert_eq!(comm, full_comm);
+    }
+
+    #[test]
+    #[should_panic(expected = "degree 9 is not supported by a key with 9 powers")]
+    fn powers_up_to_unsupported_degree_test() {
+        use crate::PolynomialCommitment;
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(8, None, rng).unwrap();
+        let (ck, _) = PC_Bls12_381::trim(&pp, 8, 0, None).unwrap();
+        ck.powers_up_to(ck.powers.len());
+    }
+
+    #[test]
+    fn hex_codec_test() {
+        use super::Commitment;
+        use crate::{Error, HexCodec, LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        let (ck, _) = PC_Bls12_381::trim(&pp, 16, 1, Some(&[8])).unwrap();
+
+        for &degree_bound in &[None, Some(8)] {
+            let p = LabeledPolynomial::new(
+                "p".to_string(),
+                rand_poly::<Bls12_381>(8, None, rng),
+                degree_bound,
+                Some(1),
+            );
+            let (comms, _) = PC_Bls12_381::commit(&ck, Some(&p), Some(rng)).unwrap();
+            let comm = comms[0].commitment();
+            let hex = comm.to_hex();
+            assert_eq!(hex.len(), 2 * 48 * (1 + degree_bound.is_some() as usize));
+            assert_eq!(&Commitment::from_hex(&hex).unwrap(), comm);
+            assert!(matches!(
+                Commitment::<Bls12_381>::from_hex(&hex[2..]),
+                Err(Error::MalformedHex(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn verify_degree_bound_test() {
+        use crate::{Error, LabeledPolynomial, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        let (ck, vk) = PC_Bls12_381::trim(&pp, 16, 1, Some(&[8, 9])).unwrap();
+        let point = rand_point::<Bls12_381>(None, rng);
+
+        for &hiding_bound in &[None, Some(1)] {
+            let p = LabeledPolynomial::new(
+                "p".to_string(),
+                rand_poly::<Bls12_381>(8, None, rng),
+                Some(8),
+                hiding_bound,
+            );
+            let value = p.evaluate(&point);
+            let (comms, rands) = PC_Bls12_381::commit(&ck, Some(&p), Some(rng)).unwrap();
+            let comm = comms[0].commitment();
+            let proof = PC_Bls12_381::open_degree_bound(&ck, &p, point, &rands[0]).unwrap();
+            assert!(PC_Bls12_381::verify_degree_bound(&vk, comm, 8, point, value, &proof).unwrap());
+            assert!(!PC_Bls12_381::verify_degree_bound(
+                &vk,
+                comm,
+                8,
+                point,
+                value + &rand_point::<Bls12_381>(None, rng),
+                &proof
+            )
+            .unwrap());
+            assert!(matches!(
+                PC_Bls12_381::verify_degree_bound(&vk, comm, 7, point, value, &proof),
+                Err(Error::UnsupportedDegreeBound(7))
+            ));
+        }
+
+        // A polynomial of degree 9 committed under a bound of 9 does not pass as
+        // respecting a bound of 8.
+        let q = LabeledPolynomial::new(
+            "q".to_string(),
+            rand_poly::<Bls12_381>(9, None, rng),
+            Some(9),
+            None,
+        );
+        let value = q.evaluate(&point);
+        let (comms, rands) = PC_Bls12_381::commit(&ck, Some(&q), None).unwrap();
+        let comm = comms[0].commitment();
+        let proof = PC_Bls12_381::open_degree_bound(&ck, &q, point, &rands[0]).unwrap();
+        assert!(PC_Bls12_381::verify_degree_bound(&vk, comm, 9, point, value, &proof).unwrap());
+        assert!(!PC_Bls12_381::verify_degree_bound(&vk, comm, 8, point, value, &proof).unwrap());
+    }
+
+    #[test]
+    fn check_prepared_test() {
+        use super::PreparedVerifierKey;
+        use crate::{Error, LabeledPolynomial, PCPreparedVerifierKey, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        let (ck, vk) = PC_Bls12_381::trim(&pp, 16, 1, Some(&[8])).unwrap();
+        let prepared_vk = PreparedVerifierKey::prepare(&vk);
+
+        let polynomials = vec![
+            LabeledPolynomial::new(
+                "a".to_string(),
+                rand_poly::<Bls12_381>(16, None, rng),
+                None,
+                None,
+            ),
+            LabeledPolynomial::new(
+                "b".to_string(),
+                rand_poly::<Bls12_381>(8, None, rng),
+                Some(8),
+                None,
+            ),
+        ];
+        let (comms, rands) = PC_Bls12_381::commit(&ck, &polynomials, None).unwrap();
+        for _ in 0..4 {
+            let point = rand_point::<Bls12_381>(None, rng);
+            let opening_challenge = rand_point::<Bls12_381>(None, rng);
+            let proof = PC_Bls12_381::open(
+                &ck,
+                &polynomials,
+                &comms,
+                &point,
+                opening_challenge,
+                &rands,
+                None,
+            )
+            .unwrap();
+            let values = polynomials
+                .iter()
+                .map(|p| p.evaluate(&point))
+                .collect::<Vec<_>>();
+            let mut wrong_values = values.clone();
+            wrong_values[1] += &rand_point::<Bls12_381>(None, rng);
+            for values in vec![values, wrong_values] {
+                let expected = PC_Bls12_381::check(
+                    &vk,
+                    &comms,
+                    &point,
+                    values.clone(),
+                    &proof,
+                    opening_challenge,
+                    None,
+                )
+                .unwrap();
+                let result = PC_Bls12_381::check_prepared(
+                    &prepared_vk,
+                    &comms,
+                    point,
+                    values,
+                    &proof,
+                    opening_challenge,
+                )
+                .unwrap();
+                assert_eq!(result, expected);
+            }
+        }
+
+        let hiding = LabeledPolynomial::new(
+            "c".to_string(),
+            rand_poly::<Bls12_381>(8, None, rng),
+            None,
+            Some(1),
+        );
+        let (comms, rands) = PC_Bls12_381::commit(&ck, Some(&hiding), Some(rng)).unwrap();
+        let point = rand_point::<Bls12_381>(None, rng);
+        let opening_challenge = rand_point::<Bls12_381>(None, rng);
+        let proof = PC_Bls12_381::open(
+            &ck,
+            Some(&hiding),
+            &comms,
+            &point,
+            opening_challenge,
+            &rands,
+            Some(rng),
+        )
+        .unwrap();
+        assert!(matches!(
+            PC_Bls12_381::check_prepared(
+                &prepared_vk,
+                &comms,
+                point,
+                Some(hiding.evaluate(&point)),
+                &proof,
+                opening_challenge,
+            ),
+            Err(Error::HidingProofUnsupported)
+        ));
+    }
+
+    #[test]
+    fn trim_hiding_bound_test() {
+        use crate::{Error, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        for supported_hiding_bound in 0..4 {
+            let (ck, _) = PC_Bls12_381::trim(&pp, 16, supported_hiding_bound, None).unwrap();
+            assert_eq!(ck.powers_of_gamma_g.len(), supported_hiding_bound + 2);
+        }
+        // `pp` has the powers of `\gamma G` up to degree 17.
+        assert!(PC_Bls12_381::trim(&pp, 16, 16, None).is_ok());
+        assert!(matches!(
+            PC_Bls12_381::trim(&pp, 16, 17, None),
+            Err(Error::HidingBoundToolarge {
+                hiding_poly_degree: 18,
+                num_powers: 18
+            })
+        ));
+    }
+
+    #[test]
+    fn trim_degree_too_large_test() {
+        use crate::{Error, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        assert!(matches!(
+            PC_Bls12_381::trim(&pp, 17, 1, None),
+            Err(Error::TrimmingDegreeTooLarge {
+                supported: 17,
+                max: 16
+            })
+        ));
+        assert!(matches!(
+            PC_Bls12_381::trim(&pp, 16, 1, Some(&[8, 20])),
+            Err(Error::TrimmingDegreeTooLarge {
+                supported: 20,
+                max: 16
+            })
+        ));
+        assert!(PC_Bls12_381::trim(&pp, 16, 1, Some(&[8, 16])).is_ok());
+    }
+
+    #[test]
+    fn trim_allocation_cap_test() {
+        use crate::{Error, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let mut pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        let g1_size = ark_std::mem::size_of::<<Bls12_381 as PairingEngine>::G1Affine>();
+        pp.allocation_cap = 10 * g1_size;
+        assert!(matches!(
+            PC_Bls12_381::trim(&pp, 16, 1, None),
+            Err(Error::AllocationTooLarge { requested_bytes }) if requested_bytes > 10 * g1_size
+        ));
+        assert!(matches!(
+            PC_Bls12_381::trim(&pp, 4, 1, Some(&[4])),
+            Err(Error::AllocationTooLarge { .. })
+        ));
+        assert!(PC_Bls12_381::trim(&pp, 4, 1, None).is_ok());
+    }
+
+    #[test]
+    fn commit_with_bounds_test() {
+        use crate::{LabeledPolynomial, PCCommitment, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        let (ck, _) = PC_Bls12_381::trim(&pp, 16, 1, Some(&[4, 8])).unwrap();
+
+        let degree_bounds = [None, Some(8), None, Some(4)];
+        let polynomials = degree_bounds
+            .iter()
+            .enumerate()
+            .map(|(i, &degree_bound)| {
+                LabeledPolynomial::new(
+                    format!("p{}", i),
+                    rand_poly::<Bls12_381>(4, None, rng),
+                    degree_bound,
+                    Some(1),
+                )
+            })
+            .collect::<Vec<_>>();
+        let (comms, rands) =
+            PC_Bls12_381::commit_with_bounds(&ck, &polynomials, Some(rng)).unwrap();
+        assert_eq!(rands.len(), polynomials.len());
+        for ((comm, degree_bound), (p, &expected)) in
+            comms.iter().zip(polynomials.iter().zip(&degree_bounds))
+        {
+            assert_eq!(comm.label(), p.label());
+            assert_eq!(*degree_bound, expected);
+            assert_eq!(comm.commitment().has_degree_bound(), expected.is_some());
+        }
+    }
+
+    #[test]
+    fn open_two_combinations_of_three_polynomials_test() {
+        use crate::{
+            Evaluations, LabeledPolynomial, LinearCombination, PolynomialCommitment, QuerySet,
+        };
+        use ark_ff::{test_rng, One};
+
+        type Fr = <Bls12_381 as PairingEngine>::Fr;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        let (ck, vk) = PC_Bls12_381::trim(&pp, 16, 1, None).unwrap();
+
+        let polynomials = ["a", "b", "c"]
+            .iter()
+            .map(|label| {
+                LabeledPolynomial::new(
+                    label.to_string(),
+                    rand_poly::<Bls12_381>(16, None, rng),
+                    None,
+                    Some(1),
+                )
+            })
+            .collect::<Vec<_>>();
+        let (comms, rands) = PC_Bls12_381::commit(&ck, &polynomials, Some(rng)).unwrap();
+
+        let two = Fr::one() + &Fr::one();
+        let lcs = vec![
+            LinearCombination::builder("a + 2b")
+                .add(Fr::one(), "a")
+                .add(two, "b")
+                .build(),
+            LinearCombination::builder("b - c + 2")
+                .add(Fr::one(), "b")
+                .add(-Fr::one(), "c")
+                .add_constant(two)
+                .build(),
+        ];
+
+        let z = rand_point::<Bls12_381>(None, rng);
+        let w = rand_point::<Bls12_381>(None, rng);
+        let mut query_set = QuerySet::new();
+        query_set.insert(("a + 2b".to_string(), ("z".to_string(), z)));
+        query_set.insert(("b - c + 2".to_string(), ("z".to_string(), z)));
+        query_set.insert(("b - c + 2".to_string(), ("w".to_string(), w)));
+
+        let evaluate = |label: &str, point: Fr| {
+            let a = polynomials[0].evaluate(&point);
+            let b = polynomials[1].evaluate(&point);
+            let c = polynomials[2].evaluate(&point);
+            if label == "a + 2b" {
+                a + &(two * &b)
+            } else {
+                b - &c + &two
+            }
+        };
+        let mut evaluations = Evaluations::new();
+        for (label, (_, point)) in &query_set {
+            evaluations.insert((label.clone(), *point), evaluate(label, *point));
+        }
+
+        let opening_challenge = rand_point::<Bls12_381>(None, rng);
+        let proof = PC_Bls12_381::open_combinations(
+            &ck,
+            &lcs,
+            &polynomials,
+            &comms,
+            &query_set,
+            opening_challenge,
+            &rands,
+            Some(rng),
+        )
+        .unwrap();
+        assert!(PC_Bls12_381::check_combinations(
+            &vk,
+            &lcs,
+            &comms,
+            &query_set,
+            &evaluations,
+            &proof,
+            opening_challenge,
+            rng,
+        )
+        .unwrap());
+
+        let mut wrong_evaluations = evaluations.clone();
+        *wrong_evaluations
+            .get_mut(&("b - c + 2".to_string(), w))
+            .unwrap() += &Fr::one();
+        assert!(!PC_Bls12_381::check_combinations(
+            &vk,
+            &lcs,
+            &comms,
+            &query_set,
+            &wrong_evaluations,
+            &proof,
+            opening_challenge,
+            rng,
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn committer_key_serialization_test() {
+        use super::CommitterKey;
+        use crate::PolynomialCommitment;
+        use ark_ff::test_rng;
+        use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+        use std::io::{self, Write};
+
+        /// Accepts `remaining` bytes, then fails every write.
+        struct FailingWriter {
+            remaining: usize,
+        }
+
+        impl Write for FailingWriter {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                if self.remaining == 0 {
+                    return Err(io::Error::new(io::ErrorKind::Other, "writer is full"));
+                }
+                let n = buf.len().min(self.remaining);
+                self.remaining -= n;
+                Ok(n)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        let (ck, _) = PC_Bls12_381::trim(&pp, 16, 1, Some(&[4, 8])).unwrap();
+
+        let mut bytes = Vec::new();
+        ck.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), ck.serialized_size());
+        let deserialized = CommitterKey::<Bls12_381>::deserialize(&bytes[..]).unwrap();
+        assert_eq!(deserialized.powers, ck.powers);
+        assert_eq!(deserialized.shifted_powers, ck.shifted_powers);
+        assert_eq!(deserialized.powers_of_gamma_g, ck.powers_of_gamma_g);
+        assert_eq!(
+            deserialized.enforced_degree_bounds,
+            ck.enforced_degree_bounds
+        );
+        assert_eq!(deserialized.max_degree, ck.max_degree);
+
+        // The error is returned however far into the key the writer fails.
+        for &remaining in &[0, bytes.len() / 4, bytes.len() / 2, bytes.len() - 1] {
+            assert!(matches!(
+                ck.serialize(FailingWriter { remaining }),
+                Err(SerializationError::IoError(_))
+            ));
+        }
+    }
 }
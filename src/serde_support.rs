@@ -0,0 +1,111 @@
+//! Implements `serde::Serialize` and `serde::Deserialize` by delegating to the
+//! canonical (compressed) serialization, encoded as a lowercase hex string.
+//! `marlin_pc::Commitment` derives its implementations from those of
+//! `kzg10::Commitment`.
+
+use crate::{hex, kzg10, String};
+use ark_ec::PairingEngine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+fn serialize_canonical<T: CanonicalSerialize, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::canonical_to_hex(value))
+}
+
+fn deserialize_canonical<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let hex = <String as Deserialize>::deserialize(deserializer)?;
+    hex::canonical_from_hex(&hex).map_err(<D::Error as de::Error>::custom)
+}
+
+impl<E: PairingEngine> Serialize for kzg10::Commitment<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_canonical(self, serializer)
+    }
+}
+
+impl<'de, E: PairingEngine> Deserialize<'de> for kzg10::Commitment<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_canonical(deserializer)
+    }
+}
+
+impl<E: PairingEngine> Serialize for kzg10::Proof<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_canonical(self, serializer)
+    }
+}
+
+impl<'de, E: PairingEngine> Deserialize<'de> for kzg10::Proof<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_canonical(deserializer)
+    }
+}
+
+impl<E: PairingEngine> Serialize for kzg10::VerifierKey<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_canonical(self, serializer)
+    }
+}
+
+impl<'de, E: PairingEngine> Deserialize<'de> for kzg10::VerifierKey<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_canonical(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kzg10::{self, KZG10};
+    use crate::marlin_pc::{self, MarlinKZG10};
+    use crate::{LabeledPolynomial, PolynomialCommitment};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::{test_rng, UniformRand};
+    use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+
+    type UniPoly = DensePolynomial<Fr>;
+    type KZG = KZG10<Bls12_381, UniPoly>;
+    type PC = MarlinKZG10<Bls12_381, UniPoly>;
+
+    #[test]
+    fn serde_json_round_trip_test() {
+        let rng = &mut test_rng();
+        let pp = PC::setup(16, None, rng).unwrap();
+        let (ck, vk) = PC::trim(&pp, 16, 1, Some(&[8])).unwrap();
+
+        for (degree_bound, hiding_bound) in vec![(None, None), (Some(8), Some(1))] {
+            let p = LabeledPolynomial::new(
+                "p".to_string(),
+                UniPoly::rand(8, rng),
+                degree_bound,
+                hiding_bound,
+            );
+            let (comms, rands) = PC::commit(&ck, Some(&p), Some(rng)).unwrap();
+            let comm = comms[0].commitment();
+            let json = serde_json::to_string(comm).unwrap();
+            let decoded_comm: marlin_pc::Commitment<Bls12_381> =
+                serde_json::from_str(&json).unwrap();
+            assert_eq!(decoded_comm, *comm);
+
+            let point = Fr::rand(rng);
+            let value = p.evaluate(&point);
+            let proof = KZG::open(&ck.powers(), p.polynomial(), point, &rands[0].rand).unwrap();
+            let json = serde_json::to_string(&proof).unwrap();
+            let decoded_proof: kzg10::Proof<Bls12_381> = serde_json::from_str(&json).unwrap();
+            assert_eq!(decoded_proof, proof);
+            assert!(KZG::check(&vk.vk, &decoded_comm.comm, point, value, &decoded_proof).unwrap());
+        }
+
+        let json = serde_json::to_string(&vk.vk).unwrap();
+        let decoded_vk: kzg10::VerifierKey<Bls12_381> = serde_json::from_str(&json).unwrap();
+        assert_eq!((decoded_vk.g, decoded_vk.gamma_g), (vk.vk.g, vk.vk.gamma_g));
+        assert_eq!((decoded_vk.h, decoded_vk.beta_h), (vk.vk.h, vk.vk.beta_h));
+
+        assert!(serde_json::from_str::<kzg10::Commitment<Bls12_381>>("\"zz\"").is_err());
+        assert!(serde_json::from_str::<kzg10::Commitment<Bls12_381>>("\"00\"").is_err());
+    }
+}
@@ -69,6 +69,7 @@ impl<E: PairingEngine> CommitterKey<E> {
         kzg10::Powers {
             powers_of_g: self.powers_of_g.as_slice().into(),
             powers_of_gamma_g: self.powers_of_gamma_g.as_slice().into(),
+            parallel_threshold: kzg10::DEFAULT_PARALLEL_THRESHOLD,
         }
     }
 
@@ -99,6 +100,7 @@ impl<E: PairingEngine> CommitterKey<E> {
                 let ck = kzg10::Powers {
                     powers_of_g: shifted_powers_of_g[powers_range.clone()].into(),
                     powers_of_gamma_g: shifted_powers_of_gamma_g[&bound].clone().into(),
+                    parallel_threshold: kzg10::DEFAULT_PARALLEL_THRESHOLD,
                 };
 
                 Some(ck)
@@ -163,9 +163,13 @@ where
         let trim_time = start_timer!(|| "Trimming public parameters");
         let prepared_neg_powers_of_h = &pp.prepared_neg_powers_of_h;
         let max_degree = pp.max_degree();
-        if supported_degree > max_degree {
-            return Err(Error::TrimmingDegreeTooLarge);
-        }
+        kzg10::check_trim_degrees(supported_degree, max_degree, enforced_degree_bounds)?;
+        kzg10::check_trim_allocation(
+            pp,
+            supported_degree,
+            supported_hiding_bound,
+            enforced_degree_bounds.map_or(false, |bounds| !bounds.is_empty()),
+        )?;
 
         let enforced_degree_bounds = enforced_degree_bounds.map(|bounds| {
             let mut v = bounds.to_vec();
@@ -239,9 +243,7 @@ where
         };
 
         let powers_of_g = pp.powers_of_g[..=supported_degree].to_vec();
-        let powers_of_gamma_g = (0..=(supported_hiding_bound + 1))
-            .map(|i| pp.powers_of_gamma_g[&i])
-            .collect();
+        let powers_of_gamma_g = kzg10::trim_powers_of_gamma_g(pp, supported_hiding_bound)?;
 
         let ck = CommitterKey {
             powers_of_g,
@@ -894,6 +896,13 @@ mod tests {
         println!("Finished bls12-381");
     }
 
+    #[test]
+    fn query_set_order_test() {
+        use crate::tests::*;
+        query_set_order_test::<_, _, PC_Bls12_381>(rand_poly::<Bls12_381>, rand_point::<Bls12_381>)
+            .expect("test failed for bls12-381");
+    }
+
     #[test]
     #[should_panic]
     fn bad_degree_bound_test() {
@@ -911,4 +920,27 @@ mod tests {
         .expect("test failed for bls12-381");
         println!("Finished bls12-381");
     }
+
+    #[test]
+    fn trim_degree_too_large_test() {
+        use crate::{Error, PolynomialCommitment};
+        use ark_ff::test_rng;
+
+        let rng = &mut test_rng();
+        let pp = PC_Bls12_381::setup(16, None, rng).unwrap();
+        assert!(matches!(
+            PC_Bls12_381::trim(&pp, 17, 1, None),
+            Err(Error::TrimmingDegreeTooLarge {
+                supported: 17,
+                max: 16
+            })
+        ));
+        assert!(matches!(
+            PC_Bls12_381::trim(&pp, 16, 1, Some(&[20])),
+            Err(Error::TrimmingDegreeTooLarge {
+                supported: 20,
+                max: 16
+            })
+        ));
+    }
 }
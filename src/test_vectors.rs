@@ -0,0 +1,227 @@
+use crate::kzg10::{
+    Commitment, Powers, Proof, Randomness, VerifierKey, DEFAULT_PARALLEL_THRESHOLD, KZG10,
+};
+use crate::{Error, PCRandomness, Polynomial, String, UVPolynomial, Vec};
+use ark_ec::PairingEngine;
+use ark_ff::{test_rng, UniformRand};
+use ark_poly::univariate::DensePolynomial;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::{
+    borrow::Cow,
+    io::{Read, Write},
+};
+
+/// The maximum degree supported by the SRS of the canonical test vectors.
+pub const MAX_DEGREE: usize = 32;
+
+/// The degrees of the polynomials in the canonical test vectors.
+pub const DEGREES: [usize; 5] = [1, 2, 7, 16, MAX_DEGREE];
+
+/// A single non-hiding [[KZG10]][kzg] evaluation: a polynomial, its commitment,
+/// and a proof that it evaluates to `value` at `point`.
+///
+/// [kzg]: http://cacr.uwaterloo.ca/techreports/2010/cacr2010-10.pdf
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct TestVector<E: PairingEngine> {
+    /// The coefficients of the polynomial, from lowest to highest degree.
+    pub coeffs: Vec<E::Fr>,
+    /// The commitment to the polynomial.
+    pub commitment: Commitment<E>,
+    /// The point at which the polynomial is evaluated.
+    pub point: E::Fr,
+    /// The evaluation of the polynomial at `point`.
+    pub value: E::Fr,
+    /// The proof that the polynomial evaluates to `value` at `point`.
+    pub proof: Proof<E>,
+}
+
+/// A set of test vectors, together with the part of the SRS needed to
+/// recompute the commitments and check the proofs.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct TestVectors<E: PairingEngine> {
+    /// Group elements of the form `{ \beta^i G }`, where `i` ranges from 0 to `MAX_DEGREE`.
+    pub powers_of_g: Vec<E::G1Affine>,
+    /// The generator of G1 that is used for making a commitment hiding.
+    pub gamma_g: E::G1Affine,
+    /// The generator of G2.
+    pub h: E::G2Affine,
+    /// \beta times the above generator of G2.
+    pub beta_h: E::G2Affine,
+    /// The test vectors.
+    pub vectors: Vec<TestVector<E>>,
+}
+
+impl<E: PairingEngine> TestVectors<E> {
+    /// Generates the canonical test vectors for `E`.
+    ///
+    /// The SRS, the polynomials (one of each degree in `DEGREES`) and the
+    /// evaluation points are all sampled from `test_rng`, so the output is the
+    /// same on every run.
+    pub fn generate() -> Result<Self, Error> {
+        let rng = &mut test_rng();
+        let pp = KZG10::<E, DensePolynomial<E::Fr>>::setup(MAX_DEGREE, false, rng)?;
+        let powers = Powers::<E> {
+            powers_of_g: Cow::Borrowed(&pp.powers_of_g),
+            powers_of_gamma_g: Cow::Owned(pp.powers_of_gamma_g.values().cloned().collect()),
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+        };
+
+        let mut vectors = Vec::new();
+        for &degree in DEGREES.iter() {
+            let p = DensePolynomial::rand(degree, rng);
+            let point = E::Fr::rand(rng);
+            let value = p.evaluate(&point);
+            let (commitment, _) = KZG10::commit(&powers, &p, None, None)?;
+            let proof = KZG10::open(&powers, &p, point, &Randomness::empty())?;
+            vectors.push(TestVector {
+                coeffs: p.coeffs,
+                commitment,
+                point,
+                value,
+                proof,
+            });
+        }
+
+        Ok(Self {
+            powers_of_g: pp.powers_of_g,
+            gamma_g: pp.powers_of_gamma_g[&0],
+            h: pp.h,
+            beta_h: pp.beta_h,
+            vectors,
+        })
+    }
+
+    /// Checks every vector in `self`: the commitment must match the one
+    /// recomputed from `powers_of_g`, `value` must be the evaluation of the
+    /// polynomial at `point`, and the proof must verify. Fails with
+    /// `Error::EmptyPowers` if `powers_of_g` is empty.
+    pub fn verify(&self) -> Result<bool, Error> {
+        if self.powers_of_g.is_empty() {
+            return Err(Error::EmptyPowers);
+        }
+        let powers = Powers::<E> {
+            powers_of_g: Cow::Borrowed(&self.powers_of_g),
+            powers_of_gamma_g: Cow::Owned(Vec::new()),
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+        };
+        let vk = VerifierKey {
+            g: self.powers_of_g[0],
+            gamma_g: self.gamma_g,
+            h: self.h,
+            beta_h: self.beta_h,
+            prepared_h: self.h.into(),
+            prepared_beta_h: self.beta_h.into(),
+        };
+
+        for vector in &self.vectors {
+            let p = DensePolynomial::from_coefficients_slice(&vector.coeffs);
+            let (commitment, _) = KZG10::commit(&powers, &p, None, None)?;
+            if commitment != vector.commitment
+                || p.evaluate(&vector.point) != vector.value
+                || !KZG10::<E, DensePolynomial<E::Fr>>::check(
+                    &vk,
+                    &vector.commitment,
+                    vector.point,
+                    vector.value,
+                    &vector.proof,
+                )?
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Serializes `self` and encodes the result as a lowercase hex string.
+    pub fn to_hex(&self) -> String {
+        crate::hex::canonical_to_hex(self)
+    }
+
+    /// Decodes test vectors from a hex string produced by `to_hex`. Fails with
+    /// `Error::MalformedHex` if `hex` does not encode valid test vectors.
+    pub fn from_hex(hex: &str) -> Result<Self, Error> {
+        crate::hex::canonical_from_hex(hex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+
+    /// Test vectors over BLS12-381 for an SRS of degree 2 with trapdoor
+    /// `\beta = 42`, generated independently of this crate. The polynomials
+    /// are `3 + 5x`, opened at 7, and `1 + 2x + 3x^2`, opened at 4.
+    const KNOWN_ANSWER_HEX: &str = concat!(
+        "0300000000000000bbc622db0af03afbef1a7af93fe8556c58ac1b173f3a4ea105b974974f8c68c3",
+        "0faca94f8c63952694d79731a7d3f117489c693be22cd6aeb8097ad3cc86b1381eae300701cbf491",
+        "edfaaade983ba4bcc9ca89347523d33f439817797bb5e30cf0f0d14abad6462ac1e5a50059164401",
+        "713e4d392d54e4617e5fd4ae94a478ebc261b22232a466f1f136cbaf663d3e95551a9771d5b2127b",
+        "6894f7701df63ede2144bdd37e4d8d03f4fb28685df2e4a06d4215cebc77319e64210acceb75fd00",
+        "b8bd21c1c85680d4efbb05a82603ac0b77d1e37a640b51b4023b40fad47ae4c65110c52d27050826",
+        "910a8ff0b2a24a027e2b045d057dace5575d941312f14c3349507fdcbb61dab51ab62099d0d06b59",
+        "654f2788a0d3ac7d609f7152602be0134444b83008f34981de2e78fcb1b2a3e5eeb5059fbc4d721e",
+        "8943b8923d5d97071198735f7e2f59c9c2af3fb46d2d1b19f47f0f31ca9a96c3ac99b01c0523b3f2",
+        "f335620b42ff2e59a067599c60bfca4cca1b3980a1272e71f3bb38fc3da67f8c0200000000000000",
+        "02000000000000000300000000000000000000000000000000000000000000000000000000000000",
+        "0500000000000000000000000000000000000000000000000000000000000000881ae73a03f0f1ac",
+        "c9d68fb777a9478b9f338c55acd34931e8e53d0afb720cccb6f2de01c643d20c28ac633d70e4d104",
+        "07000000000000000000000000000000000000000000000000000000000000002600000000000000",
+        "000000000000000000000000000000000000000000000000dc13fb180faf75a5dc009ae5468c1aa9",
+        "c75acc5f6b63d836e5657970ff98dc3cda2286a933aa594101fe72b91f79e7900003000000000000",
+        "00010000000000000000000000000000000000000000000000000000000000000002000000000000",
+        "00000000000000000000000000000000000000000000000000030000000000000000000000000000",
+        "0000000000000000000000000000000000b31cb96f14a031836b7684081f37906ac5f88cf773415b",
+        "360a3aa2a45281c8dad303e8f8c5973f51abfbcfdc45926483040000000000000000000000000000",
+        "00000000000000000000000000000000003900000000000000000000000000000000000000000000",
+        "0000000000000000006cd7252f60371c7f14575b472956571426e73d186c7df11e91ca0cb3369cce",
+        "98a7f15bc170dea0c9159d16ec69d5340e00",
+    );
+
+    #[test]
+    fn test_vectors_are_stable_and_verify() {
+        let vectors = TestVectors::<Bls12_381>::generate().unwrap();
+        assert_eq!(vectors, TestVectors::<Bls12_381>::generate().unwrap());
+        assert!(vectors.verify().unwrap());
+
+        let decoded = TestVectors::<Bls12_381>::from_hex(&vectors.to_hex()).unwrap();
+        assert_eq!(decoded, vectors);
+        assert!(decoded.verify().unwrap());
+
+        let mut tampered = vectors.clone();
+        tampered.vectors[0].value += &<Bls12_381 as PairingEngine>::Fr::from(1u64);
+        assert!(!tampered.verify().unwrap());
+    }
+
+    #[test]
+    fn known_answer_test() {
+        type Fr = <Bls12_381 as PairingEngine>::Fr;
+
+        let vectors = TestVectors::<Bls12_381>::from_hex(KNOWN_ANSWER_HEX).unwrap();
+        assert_eq!(vectors.powers_of_g.len(), 3);
+        assert_eq!(vectors.vectors.len(), 2);
+        assert_eq!(vectors.vectors[0].value, Fr::from(38u64));
+        assert_eq!(vectors.vectors[1].value, Fr::from(57u64));
+        assert!(vectors.verify().unwrap());
+        assert_eq!(vectors.to_hex(), KNOWN_ANSWER_HEX);
+    }
+
+    #[test]
+    fn verify_rejects_empty_powers_test() {
+        let mut vectors = TestVectors::<Bls12_381>::generate().unwrap();
+        vectors.powers_of_g.clear();
+        assert!(matches!(vectors.verify(), Err(Error::EmptyPowers)));
+    }
+}